@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn make_bytes(len: usize) -> Vec<u8> {
+    let mut x = 0x2545f4914f6cdd1du64;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as u8
+        })
+        .collect()
+}
+
+fn encode_slice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_slice");
+    for len in [256, 1232, 8192] {
+        let bytes = make_bytes(len);
+        group.bench_with_input(BenchmarkId::new("encode_bs58", len), &bytes, |b, bytes| {
+            b.iter(|| bs58::encode(black_box(bytes)).into_string())
+        });
+        group.bench_with_input(BenchmarkId::new("encode_five8", len), &bytes, |b, bytes| {
+            b.iter(|| five8::encode_to_string(black_box(bytes)))
+        });
+    }
+    group.finish();
+}
+
+fn decode_slice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_slice");
+    for len in [256, 1232, 8192] {
+        let string = bs58::encode(make_bytes(len)).into_string();
+        group.bench_with_input(BenchmarkId::new("decode_bs58", len), &string, |b, string| {
+            b.iter(|| bs58::decode(black_box(string)).into_vec().unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("decode_five8", len), &string, |b, string| {
+            b.iter(|| five8::decode_to_vec(black_box(string)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode_slice, decode_slice);
+criterion_main!(benches);
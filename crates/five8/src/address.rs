@@ -0,0 +1,302 @@
+//! Legacy (P2PKH and P2SH) addresses: Base58Check of a one- or two-byte
+//! version and a 20-byte hash160, for the networks in [`AddressNetwork`].
+//!
+//! The data is 25 bytes, or 26 with a two-byte version, and is converted
+//! by [`encode`] and [`decode`] specialized for those lengths, with the
+//! tables computed at compile time and AVX2 when it's available.
+//! [`decode_address`] picks the length from the encoding, so each address
+//! is decoded once.
+
+use five8_core::base58_encoded_max_len;
+
+use crate::{
+    check::{verify_check, CHECKSUM_LEN},
+    decode, encode_check, CheckDecodeError,
+};
+
+const HASH_LEN: usize = 20;
+const SHORT_LEN: usize = 1 + HASH_LEN + CHECKSUM_LEN;
+const LONG_LEN: usize = 2 + HASH_LEN + CHECKSUM_LEN;
+const SHORT_ENCODED_MAX_LEN: usize = base58_encoded_max_len(SHORT_LEN);
+
+/// The longest encoding of an address, and the buffer size for
+/// [`encode_address`].
+pub const ADDRESS_ENCODED_MAX_LEN: usize = base58_encoded_max_len(LONG_LEN);
+
+/// The networks [`decode_address`] recognizes, by their version bytes.
+///
+/// The version bytes of the networks are all different, so an address
+/// belongs to at most one of them. Litecoin's old P2SH version, which is
+/// the same as Bitcoin's, isn't included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AddressNetwork {
+    /// `1...` and `3...`
+    Bitcoin,
+    /// `m...` or `n...` and `2...`, also used by signet and regtest.
+    BitcoinTestnet,
+    /// `L...` and `M...`
+    Litecoin,
+    /// `D...` and `9...` or `A...`
+    Dogecoin,
+    /// Transparent addresses, `t1...` and `t3...`
+    Zcash,
+    /// Transparent addresses, `tm...` and `t2...`
+    ZcashTestnet,
+}
+
+/// The type of script an address pays to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressKind {
+    /// Pay to public key hash: the hash is the hash160 of a public key.
+    P2pkh,
+    /// Pay to script hash: the hash is the hash160 of a script.
+    P2sh,
+}
+
+/* (network, P2PKH version, P2SH version) */
+const REGISTRY: [(AddressNetwork, &[u8], &[u8]); 6] = [
+    (AddressNetwork::Bitcoin, &[0x00], &[0x05]),
+    (AddressNetwork::BitcoinTestnet, &[0x6f], &[0xc4]),
+    (AddressNetwork::Litecoin, &[0x30], &[0x32]),
+    (AddressNetwork::Dogecoin, &[0x1e], &[0x16]),
+    (AddressNetwork::Zcash, &[0x1c, 0xb8], &[0x1c, 0xbd]),
+    (AddressNetwork::ZcashTestnet, &[0x1d, 0x25], &[0x1c, 0xba]),
+];
+
+impl AddressNetwork {
+    /// All the networks, in the order they're listed above.
+    pub const ALL: [AddressNetwork; REGISTRY.len()] = [
+        REGISTRY[0].0,
+        REGISTRY[1].0,
+        REGISTRY[2].0,
+        REGISTRY[3].0,
+        REGISTRY[4].0,
+        REGISTRY[5].0,
+    ];
+
+    /// The version bytes of addresses of `kind` on the network.
+    pub const fn version(self, kind: AddressKind) -> &'static [u8] {
+        let (_, p2pkh, p2sh) = REGISTRY[self as usize];
+        match kind {
+            AddressKind::P2pkh => p2pkh,
+            AddressKind::P2sh => p2sh,
+        }
+    }
+
+    /// The network and kind of addresses with the given version bytes, if
+    /// they're in the registry.
+    pub fn from_version(version: &[u8]) -> Option<(AddressNetwork, AddressKind)> {
+        REGISTRY.iter().find_map(|(network, p2pkh, p2sh)| {
+            if *p2pkh == version {
+                Some((*network, AddressKind::P2pkh))
+            } else if *p2sh == version {
+                Some((*network, AddressKind::P2sh))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// A decoded legacy address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Address {
+    pub network: AddressNetwork,
+    pub kind: AddressKind,
+    /// The hash160 of the public key or script.
+    pub hash: [u8; HASH_LEN],
+}
+
+/// Encode an address and return the length of the encoding.
+///
+/// # Examples
+///
+/// ```
+/// use five8::{Address, AddressKind, AddressNetwork};
+///
+/// let address = Address {
+///     network: AddressNetwork::Bitcoin,
+///     kind: AddressKind::P2pkh,
+///     hash: [
+///         98, 233, 7, 177, 92, 191, 39, 213, 66, 83, 153, 235, 246, 240, 251, 80, 235, 184,
+///         143, 24,
+///     ],
+/// };
+/// let mut out = [0u8; five8::ADDRESS_ENCODED_MAX_LEN];
+/// let len = five8::encode_address(&address, &mut out);
+/// assert_eq!(&out[..len as usize], b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+/// let address = Address { network: AddressNetwork::Zcash, ..address };
+/// let len = five8::encode_address(&address, &mut out);
+/// assert_eq!(&out[..len as usize], b"t1StbPM4X3j4FGM57HpGnb9BMbS7C1nFW1r");
+/// ```
+pub fn encode_address(address: &Address, out: &mut [u8; ADDRESS_ENCODED_MAX_LEN]) -> u8 {
+    let version = address.network.version(address.kind);
+    if version.len() == 1 {
+        encode_check::<SHORT_LEN, ADDRESS_ENCODED_MAX_LEN>(version, &address.hash, out)
+    } else {
+        encode_check::<LONG_LEN, ADDRESS_ENCODED_MAX_LEN>(version, &address.hash, out)
+    }
+}
+
+/// Decode an address of any of the networks in [`AddressNetwork`].
+///
+/// An address with a valid checksum but version bytes that aren't in the
+/// registry gives [`CheckDecodeError::InvalidVersion`].
+///
+/// # Examples
+///
+/// ```
+/// use five8::{AddressKind, AddressNetwork, CheckDecodeError};
+///
+/// let address = five8::decode_address("MGv9cSYnaRSTZNzYaN7bhbgmozoGkKBvCn").unwrap();
+/// assert_eq!(address.network, AddressNetwork::Litecoin);
+/// assert_eq!(address.kind, AddressKind::P2sh);
+/// assert_eq!(address.hash[..4], [98, 233, 7, 177]);
+/// assert!(matches!(
+///     five8::decode_address("MGv9cSYnaRSTZNzYaN7bhbgmozoGkKBvCm"),
+///     Err(CheckDecodeError::InvalidChecksum { .. })
+/// ));
+/// ```
+pub fn decode_address(encoded: impl AsRef<[u8]>) -> Result<Address, CheckDecodeError> {
+    let encoded = encoded.as_ref();
+    /* 25-byte data is below 2^200 < 2 * 58^34, so its encoding is at most
+    35 characters long, and 35 only with a first digit of 1.  Anything
+    else can only be 26-byte data, so there's one length to try. */
+    if encoded.len() < SHORT_ENCODED_MAX_LEN
+        || (encoded.len() == SHORT_ENCODED_MAX_LEN && encoded[0] == b'2')
+    {
+        let mut data = [0u8; SHORT_LEN];
+        decode(encoded, &mut data)?;
+        address_from_data(&data, 1)
+    } else {
+        let mut data = [0u8; LONG_LEN];
+        decode(encoded, &mut data)?;
+        address_from_data(&data, 2)
+    }
+}
+
+#[inline(always)]
+fn address_from_data(data: &[u8], version_len: usize) -> Result<Address, CheckDecodeError> {
+    let checked = verify_check(data, &[])?;
+    let (version, hash) = checked.split_at(version_len);
+    let (network, kind) =
+        AddressNetwork::from_version(version).ok_or(CheckDecodeError::InvalidVersion)?;
+    Ok(Address {
+        network,
+        kind,
+        hash: hash.try_into().unwrap(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use five8_core::DecodeError;
+
+    fn check_against_bs58(address: &Address) {
+        let mut data = address.network.version(address.kind).to_vec();
+        data.extend_from_slice(&address.hash);
+        let expected = bs58::encode(&data).with_check().into_vec();
+        test_util::check_against_bs58(
+            &expected,
+            |out| encode_address(address, out),
+            |encoded| decode_address(encoded),
+            *address,
+        );
+    }
+
+    #[test]
+    fn test_address_matches_bs58() {
+        for network in AddressNetwork::ALL {
+            for kind in [AddressKind::P2pkh, AddressKind::P2sh] {
+                for hash in [
+                    [0; 20],
+                    [1; 20],
+                    [0xff; 20],
+                    core::array::from_fn(|i| i as u8),
+                ] {
+                    check_against_bs58(&Address {
+                        network,
+                        kind,
+                        hash,
+                    });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        for (i, network) in AddressNetwork::ALL.into_iter().enumerate() {
+            assert_eq!(network as usize, i);
+            for kind in [AddressKind::P2pkh, AddressKind::P2sh] {
+                assert_eq!(
+                    AddressNetwork::from_version(network.version(kind)),
+                    Some((network, kind))
+                );
+            }
+        }
+        assert_eq!(AddressNetwork::from_version(&[0x1c]), None);
+        assert_eq!(AddressNetwork::from_version(&[]), None);
+    }
+
+    #[test]
+    fn test_address_errors() {
+        /* A valid Base58Check string of 21 bytes with an unknown version */
+        let encoded = bs58::encode([[0x01].as_slice(), &[7; 20]].concat())
+            .with_check()
+            .into_vec();
+        assert_eq!(
+            decode_address(&encoded),
+            Err(CheckDecodeError::InvalidVersion)
+        );
+        let encoded = bs58::encode([[0x1c, 0xb9].as_slice(), &[7; 20]].concat())
+            .with_check()
+            .into_vec();
+        assert_eq!(
+            decode_address(&encoded),
+            Err(CheckDecodeError::InvalidVersion)
+        );
+        /* 26-byte data starting with a zero byte is 35 characters from a
+        '1', which only the two-byte versions can be */
+        let encoded = bs58::encode([[0x00, 0x10].as_slice(), &[7; 20]].concat())
+            .with_check()
+            .into_vec();
+        assert_eq!(encoded.len(), SHORT_ENCODED_MAX_LEN);
+        assert_eq!(
+            decode_address(&encoded),
+            Err(CheckDecodeError::InvalidVersion)
+        );
+        /* A Solana public key */
+        assert_eq!(
+            decode_address("JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFF"),
+            Err(CheckDecodeError::Decode(DecodeError::TooLong))
+        );
+        assert_eq!(
+            decode_address(""),
+            Err(CheckDecodeError::Decode(DecodeError::TooShort))
+        );
+        assert_eq!(
+            decode_address("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfN0"),
+            Err(CheckDecodeError::Decode(DecodeError::InvalidChar(b'0')))
+        );
+        assert!(matches!(
+            decode_address("t1StbPM4X3j4FGM57HpGnb9BMbS7C1nFW1s"),
+            Err(CheckDecodeError::InvalidChecksum { .. })
+        ));
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_address_roundtrip(network in 0..AddressNetwork::ALL.len(), p2sh in any::<bool>(), hash in prop::array::uniform20(0u8..)) {
+            let kind = if p2sh { AddressKind::P2sh } else { AddressKind::P2pkh };
+            check_against_bs58(&Address { network: AddressNetwork::ALL[network], kind, hash });
+        }
+    }
+}
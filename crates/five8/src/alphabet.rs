@@ -0,0 +1,368 @@
+//! Encoding and decoding with other alphabets than Bitcoin's.
+//!
+//! These run the same kernels as the Bitcoin alphabet functions. The
+//! kernels compute Bitcoin characters with arithmetic, and look the
+//! characters of other alphabets up in their tables in the same pass: with
+//! byte shuffles on x86_64 and aarch64 and with the 58- and 128-byte tables
+//! in the scalar code.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use five8_core::{
+    Alphabet, DecodeError, BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN, N_32, N_64,
+};
+
+use crate::{
+    decode::{decode_32_charset, decode_64_charset},
+    encode::{encode_32_charset, encode_64_charset},
+    generic::{decode_charset, encode_charset},
+};
+
+/* The alphabet the encode and decode kernels convert digits to and from.
+The Bitcoin alphabet has arithmetic conversions, and the kernels look
+the characters of any other alphabet up in its tables instead. */
+pub(crate) trait Charset: Copy {
+    /* Whether to use the Bitcoin arithmetic, known at compile time so
+    that the unused tables and lookups are optimized away */
+    const BITCOIN: bool;
+
+    fn alphabet(&self) -> &Alphabet;
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Bitcoin;
+
+impl Charset for Bitcoin {
+    const BITCOIN: bool = true;
+
+    #[inline(always)]
+    fn alphabet(&self) -> &Alphabet {
+        &Alphabet::BITCOIN
+    }
+}
+
+impl Charset for &Alphabet {
+    const BITCOIN: bool = false;
+
+    #[inline(always)]
+    fn alphabet(&self) -> &Alphabet {
+        self
+    }
+}
+
+/// [`encode_32`] with another alphabet.
+///
+/// [`encode_32`]: crate::encode_32
+///
+/// # Examples
+///
+/// ```
+/// use five8::Alphabet;
+///
+/// let mut buf = [0u8; 44];
+/// let len = five8::encode_32_with_alphabet(&Alphabet::RIPPLE, &[0; 32], &mut buf);
+/// assert_eq!(&buf[..len as usize], [b'r'; 32]);
+/// ```
+#[inline]
+pub fn encode_32_with_alphabet(
+    alphabet: &Alphabet,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    encode_32_charset(alphabet, bytes, out)
+}
+
+/// [`encode_64`] with another alphabet.
+///
+/// [`encode_64`]: crate::encode_64
+#[inline]
+pub fn encode_64_with_alphabet(
+    alphabet: &Alphabet,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    encode_64_charset(alphabet, bytes, out)
+}
+
+/// [`decode_32`] with another alphabet.
+///
+/// [`decode_32`]: crate::decode_32
+///
+/// # Examples
+///
+/// ```
+/// use five8::Alphabet;
+///
+/// let mut buf = [0u8; 44];
+/// let len = five8::encode_32_with_alphabet(&Alphabet::FLICKR, &[7; 32], &mut buf);
+/// let mut out = [0u8; 32];
+/// five8::decode_32_with_alphabet(&Alphabet::FLICKR, &buf[..len as usize], &mut out).unwrap();
+/// assert_eq!(out, [7; 32]);
+/// ```
+#[inline]
+pub fn decode_32_with_alphabet<I: AsRef<[u8]>>(
+    alphabet: &Alphabet,
+    encoded: I,
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    decode_32_charset(alphabet, encoded.as_ref(), out)
+}
+
+/// [`decode_64`] with another alphabet.
+///
+/// [`decode_64`]: crate::decode_64
+#[inline]
+pub fn decode_64_with_alphabet<I: AsRef<[u8]>>(
+    alphabet: &Alphabet,
+    encoded: I,
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    decode_64_charset(alphabet, encoded.as_ref(), out)
+}
+
+/// [`encode`](crate::encode()) with another alphabet.
+///
+/// # Examples
+///
+/// ```
+/// use five8::Alphabet;
+///
+/// /* An XRP Ledger account ID with its version byte and checksum */
+/// let bytes = [
+///     0, 136, 165, 165, 124, 130, 159, 64, 242, 94, 168, 51, 133, 187, 222, 108, 61, 139, 76,
+///     160, 130, 237, 67, 134, 65,
+/// ];
+/// let mut out = [0u8; five8::base58_encoded_max_len(25)];
+/// let len = five8::encode_with_alphabet(&Alphabet::RIPPLE, &bytes, &mut out);
+/// assert_eq!(&out[..len as usize], b"rDTXLQ7ZKZVKz33zJbHjgVShjsBnqMBhmN");
+/// ```
+#[inline]
+pub fn encode_with_alphabet<const N: usize, const M: usize>(
+    alphabet: &Alphabet,
+    bytes: &[u8; N],
+    out: &mut [u8; M],
+) -> u8 {
+    encode_charset(alphabet, bytes, out)
+}
+
+/// [`decode`](crate::decode()) with another alphabet.
+#[inline]
+pub fn decode_with_alphabet<const N: usize, I: AsRef<[u8]>>(
+    alphabet: &Alphabet,
+    encoded: I,
+    out: &mut [u8; N],
+) -> Result<(), DecodeError> {
+    decode_charset(alphabet, encoded.as_ref(), out)
+}
+
+/// [`encode_to_string`](crate::encode_to_string) with another alphabet.
+///
+/// # Examples
+///
+/// ```
+/// use five8::Alphabet;
+///
+/// let encoded = five8::encode_to_string_with_alphabet(&Alphabet::FLICKR, b"hello world");
+/// assert_eq!(encoded, "rTu1dk6cWsRYjYu");
+/// let decoded = five8::decode_to_vec_with_alphabet(&Alphabet::FLICKR, &encoded).unwrap();
+/// assert_eq!(decoded, b"hello world");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_to_string_with_alphabet(alphabet: &Alphabet, bytes: &[u8]) -> String {
+    crate::slice::encode_to_string_charset(alphabet, bytes)
+}
+
+/// [`decode_to_vec`](crate::decode_to_vec) with another alphabet.
+#[cfg(feature = "alloc")]
+pub fn decode_to_vec_with_alphabet<I: AsRef<[u8]>>(
+    alphabet: &Alphabet,
+    encoded: I,
+) -> Result<Vec<u8>, DecodeError> {
+    crate::slice::decode_to_vec_charset(alphabet, encoded.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+
+    fn bs58_alphabet(alphabet: &Alphabet) -> bs58::Alphabet {
+        bs58::Alphabet::new(alphabet.as_bytes()).unwrap()
+    }
+
+    fn check_alphabets_32_64(bytes_32: [u8; 32], bytes_64: [u8; 64]) {
+        for alphabet in [Alphabet::BITCOIN, Alphabet::RIPPLE, Alphabet::FLICKR] {
+            let expected = bs58::encode(bytes_32)
+                .with_alphabet(&bs58_alphabet(&alphabet))
+                .into_vec();
+            let mut buf = [0u8; 44];
+            let len = encode_32_with_alphabet(&alphabet, &bytes_32, &mut buf);
+            assert_eq!(&buf[..len as usize], expected);
+            let mut out = [0u8; 32];
+            decode_32_with_alphabet(&alphabet, &expected, &mut out).unwrap();
+            assert_eq!(out, bytes_32);
+
+            let expected = bs58::encode(bytes_64)
+                .with_alphabet(&bs58_alphabet(&alphabet))
+                .into_vec();
+            let mut buf = [0u8; 88];
+            let len = encode_64_with_alphabet(&alphabet, &bytes_64, &mut buf);
+            assert_eq!(&buf[..len as usize], expected);
+            let mut out = [0u8; 64];
+            decode_64_with_alphabet(&alphabet, &expected, &mut out).unwrap();
+            assert_eq!(out, bytes_64);
+        }
+    }
+
+    #[test]
+    fn test_alphabets_32_64() {
+        check_alphabets_32_64([0; 32], [0; 64]);
+        check_alphabets_32_64([255; 32], [255; 64]);
+        let mut bytes_64 = [0u8; 64];
+        bytes_64[40] = 9;
+        check_alphabets_32_64([1; 32], bytes_64);
+    }
+
+    #[test]
+    fn test_alphabets_backends() {
+        use crate::{
+            decode::{decode_32_with_charset, decode_64_with_charset},
+            encode::{encode_32_with_charset, encode_64_with_charset},
+            Backend,
+        };
+        let bytes_32: [u8; 32] = core::array::from_fn(|i| (i * 29) as u8);
+        let mut bytes_64: [u8; 64] = core::array::from_fn(|i| (i * 31) as u8);
+        bytes_64[..3].fill(0);
+        for backend in Backend::ALL.iter().filter(|b| b.is_available()) {
+            for alphabet in [Alphabet::RIPPLE, Alphabet::FLICKR] {
+                let expected = bs58::encode(bytes_32)
+                    .with_alphabet(&bs58_alphabet(&alphabet))
+                    .into_vec();
+                let mut buf = [0u8; 44];
+                let len = encode_32_with_charset(&alphabet, *backend, &bytes_32, &mut buf);
+                assert_eq!(&buf[..len as usize], expected, "{backend:?}");
+                let mut out = [0u8; 32];
+                decode_32_with_charset(&alphabet, *backend, &expected, &mut out).unwrap();
+                assert_eq!(out, bytes_32, "{backend:?}");
+                for c in [b'0', b'l', 0x80, 0xff] {
+                    let mut invalid = expected.clone();
+                    invalid[20] = c;
+                    assert_eq!(
+                        decode_32_with_charset(&alphabet, *backend, &invalid, &mut out),
+                        Err(DecodeError::InvalidChar(c)),
+                        "{backend:?}"
+                    );
+                }
+
+                let expected = bs58::encode(bytes_64)
+                    .with_alphabet(&bs58_alphabet(&alphabet))
+                    .into_vec();
+                let mut buf = [0u8; 88];
+                let len = encode_64_with_charset(&alphabet, *backend, &bytes_64, &mut buf);
+                assert_eq!(&buf[..len as usize], expected, "{backend:?}");
+                let mut out = [0u8; 64];
+                decode_64_with_charset(&alphabet, *backend, &expected, &mut out).unwrap();
+                assert_eq!(out, bytes_64, "{backend:?}");
+                for c in [b'0', b'l', 0x80, 0xff] {
+                    let mut invalid = expected.clone();
+                    invalid[70] = c;
+                    assert_eq!(
+                        decode_64_with_charset(&alphabet, *backend, &invalid, &mut out),
+                        Err(DecodeError::InvalidChar(c)),
+                        "{backend:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_alphabet_generic() {
+        let bytes: [u8; 20] = core::array::from_fn(|i| (i * 37) as u8);
+        let expected = bs58::encode(bytes)
+            .with_alphabet(bs58::Alphabet::RIPPLE)
+            .into_vec();
+        let mut buf = [0u8; 28];
+        let len = encode_with_alphabet(&Alphabet::RIPPLE, &bytes, &mut buf);
+        assert_eq!(&buf[..len as usize], expected);
+        let mut out = [0u8; 20];
+        decode_with_alphabet(&Alphabet::RIPPLE, &expected, &mut out).unwrap();
+        assert_eq!(out, bytes);
+    }
+
+    #[test]
+    fn test_alphabet_decode_errors() {
+        let mut out = [0u8; 32];
+        /* The leading zeros are the alphabet's digit 0, not '1' */
+        decode_32_with_alphabet(&Alphabet::RIPPLE, [b'r'; 32], &mut out).unwrap();
+        assert_eq!(out, [0; 32]);
+        assert_eq!(
+            decode_32_with_alphabet(&Alphabet::RIPPLE, [b'0'; 32], &mut out),
+            Err(DecodeError::InvalidChar(b'0'))
+        );
+        assert_eq!(
+            decode_32_with_alphabet(&Alphabet::RIPPLE, "rrr\u{e9}", &mut out),
+            Err(DecodeError::InvalidChar(0xc3))
+        );
+        /* Same errors as decode_32 for the same input */
+        assert_eq!(
+            decode_32_with_alphabet(&Alphabet::FLICKR, [b'2'; 46], &mut out),
+            Err(DecodeError::TooLong)
+        );
+        let mut long = [b'2'; 60];
+        long[50] = b'0';
+        assert_eq!(
+            decode_32_with_alphabet(&Alphabet::FLICKR, long, &mut out),
+            crate::decode_32(&long[..], &mut out)
+        );
+        assert_eq!(
+            decode_32_with_alphabet(&Alphabet::FLICKR, [b'2'; 10], &mut out),
+            Err(DecodeError::TooShort)
+        );
+        let mut out = [0u8; 20];
+        assert_eq!(
+            decode_with_alphabet(&Alphabet::FLICKR, [b'2'; 40], &mut out),
+            Err(DecodeError::TooLong)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_alphabet_slice() {
+        for alphabet in [Alphabet::RIPPLE, Alphabet::FLICKR] {
+            for len in [0, 1, 5, 32, 40, 64, 100] {
+                let bytes: Vec<u8> = (0..len).map(|i| (i * 13) as u8).collect();
+                let expected = bs58::encode(&bytes)
+                    .with_alphabet(&bs58_alphabet(&alphabet))
+                    .into_string();
+                assert_eq!(encode_to_string_with_alphabet(&alphabet, &bytes), expected);
+                assert_eq!(
+                    decode_to_vec_with_alphabet(&alphabet, &expected).unwrap(),
+                    bytes
+                );
+            }
+        }
+        assert_eq!(
+            decode_to_vec_with_alphabet(&Alphabet::RIPPLE, "rp0"),
+            Err(DecodeError::InvalidChar(b'0'))
+        );
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_alphabet_32(key in prop::array::uniform32(0u8..)) {
+            let expected = bs58::encode(key)
+                .with_alphabet(bs58::Alphabet::RIPPLE)
+                .into_vec();
+            let mut buf = [0u8; 44];
+            let len = encode_32_with_alphabet(&Alphabet::RIPPLE, &key, &mut buf);
+            assert_eq!(&buf[..len as usize], expected);
+            let mut out = [0u8; 32];
+            decode_32_with_alphabet(&Alphabet::RIPPLE, &expected, &mut out).unwrap();
+            assert_eq!(out, key);
+        }
+    }
+}
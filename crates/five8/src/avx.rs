@@ -1,13 +1,17 @@
-use crate::bits_find_lsb::fd_ulong_find_lsb_w_default;
+use crate::{alphabet::Charset, bits_find_lsb::fd_ulong_find_lsb_w_default, sse};
 use core::arch::x86_64::{
-    __m256i, _mm256_add_epi8, _mm256_and_si256, _mm256_cmpeq_epi64, _mm256_cmpeq_epi8,
-    _mm256_cmpgt_epi64, _mm256_cmpgt_epi8, _mm256_extractf128_si256, _mm256_loadu_si256,
-    _mm256_movemask_epi8, _mm256_mul_epu32, _mm256_or_si256, _mm256_set1_epi64x, _mm256_set1_epi8,
-    _mm256_set_m128i, _mm256_setr_epi64x, _mm256_setr_epi8, _mm256_setzero_si256,
-    _mm256_shuffle_epi8, _mm256_slli_epi64, _mm256_slli_si256, _mm256_srli_epi64,
-    _mm256_srlv_epi64, _mm256_storeu_si256, _mm256_sub_epi64, _mm256_sub_epi8, _mm_or_si128,
-    _mm_setzero_si128, _mm_slli_si128, _mm_srli_si128,
+    __m128i, __m256i, _mm256_add_epi64, _mm256_add_epi8, _mm256_and_si256, _mm256_andnot_si256,
+    _mm256_broadcastsi128_si256, _mm256_cmpeq_epi64, _mm256_cmpeq_epi8, _mm256_cmpgt_epi64,
+    _mm256_cmpgt_epi8, _mm256_extractf128_si256, _mm256_loadu_si256, _mm256_madd_epi16,
+    _mm256_maddubs_epi16, _mm256_min_epu8, _mm256_movemask_epi8, _mm256_mul_epu32, _mm256_or_si256,
+    _mm256_permute2x128_si256, _mm256_set1_epi64x, _mm256_set1_epi8, _mm256_set_m128i,
+    _mm256_setr_epi16, _mm256_setr_epi64x, _mm256_setr_epi8, _mm256_setzero_si256,
+    _mm256_shuffle_epi8, _mm256_slli_epi64, _mm256_slli_si256, _mm256_srli_epi16,
+    _mm256_srli_epi64, _mm256_srlv_epi64, _mm256_storeu_si256, _mm256_sub_epi64, _mm256_sub_epi8,
+    _mm256_unpackhi_epi64, _mm256_unpacklo_epi64, _mm_loadu_si128, _mm_or_si128, _mm_setzero_si128,
+    _mm_slli_si128, _mm_srli_si128,
 };
+use five8_core::BASE58_INVALID_CHAR;
 
 #[inline(always)]
 pub(crate) fn wuc_ldu(p: *const u8) -> __m256i {
@@ -65,7 +69,16 @@ fn wl_sub(a: __m256i, b: __m256i) -> __m256i {
 }
 
 #[inline(always)]
-pub(crate) fn intermediate_to_raw(intermediate: __m256i) -> __m256i {
+pub(crate) fn wl_add(a: __m256i, b: __m256i) -> __m256i {
+    unsafe { _mm256_add_epi64(a, b) }
+}
+
+/* intermediate_to_digits returns the 5 base58 digits of each ulong in
+intermediate (each must be < 58^5) as [rem0, rem1, rem2, rem3, rem4],
+where rem0 holds the least significant digits and rem4 the most
+significant ones. */
+#[inline(always)]
+fn intermediate_to_digits(intermediate: __m256i) -> [__m256i; 5] {
     /* The computation we need to do here mathematically is
     y=(floor(x/58^k) % 58) for various values of k.  It seems that the
     best way to compute it (at least what the compiler generates in the
@@ -103,6 +116,12 @@ pub(crate) fn intermediate_to_raw(intermediate: __m256i) -> __m256i {
     let div4 = div3364(div2);
     let rem3 = wl_sub(div3, unsafe { _mm256_mul_epu32(div4, broadcast_58) });
     let rem4 = div4;
+    [rem0, rem1, rem2, rem3, rem4]
+}
+
+#[inline(always)]
+pub(crate) fn intermediate_to_raw(intermediate: __m256i) -> __m256i {
+    let [rem0, rem1, rem2, rem3, rem4] = intermediate_to_digits(intermediate);
     /* Okay, we have all 20 terms we need at this point, but they're
     spread out over 5 registers. Each value is stored as an 8B long,
     even though it's less than 58, so 7 of those bytes are 0.  That
@@ -156,6 +175,65 @@ pub(crate) fn intermediate_to_raw(intermediate: __m256i) -> __m256i {
     }
 }
 
+/* intermediate_to_raw_per_ulong is like intermediate_to_raw, but keeps
+the 5 digits of each ulong in that ulong, most significant first:
+
+  [ a b c d e 0 0 0  f g h i j 0 0 0 | k l m n o 0 0 0  p q r s t 0 0 0 ]
+
+This is the useful layout when each ulong belongs to a different input,
+see transpose_4x4.  compact_raw_per_ulong then converts it to the
+layout intermediate_to_raw returns. */
+#[inline(always)]
+pub(crate) fn intermediate_to_raw_per_ulong(intermediate: __m256i) -> __m256i {
+    let [rem0, rem1, rem2, rem3, rem4] = intermediate_to_digits(intermediate);
+    unsafe {
+        _mm256_or_si256(
+            _mm256_or_si256(
+                _mm256_or_si256(rem4, wl_shl::<8>(rem3)),
+                _mm256_or_si256(wl_shl::<16>(rem2), wl_shl::<24>(rem1)),
+            ),
+            wl_shl::<32>(rem0),
+        )
+    }
+}
+
+#[inline(always)]
+pub(crate) fn compact_raw_per_ulong(raw: __m256i) -> __m256i {
+    unsafe {
+        _mm256_shuffle_epi8(
+            raw,
+            _mm256_setr_epi8(
+                0, 1, 2, 3, 4, 8, 9, 10, 11, 12, -1, -1, -1, -1, -1, -1, 0, 1, 2, 3, 4, 8, 9, 10,
+                11, 12, -1, -1, -1, -1, -1, -1,
+            ),
+        )
+    }
+}
+
+/* transpose_4x4 transposes a 4x4 matrix of ulongs, i.e. ulong j of
+output i is ulong i of input j.  The batch functions use it to move 4
+inputs into one ulong each and back. */
+#[inline(always)]
+pub(crate) fn transpose_4x4(
+    in0: __m256i,
+    in1: __m256i,
+    in2: __m256i,
+    in3: __m256i,
+) -> [__m256i; 4] {
+    let lo01 = unsafe { _mm256_unpacklo_epi64(in0, in1) }; /* [ a0 b0 | a2 b2 ] */
+    let hi01 = unsafe { _mm256_unpackhi_epi64(in0, in1) }; /* [ a1 b1 | a3 b3 ] */
+    let lo23 = unsafe { _mm256_unpacklo_epi64(in2, in3) }; /* [ c0 d0 | c2 d2 ] */
+    let hi23 = unsafe { _mm256_unpackhi_epi64(in2, in3) }; /* [ c1 d1 | c3 d3 ] */
+    unsafe {
+        [
+            _mm256_permute2x128_si256::<0x20>(lo01, lo23),
+            _mm256_permute2x128_si256::<0x20>(hi01, hi23),
+            _mm256_permute2x128_si256::<0x31>(lo01, lo23),
+            _mm256_permute2x128_si256::<0x31>(hi01, hi23),
+        ]
+    }
+}
+
 #[inline(always)]
 const fn fd_ulong_mask_lsb(n: i32) -> u64 {
     (((n <= 63) as u64) << (n & 63)).wrapping_sub(1)
@@ -196,6 +274,63 @@ pub(crate) fn raw_to_base58(in_: __m256i) -> __m256i {
     unsafe { _mm256_sub_epi8(in_, sum) }
 }
 
+/* Same as lookup in sse.rs, with each table repeated in both lanes. */
+#[inline(always)]
+fn lookup<const TABLES: usize>(idx: __m256i, table: &[__m256i; TABLES]) -> __m256i {
+    unsafe {
+        let low_nibble_mask = _mm256_set1_epi8(0x0f);
+        let lo = _mm256_and_si256(idx, low_nibble_mask);
+        let hi = _mm256_and_si256(_mm256_srli_epi16::<4>(idx), low_nibble_mask);
+        let mut out = _mm256_setzero_si256();
+        for (k, t) in table.iter().enumerate() {
+            let select = _mm256_cmpeq_epi8(hi, _mm256_set1_epi8(k as i8));
+            out = _mm256_or_si256(out, _mm256_and_si256(select, _mm256_shuffle_epi8(*t, lo)));
+        }
+        out
+    }
+}
+
+/* chars_table and digits_table are the tables of the same names in
+sse.rs, repeated in both lanes. */
+#[inline(always)]
+pub(crate) fn chars_table(chars: &[u8; 58]) -> [__m256i; 4] {
+    sse::chars_table(chars).map(|t| unsafe { _mm256_broadcastsi128_si256(t) })
+}
+
+#[inline(always)]
+pub(crate) fn digits_table(decode: &[u8; 128]) -> [__m256i; 8] {
+    sse::digits_table(decode).map(|t| unsafe { _mm256_broadcastsi128_si256(t) })
+}
+
+/* raw_to_chars and chars_to_digits work like the SSSE3 versions, on 32
+bytes at a time. */
+#[inline(always)]
+pub(crate) fn raw_to_chars<C: Charset>(in_: __m256i, chars: &[__m256i; 4]) -> __m256i {
+    if C::BITCOIN {
+        raw_to_base58(in_)
+    } else {
+        lookup(in_, chars)
+    }
+}
+
+#[inline(always)]
+pub(crate) fn chars_to_digits<C: Charset>(p: *mut u8, digits: &[__m256i; 8]) -> u32 {
+    if C::BITCOIN {
+        return base58_chars_to_digits(p);
+    }
+    unsafe {
+        let chars = wuc_ldu(p);
+        let looked_up = lookup(chars, digits);
+        let zero = _mm256_cmpeq_epi8(chars, _mm256_setzero_si256());
+        wuc_stu(p, _mm256_andnot_si256(zero, looked_up));
+        let invalid = _mm256_or_si256(
+            chars,
+            _mm256_cmpeq_epi8(looked_up, _mm256_set1_epi8(BASE58_INVALID_CHAR as i8)),
+        );
+        _mm256_movemask_epi8(invalid) as u32
+    }
+}
+
 /* count_leading_zeros_{n} counts the number of zero bytes prior to the
 first non-zero byte in the first n bytes.  If all n bytes are zero,
 returns n.  Return value is in [0, n].  For the two-vector cases, in0
@@ -338,6 +473,236 @@ pub(crate) fn ten_per_slot_down_64(
     (out0, out1, out2)
 }
 
+/* raw_to_intermediate is the inverse of intermediate_to_raw.  It
+converts the 20 raw base58 digits starting at p (each in [0, 58)) to
+the 4 corresponding terms of the intermediate form:
+
+  intermediate[i] = sum_k raw[5*i+k] * 58^(4-k)
+
+It reads 26 bytes starting at p, so the caller has to pad the raw
+array.  As with intermediate_to_raw, crossing the 128-bit boundary is
+inconvenient, so each half of the register is loaded separately with
+the two groups of 5 it needs:
+
+  [ a0 .. a4 b0 .. b4 xx .. xx | c0 .. c4 d0 .. d4 xx .. xx ]
+
+which we then spread out to one group per ulong:
+
+  [ a0 .. a4 0 0 0 b0 .. b4 0 0 0 | c0 .. c4 0 0 0 d0 .. d4 0 0 0 ]
+
+The digits are small enough that the multiply-adds meant for 8 and 16
+bit integers can do most of the work:
+  maddubs: [ 58*a0+a1, 58*a2+a3, a4, 0 ] (each < 58^2, fits in 16 bits)
+  madd:    [ 58^2*(58*a0+a1) + 58*a2+a3, a4 ] (< 58^4, fits in 32 bits)
+and the last step is a 32x32->64 multiply by 58 plus a4. */
+#[inline(always)]
+pub(crate) fn raw_to_intermediate(p: *const u8) -> __m256i {
+    let lo = unsafe { _mm_loadu_si128(p as *const __m128i) };
+    let hi = unsafe { _mm_loadu_si128(p.add(10) as *const __m128i) };
+    let spread = unsafe {
+        _mm256_shuffle_epi8(
+            _mm256_set_m128i(hi, lo),
+            _mm256_setr_epi8(
+                0, 1, 2, 3, 4, -1, -1, -1, 5, 6, 7, 8, 9, -1, -1, -1, 0, 1, 2, 3, 4, -1, -1, -1, 5,
+                6, 7, 8, 9, -1, -1, -1,
+            ),
+        )
+    };
+    let pairs = unsafe {
+        _mm256_maddubs_epi16(
+            spread,
+            _mm256_setr_epi8(
+                58, 1, 58, 1, 1, 0, 0, 0, 58, 1, 58, 1, 1, 0, 0, 0, 58, 1, 58, 1, 1, 0, 0, 0, 58,
+                1, 58, 1, 1, 0, 0, 0,
+            ),
+        )
+    };
+    let quads = unsafe {
+        _mm256_madd_epi16(
+            pairs,
+            _mm256_setr_epi16(3364, 1, 1, 0, 3364, 1, 1, 0, 3364, 1, 1, 0, 3364, 1, 1, 0),
+        )
+    };
+    wl_add(
+        unsafe { _mm256_mul_epu32(quads, wl_bcast(58)) },
+        wl_shru::<32>(quads),
+    )
+}
+
+/* intermediate_to_binary computes the product of the intermediate terms
+and the decode table:
+
+  binary[j] = sum_i intermediate[i] * dec_table[i][j]
+
+Each intermediate term is less than 58^5 < 2^32 and each table entry is
+a uint, so the products are exactly what mul_epu32 computes.  The table
+is used in its natural layout: a 256-bit load of a row gives 8 uints,
+the even columns of which are already in the low half of each ulong,
+and shifting the row right by 32 bits gives the odd columns.  So each
+block of 8 columns is accumulated in two registers:
+
+  even: [ b0 b2 | b4 b6 ]    odd: [ b1 b3 | b5 b7 ]
+
+and interleaved once at the end.  The sums are the same (mod 2^64) as in
+the scalar code, which never overflows. */
+#[inline(always)]
+pub(crate) fn intermediate_to_binary<
+    const INTERMEDIATE_SZ: usize,
+    const INTERMEDIATE_SZ_W_PADDING: usize,
+    const BINARY_SZ: usize,
+    const N_BLOCKS: usize,
+>(
+    intermediate: &[u64; INTERMEDIATE_SZ_W_PADDING],
+    dec_table: &[[u32; BINARY_SZ]; INTERMEDIATE_SZ],
+) -> [u64; BINARY_SZ] {
+    let zero = unsafe { _mm256_setzero_si256() };
+    let mut even = [zero; N_BLOCKS];
+    let mut odd = [zero; N_BLOCKS];
+    for i in 0..INTERMEDIATE_SZ {
+        let term = wl_bcast(unsafe { *intermediate.get_unchecked(i) } as i64);
+        let row = unsafe { dec_table.get_unchecked(i) }.as_ptr() as *const u8;
+        for b in 0..N_BLOCKS {
+            let cols = wuc_ldu(unsafe { row.add(32 * b) });
+            unsafe {
+                *even.get_unchecked_mut(b) =
+                    wl_add(*even.get_unchecked(b), _mm256_mul_epu32(term, cols));
+                *odd.get_unchecked_mut(b) = wl_add(
+                    *odd.get_unchecked(b),
+                    _mm256_mul_epu32(term, wl_shru::<32>(cols)),
+                );
+            }
+        }
+    }
+    let mut binary = [0u64; BINARY_SZ];
+    let binary_ptr = binary.as_mut_ptr() as *mut u8;
+    for b in 0..N_BLOCKS {
+        let (e, o) = unsafe { (*even.get_unchecked(b), *odd.get_unchecked(b)) };
+        let lo = unsafe { _mm256_unpacklo_epi64(e, o) }; /* [ b0 b1 | b4 b5 ] */
+        let hi = unsafe { _mm256_unpackhi_epi64(e, o) }; /* [ b2 b3 | b6 b7 ] */
+        unsafe {
+            wuc_stu(
+                binary_ptr.add(64 * b),
+                _mm256_permute2x128_si256::<0x20>(lo, hi),
+            );
+            wuc_stu(
+                binary_ptr.add(64 * b + 32),
+                _mm256_permute2x128_si256::<0x31>(lo, hi),
+            );
+        }
+    }
+    binary
+}
+
+/* table_product is intermediate_to_binary for the generic functions,
+whose sizes can't be array lengths: it computes
+
+  product[j] = sum_i multipliers[i] * table[i][j]
+
+for the first rows rows and the first 8*blocks columns, the rest of
+product being zero.  Each multiplier must be less than 2^32, and the
+caller makes sure the sums fit in a ulong. */
+#[inline(always)]
+pub(crate) fn table_product<const ROWS: usize, const COLS: usize, const BLOCKS: usize>(
+    multipliers: &[u64; ROWS],
+    table: &[[u32; COLS]; ROWS],
+    rows: usize,
+    blocks: usize,
+) -> [u64; COLS] {
+    const { assert!(COLS == 8 * BLOCKS) };
+    debug_assert!(rows <= ROWS && blocks <= BLOCKS);
+    let zero = unsafe { _mm256_setzero_si256() };
+    let mut even = [zero; BLOCKS];
+    let mut odd = [zero; BLOCKS];
+    for i in 0..rows {
+        let multiplier = wl_bcast(unsafe { *multipliers.get_unchecked(i) } as i64);
+        let row = unsafe { table.get_unchecked(i) }.as_ptr() as *const u8;
+        for b in 0..blocks {
+            let cols = wuc_ldu(unsafe { row.add(32 * b) });
+            unsafe {
+                *even.get_unchecked_mut(b) =
+                    wl_add(*even.get_unchecked(b), _mm256_mul_epu32(multiplier, cols));
+                *odd.get_unchecked_mut(b) = wl_add(
+                    *odd.get_unchecked(b),
+                    _mm256_mul_epu32(multiplier, wl_shru::<32>(cols)),
+                );
+            }
+        }
+    }
+    let mut product = [0u64; COLS];
+    let product_ptr = product.as_mut_ptr() as *mut u8;
+    for b in 0..blocks {
+        let (e, o) = unsafe { (*even.get_unchecked(b), *odd.get_unchecked(b)) };
+        let lo = unsafe { _mm256_unpacklo_epi64(e, o) };
+        let hi = unsafe { _mm256_unpackhi_epi64(e, o) };
+        unsafe {
+            wuc_stu(
+                product_ptr.add(64 * b),
+                _mm256_permute2x128_si256::<0x20>(lo, hi),
+            );
+            wuc_stu(
+                product_ptr.add(64 * b + 32),
+                _mm256_permute2x128_si256::<0x31>(lo, hi),
+            );
+        }
+    }
+    product
+}
+
+/* base58_chars_to_digits converts the 32 bytes at p from base58
+characters to their digit values in place, and returns a bitmask with
+bit i set if byte i is not a base58 character.  Same as the SSSE3
+version in sse.rs, which explains the lookup tables, with each table
+repeated in both lanes. */
+#[inline(always)]
+pub(crate) fn base58_chars_to_digits(p: *mut u8) -> u32 {
+    unsafe {
+        let chars = wuc_ldu(p);
+        let low_nibble_mask = _mm256_set1_epi8(0x0f);
+        let lo = _mm256_and_si256(chars, low_nibble_mask);
+        let hi = _mm256_and_si256(_mm256_srli_epi16::<4>(chars), low_nibble_mask);
+        let hi_bit = _mm256_shuffle_epi8(
+            _mm256_setr_epi8(
+                0, 0, 0, 1, 2, 4, 8, 16, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 4, 8, 16, 0, 0, 0,
+                0, 0, 0, 0, 0,
+            ),
+            hi,
+        );
+        let valid = _mm256_and_si256(
+            _mm256_shuffle_epi8(
+                _mm256_setr_epi8(
+                    20, 31, 31, 31, 31, 31, 31, 31, 31, 29, 30, 10, 2, 10, 10, 8, 20, 31, 31, 31,
+                    31, 31, 31, 31, 31, 29, 30, 10, 2, 10, 10, 8,
+                ),
+                lo,
+            ),
+            hi_bit,
+        );
+        let past_gap = _mm256_and_si256(
+            _mm256_shuffle_epi8(
+                _mm256_setr_epi8(
+                    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 2, 2, 10, 10, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                    0, 2, 2, 2, 10, 10, 10,
+                ),
+                lo,
+            ),
+            hi_bit,
+        );
+        let offset = _mm256_shuffle_epi8(
+            _mm256_setr_epi8(
+                0, 0, 0, -49, -56, -58, -64, -65, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -49, -56, -58,
+                -64, -65, 0, 0, 0, 0, 0, 0, 0, 0,
+            ),
+            hi,
+        );
+        let digits = _mm256_sub_epi8(
+            _mm256_add_epi8(chars, offset),
+            _mm256_min_epu8(past_gap, _mm256_set1_epi8(1)),
+        );
+        wuc_stu(p, digits);
+        _mm256_movemask_epi8(_mm256_cmpeq_epi8(valid, _mm256_setzero_si256())) as u32
+    }
+}
+
 /* wl_ld return the 4 longs at the 32-byte aligned / 32-byte sized
 location p as a vector long.  wl_ldu is the same but p does not have
 to be aligned.  wl_st writes the vector long to the 32-byte aligned /
@@ -355,7 +720,8 @@ pub(crate) fn wl_ld(p: *const i64) -> __m256i {
 mod tests {
     use super::*;
 
-    use core::arch::x86_64::{_mm256_load_si256, _mm256_store_si256};
+    use core::arch::x86_64::{_mm256_load_si256, _mm256_loadu_si256, _mm256_store_si256};
+    use five8_core::Alphabet;
 
     fn wuc_ld(p: *const u8) -> __m256i {
         unsafe { _mm256_load_si256(p as *const __m256i) }
@@ -438,4 +804,119 @@ mod tests {
             assert_eq!(out.0[i], i as u8 + 1);
         }
     }
+
+    #[test]
+    fn test_intermediate_to_raw_per_ulong() {
+        let terms: [[u64; 4]; 4] = core::array::from_fn(|i| {
+            core::array::from_fn(|k| 656356767 - 12345 * (4 * i + k) as u64)
+        });
+        let ld = |p: &[u64; 4]| wuc_ldu(p.as_ptr() as *const u8);
+        let transposed = transpose_4x4(
+            intermediate_to_raw_per_ulong(ld(&terms[0])),
+            intermediate_to_raw_per_ulong(ld(&terms[1])),
+            intermediate_to_raw_per_ulong(ld(&terms[2])),
+            intermediate_to_raw_per_ulong(ld(&terms[3])),
+        );
+        for (k, transposed) in transposed.into_iter().enumerate() {
+            let input = [terms[0][k], terms[1][k], terms[2][k], terms[3][k]];
+            let mut expected = [0u8; 32];
+            let mut found = [0u8; 32];
+            wuc_stu(expected.as_mut_ptr(), intermediate_to_raw(ld(&input)));
+            wuc_stu(found.as_mut_ptr(), compact_raw_per_ulong(transposed));
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_raw_to_intermediate() {
+        let mut raw = [0u8; 26];
+        for (i, d) in raw.iter_mut().take(20).enumerate() {
+            *d = (57 - 2 * i) as u8;
+        }
+        let mut out = [0u64; 4];
+        wuc_stu(
+            out.as_mut_ptr() as *mut u8,
+            raw_to_intermediate(raw.as_ptr()),
+        );
+        for (i, term) in out.iter().enumerate() {
+            let expected = raw[5 * i..5 * i + 5]
+                .iter()
+                .fold(0u64, |acc, d| acc * 58 + *d as u64);
+            assert_eq!(*term, expected);
+        }
+        /* the largest term */
+        let raw = [57u8; 26];
+        wuc_stu(
+            out.as_mut_ptr() as *mut u8,
+            raw_to_intermediate(raw.as_ptr()),
+        );
+        assert_eq!(out, [656356767; 4]);
+    }
+
+    #[test]
+    fn test_intermediate_to_binary() {
+        let intermediate: [u64; 12] = core::array::from_fn(|i| 656356767 - 1000 * i as u64);
+        let binary =
+            intermediate_to_binary::<9, 12, 8, 1>(&intermediate, &five8_core::DEC_TABLE_32);
+        for (j, b) in binary.iter().enumerate() {
+            let expected = (0..9)
+                .map(|i| intermediate[i] * five8_core::DEC_TABLE_32[i][j] as u64)
+                .sum::<u64>();
+            assert_eq!(*b, expected);
+        }
+    }
+
+    #[test]
+    fn test_base58_chars_to_digits() {
+        for block in 0..8 {
+            let mut buf: [u8; 32] = core::array::from_fn(|i| (32 * block + i) as u8);
+            let mut expected = buf;
+            let expected_invalid = crate::sse::base58_chars_to_digits(expected.as_mut_ptr())
+                | crate::sse::base58_chars_to_digits(unsafe { expected.as_mut_ptr().add(16) })
+                    << 16;
+            let invalid = base58_chars_to_digits(buf.as_mut_ptr());
+            assert_eq!(invalid, expected_invalid);
+            assert_eq!(buf, expected);
+        }
+    }
+
+    #[test]
+    fn test_chars_to_digits() {
+        let alphabet = &Alphabet::FLICKR;
+        let sse_table = sse::digits_table(alphabet.decode_table());
+        let table = digits_table(alphabet.decode_table());
+        for block in 0..8 {
+            let mut buf: [u8; 32] = core::array::from_fn(|i| (32 * block + i) as u8);
+            let mut expected = buf;
+            let expected_invalid =
+                sse::chars_to_digits::<&Alphabet>(expected.as_mut_ptr(), &sse_table)
+                    | sse::chars_to_digits::<&Alphabet>(
+                        unsafe { expected.as_mut_ptr().add(16) },
+                        &sse_table,
+                    ) << 16;
+            let invalid = chars_to_digits::<&Alphabet>(buf.as_mut_ptr(), &table);
+            assert_eq!(invalid, expected_invalid);
+            assert_eq!(buf, expected);
+        }
+    }
+
+    #[test]
+    fn test_raw_to_chars() {
+        let alphabet = &Alphabet::RIPPLE;
+        let table = chars_table(alphabet.as_bytes());
+        let raw: [u8; 64] = core::array::from_fn(|i| ((i * 5) % 58) as u8);
+        for k in 0..2 {
+            let mut chars = [0u8; 32];
+            wuc_st(
+                chars.as_mut_ptr(),
+                raw_to_chars::<&Alphabet>(
+                    unsafe { _mm256_loadu_si256(raw[32 * k..].as_ptr() as *const __m256i) },
+                    &table,
+                ),
+            );
+            let expected: [u8; 32] =
+                core::array::from_fn(|i| alphabet.as_bytes()[raw[32 * k + i] as usize]);
+            assert_eq!(chars, expected);
+        }
+    }
 }
@@ -0,0 +1,708 @@
+/* AVX-512 versions of the 64-byte code paths.
+
+The IFMA instructions (madd52lo/madd52hi) multiply 52-bit lanes exactly
+and add the low or high 52 bits of the 104-bit product to an
+accumulator, which covers both the 32x32 bit table products and the
+divisions by 58.  The VBMI byte permutes move digits between the
+one-term-per-ulong form and the packed 90-byte form, and do the table
+lookups between digits and characters, in one instruction each.  With
+512-bit registers, the 18 intermediate terms fit in three registers and
+the 90 raw digits in two. */
+use core::arch::x86_64::{
+    __m512i, _mm512_add_epi64, _mm512_add_epi8, _mm512_alignr_epi64, _mm512_and_si512,
+    _mm512_castsi512_si128, _mm512_cmpeq_epi8_mask, _mm512_cmpge_epi64_mask,
+    _mm512_cmplt_epi64_mask, _mm512_cvtepu64_pd, _mm512_cvttpd_epu64, _mm512_loadu_si512,
+    _mm512_madd52hi_epu64, _mm512_madd52lo_epu64, _mm512_madd_epi16, _mm512_maddubs_epi16,
+    _mm512_mask_add_epi64, _mm512_mask_mov_epi64, _mm512_mask_set1_epi64, _mm512_mask_sub_epi64,
+    _mm512_maskz_loadu_epi8, _mm512_maskz_mov_epi64, _mm512_maskz_permutex2var_epi8,
+    _mm512_maskz_permutexvar_epi8, _mm512_movepi8_mask, _mm512_mul_epu32, _mm512_mul_pd,
+    _mm512_or_si512, _mm512_permutex2var_epi8, _mm512_permutexvar_epi8, _mm512_set1_epi64,
+    _mm512_set1_epi8, _mm512_set1_pd, _mm512_setzero_si512, _mm512_shuffle_epi8, _mm512_slli_epi64,
+    _mm512_srai_epi64, _mm512_srli_epi64, _mm512_storeu_si512, _mm512_sub_epi64,
+    _mm512_test_epi64_mask, _mm512_test_epi8_mask, _mm_cvtsi128_si64,
+};
+
+use five8_core::BASE58_INVALID_CHAR;
+
+#[repr(C, align(64))]
+pub(crate) struct Aligned64<T>(pub(crate) T);
+
+#[inline(always)]
+fn ld(p: *const u8) -> __m512i {
+    unsafe { _mm512_loadu_si512(p as *const _) }
+}
+
+#[inline(always)]
+fn st(p: *mut u8, v: __m512i) {
+    unsafe { _mm512_storeu_si512(p as *mut _, v) }
+}
+
+#[inline(always)]
+fn bcast(l0: u64) -> __m512i {
+    unsafe { _mm512_set1_epi64(l0 as i64) }
+}
+
+#[inline(always)]
+fn add(a: __m512i, b: __m512i) -> __m512i {
+    unsafe { _mm512_add_epi64(a, b) }
+}
+
+/* Adds the low and the high 52 bits of the products a*b to lo and hi
+respectively, so the sum of the products is lo + hi*2^52. */
+#[inline(always)]
+fn madd52(lo: &mut __m512i, hi: &mut __m512i, a: __m512i, b: __m512i) {
+    unsafe {
+        *lo = _mm512_madd52lo_epu64(*lo, a, b);
+        *hi = _mm512_madd52hi_epu64(*hi, a, b);
+    }
+}
+
+/* Converts lo + hi*2^52 to a ulong.  The sums computed here are the same
+as the ones computed by the scalar code, which never overflow. */
+#[inline(always)]
+fn combine52(lo: __m512i, hi: __m512i) -> __m512i {
+    add(lo, unsafe { _mm512_slli_epi64::<52>(hi) })
+}
+
+/* widen_table converts a table of uints to ulongs, as needed for the
+IFMA operands, shifting each row right by SHIFT columns and padding it
+with zeros to COLS columns. */
+pub(crate) const fn widen_table<
+    const ROWS: usize,
+    const IN_COLS: usize,
+    const COLS: usize,
+    const SHIFT: usize,
+>(
+    table: &[[u32; IN_COLS]; ROWS],
+) -> Aligned64<[[u64; COLS]; ROWS]> {
+    let mut out = [[0u64; COLS]; ROWS];
+    let mut i = 0;
+    while i < ROWS {
+        let mut j = 0;
+        while j < IN_COLS {
+            out[i][j + SHIFT] = table[i][j] as u64;
+            j += 1;
+        }
+        i += 1;
+    }
+    Aligned64(out)
+}
+
+/* Returns the 16 big endian uints in the 64 bytes at p, one per uint
+lane. */
+#[inline(always)]
+pub(crate) fn bytes_to_binary_64(p: *const u8) -> __m512i {
+    unsafe { _mm512_shuffle_epi8(ld(p), ld(BSWAP32_IDX.as_ptr())) }
+}
+
+const BSWAP32_IDX: [u8; 64] = {
+    let mut idx = [0u8; 64];
+    let mut i = 0;
+    while i < 64 {
+        /* pshufb indexes within each 128-bit lane */
+        idx[i] = ((i & 12) + 3 - (i & 3)) as u8;
+        i += 1;
+    }
+    idx
+};
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_64(bytes: __m512i) -> u64 {
+    let nonzero = unsafe { _mm512_test_epi8_mask(bytes, bytes) };
+    nonzero.trailing_zeros() as u64
+}
+
+/* Column register r of row i of a table with COLS columns. */
+#[inline(always)]
+fn table_cols<const ROWS: usize, const COLS: usize>(
+    table: &Aligned64<[[u64; COLS]; ROWS]>,
+    i: usize,
+    r: usize,
+) -> __m512i {
+    ld(unsafe { (table.0.as_ptr() as *const u8).add(8 * COLS * i + 64 * r) })
+}
+
+/* binary_to_intermediate_64 computes the same thing as the loops in
+make_intermediate_array_64, including the mini-reduction of
+intermediate[16] after the first 8 rows, but with the products done 8
+at a time with IFMA.  table is ENC_TABLE_64 with each row shifted to
+the position of the intermediate term it is added to.
+
+The even and the odd rows go to separate accumulators, which halves the
+length of the dependency chains. */
+#[inline(always)]
+pub(crate) fn binary_to_intermediate_64(
+    binary: &[u32; 16],
+    table: &Aligned64<[[u64; 24]; 16]>,
+) -> [__m512i; 3] {
+    let zero = unsafe { _mm512_setzero_si512() };
+    let b = |i: usize| bcast(unsafe { *binary.get_unchecked(i) } as u64);
+    let (mut lo0_e, mut hi0_e, mut lo1_e, mut hi1_e, mut lo2_e, mut hi2_e) =
+        (zero, zero, zero, zero, zero, zero);
+    let (mut lo0_o, mut hi0_o, mut lo1_o, mut hi1_o, mut lo2_o, mut hi2_o) =
+        (zero, zero, zero, zero, zero, zero);
+    for i in (0..8).step_by(2) {
+        let (b_e, b_o) = (b(i), b(i + 1));
+        madd52(&mut lo0_e, &mut hi0_e, b_e, table_cols(table, i, 0));
+        madd52(&mut lo1_e, &mut hi1_e, b_e, table_cols(table, i, 1));
+        madd52(&mut lo2_e, &mut hi2_e, b_e, table_cols(table, i, 2));
+        madd52(&mut lo0_o, &mut hi0_o, b_o, table_cols(table, i + 1, 0));
+        madd52(&mut lo1_o, &mut hi1_o, b_o, table_cols(table, i + 1, 1));
+        madd52(&mut lo2_o, &mut hi2_o, b_o, table_cols(table, i + 1, 2));
+    }
+    let acc0 = add(combine52(lo0_e, hi0_e), combine52(lo0_o, hi0_o));
+    let mut acc1 = add(combine52(lo1_e, hi1_e), combine52(lo1_o, hi1_o));
+    let mut acc2 = add(combine52(lo2_e, hi2_e), combine52(lo2_o, hi2_o));
+    /* Mini-reduction: intermediate[16] is lane 0 of the last register,
+    and intermediate[15] is lane 7 of the one before.  58^5 is R1DIV. */
+    let term16 = unsafe { _mm_cvtsi128_si64(_mm512_castsi512_si128(acc2)) } as u64;
+    unsafe {
+        acc2 = _mm512_mask_set1_epi64(acc2, 1, (term16 % 656356768) as i64);
+        acc1 = _mm512_mask_add_epi64(acc1, 1 << 7, acc1, bcast(term16 / 656356768));
+    }
+    /* The remaining rows are zero in the first register.  The sums so far
+    can be used as the low accumulators of the even rows, since they
+    only get bigger. */
+    let (mut hi1_e, mut hi2_e) = (zero, zero);
+    let (mut lo1_o, mut hi1_o, mut lo2_o, mut hi2_o) = (zero, zero, zero, zero);
+    for i in (8..16).step_by(2) {
+        let (b_e, b_o) = (b(i), b(i + 1));
+        madd52(&mut acc1, &mut hi1_e, b_e, table_cols(table, i, 1));
+        madd52(&mut acc2, &mut hi2_e, b_e, table_cols(table, i, 2));
+        madd52(&mut lo1_o, &mut hi1_o, b_o, table_cols(table, i + 1, 1));
+        madd52(&mut lo2_o, &mut hi2_o, b_o, table_cols(table, i + 1, 2));
+    }
+    [
+        acc0,
+        add(combine52(acc1, hi1_e), combine52(lo1_o, hi1_o)),
+        add(combine52(acc2, hi2_e), combine52(lo2_o, hi2_o)),
+    ]
+}
+
+/* Divides each lane by 58^5, returning the quotient and the remainder.
+
+The quotient is first estimated in double precision.  The relative error
+of the estimate is a few ulps, so for x < 2^64 it is off from x/58^5 by
+much less than 1, and floor() is off by at most one in either
+direction, which the remainder shows and a single correction fixes.
+The remainder before the correction is in [-58^5, 2*58^5), so it can be
+computed modulo 2^52 with IFMA and sign extended. */
+#[inline(always)]
+fn divmod_r1div(x: __m512i) -> (__m512i, __m512i) {
+    unsafe {
+        let r1div = bcast(656356768);
+        let q = _mm512_cvttpd_epu64(_mm512_mul_pd(
+            _mm512_cvtepu64_pd(x),
+            _mm512_set1_pd(1.0 / 656356768.0),
+        ));
+        let r = _mm512_sub_epi64(x, _mm512_madd52lo_epu64(_mm512_setzero_si512(), q, r1div));
+        let r = _mm512_srai_epi64::<12>(_mm512_slli_epi64::<12>(r));
+        let too_high = _mm512_cmplt_epi64_mask(r, _mm512_setzero_si512());
+        let too_low = _mm512_cmpge_epi64_mask(r, r1div);
+        let one = bcast(1);
+        let q = _mm512_mask_sub_epi64(q, too_high, q, one);
+        let r = _mm512_mask_add_epi64(r, too_high, r, r1div);
+        let q = _mm512_mask_add_epi64(q, too_low, q, one);
+        let r = _mm512_mask_sub_epi64(r, too_low, r, r1div);
+        (q, r)
+    }
+}
+
+/* Same as divmod_r1div, for x < 2^36.  The quotient is
+floor(x*ceil(2^81/58^5)/2^81), which is exact in that range, and all
+the products fit in 52 bits. */
+#[inline(always)]
+fn divmod_r1div_small(x: __m512i) -> (__m512i, __m512i) {
+    unsafe {
+        let zero = _mm512_setzero_si512();
+        let q = _mm512_srli_epi64::<29>(_mm512_madd52hi_epu64(zero, x, bcast(3683746031288366)));
+        let r = _mm512_sub_epi64(x, _mm512_madd52lo_epu64(zero, q, bcast(656356768)));
+        (q, r)
+    }
+}
+
+/* Adds each quotient to the term before it, which for the first lane of
+a register is the last lane of the one before.  The first term has
+nowhere to carry to, so it is left as is.  Also returns whether there
+was anything to carry. */
+#[inline(always)]
+fn move_carries(
+    terms: [__m512i; 3],
+    [q0, q1, q2]: [__m512i; 3],
+    [r0, r1, r2]: [__m512i; 3],
+) -> ([__m512i; 3], bool) {
+    unsafe {
+        let q0 = _mm512_maskz_mov_epi64(!1, q0);
+        let r0 = _mm512_mask_mov_epi64(r0, 1, terms[0]);
+        let any = _mm512_or_si512(q0, _mm512_or_si512(q1, q2));
+        (
+            [
+                add(r0, _mm512_alignr_epi64::<1>(q1, q0)),
+                add(r1, _mm512_alignr_epi64::<1>(q2, q1)),
+                add(r2, _mm512_alignr_epi64::<1>(_mm512_setzero_si512(), q2)),
+            ],
+            _mm512_test_epi64_mask(any, any) != 0,
+        )
+    }
+}
+
+/* adjust_intermediate_64 gives the same result as
+adjust_intermediate_array, i.e. makes every term except the first less
+than 58^5 while keeping the value, but moves the carries of all the
+terms at once instead of one after the other.
+
+Since there is exactly one such representation of the value, the order
+in which the carries are moved doesn't matter.  The first round leaves
+terms below 58^5 + 2^35, the second below 58^5 + 2^6, and after that
+the carries are almost always 0.  The last 6 lanes are past the end and
+stay zero. */
+#[inline(always)]
+pub(crate) fn adjust_intermediate_64(terms: [__m512i; 3]) -> [__m512i; 3] {
+    let (q0, r0) = divmod_r1div(terms[0]);
+    let (q1, r1) = divmod_r1div(terms[1]);
+    let (q2, r2) = divmod_r1div(terms[2]);
+    let (mut terms, _) = move_carries(terms, [q0, q1, q2], [r0, r1, r2]);
+    loop {
+        let (q0, r0) = divmod_r1div_small(terms[0]);
+        let (q1, r1) = divmod_r1div_small(terms[1]);
+        let (q2, r2) = divmod_r1div_small(terms[2]);
+        let (moved, any) = move_carries(terms, [q0, q1, q2], [r0, r1, r2]);
+        if !any {
+            return terms;
+        }
+        terms = moved;
+    }
+}
+
+/* intermediate_to_raw converts each term (< 58^5) of the intermediate
+form to its 5 base58 digits, stored most significant first in bytes 0
+to 4 of the ulong, with bytes 5 to 7 zero.
+
+The quotients q_k = floor(x/58^k) are madd52hi's with ceil(2^52/58^k).
+For x < 2^30, the result exceeds x/58^k by less than 2^-22, which isn't
+enough to reach the next integer for k <= 3, so these are exact.  q_4
+is computed from q_1 the same way.  With q_0 = x, digit k is
+q_(4-k) - 58*q_(5-k), and collecting the terms of each q in
+  d0 + d1<<8 + d2<<16 + d3<<24 + d4<<32
+gives x<<32 plus a multiple of each of q_1 to q_4.  The result is less
+than 2^40, so these can be summed modulo 2^52 with madd52lo. */
+#[inline(always)]
+pub(crate) fn intermediate_to_raw(terms: __m512i) -> __m512i {
+    unsafe {
+        let zero = _mm512_setzero_si512();
+        let q1 = _mm512_madd52hi_epu64(zero, terms, bcast(77648269437423));
+        let q2 = _mm512_madd52hi_epu64(zero, terms, bcast(1338763266163));
+        let q3 = _mm512_madd52hi_epu64(zero, terms, bcast(23082125279));
+        let q4 = _mm512_madd52hi_epu64(zero, q1, bcast(23082125279));
+        /* (2^(32-8k) - 58*2^(40-8k)) mod 2^52 */
+        let lo = _mm512_madd52lo_epu64(_mm512_slli_epi64::<32>(terms), q1, bcast(4503350536044544));
+        let lo = _mm512_madd52lo_epu64(lo, q2, bcast(4503598654357504));
+        let hi = _mm512_madd52lo_epu64(zero, q3, bcast(4503599623569664));
+        let hi = _mm512_madd52lo_epu64(hi, q4, bcast(4503599627355649));
+        _mm512_and_si512(add(lo, hi), bcast((1 << 40) - 1))
+    }
+}
+
+/* compact_idx gives the byte permute that packs the 5 digits in each
+ulong of raw (as returned by intermediate_to_raw) into bytes FIRST_OUT
+to FIRST_OUT+63 of the packed 90-digit form, reading from registers
+starting at byte FIRST_SRC.  Bytes past the 90th are taken from byte 5
+of the first register, which is always zero. */
+const fn compact_idx(first_out: usize, first_src: usize) -> [u8; 64] {
+    let mut idx = [0u8; 64];
+    let mut i = 0;
+    while i < 64 {
+        let o = first_out + i;
+        idx[i] = if o < 90 {
+            (8 * (o / 5) + o % 5 - first_src) as u8
+        } else {
+            5
+        };
+        i += 1;
+    }
+    idx
+}
+
+const COMPACT_IDX_0: [u8; 64] = compact_idx(0, 0);
+const COMPACT_IDX_1: [u8; 64] = compact_idx(64, 64);
+
+/* ten_per_slot_down_64 in avx.rs does this with a series of shifts and
+blends.  Here each output register is a single two-source permute: the
+first 64 digits come from terms 0 to 12, in raw0 and raw1, and the last
+26 from terms 12 to 17, in raw1 and raw2.  The remaining 38 bytes of the
+second register are zero. */
+#[inline(always)]
+pub(crate) fn compact_raw_90(raw: [__m512i; 3]) -> (__m512i, __m512i) {
+    unsafe {
+        (
+            _mm512_permutex2var_epi8(raw[0], ld(COMPACT_IDX_0.as_ptr()), raw[1]),
+            _mm512_permutex2var_epi8(raw[1], ld(COMPACT_IDX_1.as_ptr()), raw[2]),
+        )
+    }
+}
+
+/* Counts the leading zero digits of the packed 90-digit form. */
+#[inline(always)]
+pub(crate) fn count_leading_zeros_90(raw0: __m512i, raw1: __m512i) -> u64 {
+    let nonzero0 = unsafe { _mm512_test_epi8_mask(raw0, raw0) };
+    /* bit 26 caps the count at 90 */
+    let nonzero1 = unsafe { _mm512_test_epi8_mask(raw1, raw1) } & ((1 << 26) - 1) | (1 << 26);
+    if nonzero0 != 0 {
+        nonzero0.trailing_zeros() as u64
+    } else {
+        64 + nonzero1.trailing_zeros() as u64
+    }
+}
+
+const SHIFT_IDX: [u8; 64] = {
+    let mut idx = [0u8; 64];
+    let mut i = 0;
+    while i < 64 {
+        idx[i] = i as u8;
+        i += 1;
+    }
+    idx
+};
+
+/* shift_down_90 moves the packed 90-byte form down by skip < 64 bytes
+across both registers, so that byte skip comes first.  The top skip
+bytes of the second register are left over. */
+#[inline(always)]
+pub(crate) fn shift_down_90(lo: __m512i, hi: __m512i, skip: usize) -> (__m512i, __m512i) {
+    unsafe {
+        let idx = _mm512_add_epi8(ld(SHIFT_IDX.as_ptr()), _mm512_set1_epi8(skip as i8));
+        (
+            _mm512_permutex2var_epi8(lo, idx, hi),
+            _mm512_permutexvar_epi8(idx, hi),
+        )
+    }
+}
+
+/* chars_table loads an alphabet's 58 characters into a register for
+raw_to_base58, zero padded.  The masked load doesn't read past them. */
+#[inline(always)]
+pub(crate) fn chars_table(chars: &[u8; 58]) -> __m512i {
+    unsafe { _mm512_maskz_loadu_epi8((1 << 58) - 1, chars.as_ptr() as *const i8) }
+}
+
+/* raw_to_base58 maps each digit (< 58) to its character with a single
+64-entry table lookup, for any alphabet. */
+#[inline(always)]
+pub(crate) fn raw_to_base58(raw: __m512i, chars: __m512i) -> __m512i {
+    unsafe { _mm512_permutexvar_epi8(raw, chars) }
+}
+
+/* chars_to_raw_90 loads the char_cnt characters of encoded right-aligned
+into the packed 90-digit form and converts them to digits with an
+alphabet's 128-entry decode table, with zeros in front.  It returns the
+digits, and a mask with bit i set if position i of the packed form has a
+character that is not in the alphabet.
+
+The masked loads don't touch the bytes outside of encoded, so the load
+address can be before its start. */
+#[inline(always)]
+pub(crate) fn chars_to_raw_90(
+    encoded: &[u8],
+    char_cnt: usize,
+    decode: &[u8; 128],
+) -> (__m512i, __m512i, u128) {
+    let prepend_0 = 90 - char_cnt;
+    let mask = ((1u128 << char_cnt) - 1) << prepend_0;
+    let (mask0, mask1) = (mask as u64, (mask >> 64) as u64);
+    let base = encoded.as_ptr().wrapping_sub(prepend_0) as *const i8;
+    let table_lo = ld(decode.as_ptr());
+    let table_hi = ld(unsafe { decode.as_ptr().add(64) });
+    let invalid_digit = unsafe { _mm512_set1_epi8(BASE58_INVALID_CHAR as i8) };
+    let lookup = |m: u64, p: *const i8| unsafe {
+        let chars = _mm512_maskz_loadu_epi8(m, p);
+        let digits = _mm512_maskz_permutex2var_epi8(m, table_lo, chars, table_hi);
+        /* The lookup only uses the low 7 bits, so the bytes >= 0x80 need
+        their own check. */
+        let invalid =
+            (_mm512_cmpeq_epi8_mask(digits, invalid_digit) | _mm512_movepi8_mask(chars)) & m;
+        (digits, invalid)
+    };
+    let (raw0, invalid0) = lookup(mask0, base);
+    let (raw1, invalid1) = lookup(mask1, base.wrapping_add(64));
+    (raw0, raw1, invalid0 as u128 | (invalid1 as u128) << 64)
+}
+
+/* expand_idx gives the byte permute that spreads the packed digits of
+terms FIRST_TERM to FIRST_TERM+7 to one term per ulong, in bytes 0 to 4,
+reading from registers starting at digit FIRST_SRC.  Bytes 5 to 7 are
+zeroed with EXPAND_MASK. */
+const fn expand_idx(first_term: usize, first_src: usize) -> [u8; 64] {
+    let mut idx = [0u8; 64];
+    let mut i = 0;
+    while i < 64 {
+        let digit = 5 * (first_term + i / 8) + i % 8;
+        idx[i] = if i % 8 < 5 && digit < 90 {
+            (digit - first_src) as u8
+        } else {
+            0
+        };
+        i += 1;
+    }
+    idx
+}
+
+const EXPAND_IDX_0: [u8; 64] = expand_idx(0, 0);
+const EXPAND_IDX_1: [u8; 64] = expand_idx(8, 0);
+const EXPAND_IDX_2: [u8; 64] = expand_idx(16, 64);
+const EXPAND_MASK: u64 = 0x1f1f1f1f1f1f1f1f;
+
+/* expand_raw_90 is the inverse of compact_raw_90, except that the terms
+past the 18th are zero. */
+#[inline(always)]
+pub(crate) fn expand_raw_90(raw0: __m512i, raw1: __m512i) -> [__m512i; 3] {
+    unsafe {
+        [
+            _mm512_maskz_permutexvar_epi8(EXPAND_MASK, ld(EXPAND_IDX_0.as_ptr()), raw0),
+            _mm512_maskz_permutex2var_epi8(EXPAND_MASK, raw0, ld(EXPAND_IDX_1.as_ptr()), raw1),
+            _mm512_maskz_permutexvar_epi8(EXPAND_MASK & 0xffff, ld(EXPAND_IDX_2.as_ptr()), raw1),
+        ]
+    }
+}
+
+/* raw_to_intermediate is the inverse of intermediate_to_raw, done the
+same way as in avx.rs:
+  maddubs: [ 58*d0+d1, 58*d2+d3, d4, 0 ]
+  madd:    [ 58^2*(58*d0+d1) + 58*d2+d3, d4 ]
+and a final 32x32->64 multiply by 58 plus d4. */
+#[inline(always)]
+pub(crate) fn raw_to_intermediate(raw: __m512i) -> __m512i {
+    unsafe {
+        let pairs = _mm512_maddubs_epi16(raw, _mm512_set1_epi64(0x0000_0001_013a_013a));
+        let quads = _mm512_madd_epi16(pairs, _mm512_set1_epi64(0x0000_0001_0001_0d24));
+        add(
+            _mm512_mul_epu32(quads, bcast(58)),
+            _mm512_srli_epi64::<32>(quads),
+        )
+    }
+}
+
+/* intermediate_to_binary_64 is the AVX-512 version of the product with
+DEC_TABLE_64 in base58_decode_before_be_convert, done 8 columns at a
+time with IFMA, with separate accumulators for the even and the odd
+rows as in binary_to_intermediate_64.  Rows 9 and up are zero in the
+first 8 columns. */
+#[inline(always)]
+pub(crate) fn intermediate_to_binary_64(
+    intermediate: &[u64; 24],
+    table: &Aligned64<[[u64; 16]; 18]>,
+) -> [u64; 16] {
+    let zero = unsafe { _mm512_setzero_si512() };
+    let t = |i: usize| bcast(unsafe { *intermediate.get_unchecked(i) });
+    let (mut lo0_e, mut hi0_e, mut lo1_e, mut hi1_e) = (zero, zero, zero, zero);
+    let (mut lo0_o, mut hi0_o, mut lo1_o, mut hi1_o) = (zero, zero, zero, zero);
+    for i in (0..8).step_by(2) {
+        let (t_e, t_o) = (t(i), t(i + 1));
+        madd52(&mut lo0_e, &mut hi0_e, t_e, table_cols(table, i, 0));
+        madd52(&mut lo1_e, &mut hi1_e, t_e, table_cols(table, i, 1));
+        madd52(&mut lo0_o, &mut hi0_o, t_o, table_cols(table, i + 1, 0));
+        madd52(&mut lo1_o, &mut hi1_o, t_o, table_cols(table, i + 1, 1));
+    }
+    let t_8 = t(8);
+    madd52(&mut lo0_e, &mut hi0_e, t_8, table_cols(table, 8, 0));
+    madd52(&mut lo1_e, &mut hi1_e, t_8, table_cols(table, 8, 1));
+    for i in (9..17).step_by(2) {
+        let (t_o, t_e) = (t(i), t(i + 1));
+        madd52(&mut lo1_o, &mut hi1_o, t_o, table_cols(table, i, 1));
+        madd52(&mut lo1_e, &mut hi1_e, t_e, table_cols(table, i + 1, 1));
+    }
+    let t_17 = t(17);
+    madd52(&mut lo1_o, &mut hi1_o, t_17, table_cols(table, 17, 1));
+    let mut binary = [0u64; 16];
+    let p = binary.as_mut_ptr() as *mut u8;
+    st(p, add(combine52(lo0_e, hi0_e), combine52(lo0_o, hi0_o)));
+    st(
+        unsafe { p.add(64) },
+        add(combine52(lo1_e, hi1_e), combine52(lo1_o, hi1_o)),
+    );
+    binary
+}
+
+/* Stores the three registers of terms to an array. */
+#[inline(always)]
+pub(crate) fn store_terms(terms: [__m512i; 3]) -> [u64; 24] {
+    let mut out = [0u64; 24];
+    let p = out.as_mut_ptr() as *mut u8;
+    st(p, terms[0]);
+    st(unsafe { p.add(64) }, terms[1]);
+    st(unsafe { p.add(128) }, terms[2]);
+    out
+}
+
+#[inline(always)]
+pub(crate) fn store_u32s(out: &mut [u32; 16], v: __m512i) {
+    st(out.as_mut_ptr() as *mut u8, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use five8_core::{Alphabet, DEC_TABLE_64};
+
+    /* Loads an array of terms into three registers. */
+    fn load_terms(terms: &[u64; 24]) -> [__m512i; 3] {
+        let p = terms.as_ptr() as *const u8;
+        [ld(p), ld(unsafe { p.add(64) }), ld(unsafe { p.add(128) })]
+    }
+
+    /* The tests use the instructions directly, so they are skipped on
+    CPUs without them. */
+    fn available() -> bool {
+        crate::cpu::avx512_available()
+    }
+
+    #[test]
+    fn test_intermediate_to_raw() {
+        if !available() {
+            return;
+        }
+        let mut terms = [0u64; 24];
+        let samples = [0u64, 1, 57, 58, 3363, 3364, 195111, 11316495, 656356767];
+        for (i, term) in terms.iter_mut().enumerate() {
+            *term = (samples[i % samples.len()] + i as u64) % 656356768;
+        }
+        let raw = store_terms(load_terms(&terms).map(intermediate_to_raw));
+        for (term, raw) in terms.iter().zip(raw) {
+            let mut expected = 0u64;
+            let mut rest = *term;
+            for k in (0..5).rev() {
+                expected |= (rest % 58) << (8 * k);
+                rest /= 58;
+            }
+            assert_eq!(raw, expected, "{term}");
+        }
+        let round_trip = store_terms(load_terms(&raw_terms(&terms)).map(raw_to_intermediate));
+        assert_eq!(round_trip, terms);
+    }
+
+    fn raw_terms(terms: &[u64; 24]) -> [u64; 24] {
+        store_terms(load_terms(terms).map(intermediate_to_raw))
+    }
+
+    #[test]
+    fn test_compact_expand_raw_90() {
+        if !available() {
+            return;
+        }
+        let mut terms = [0u64; 24];
+        for (i, term) in terms.iter_mut().take(18).enumerate() {
+            *term = 36_000_000 * i as u64 + 12345;
+        }
+        let raw = raw_terms(&terms);
+        let (raw0, raw1) = compact_raw_90(load_terms(&raw));
+        let mut packed = [0u8; 128];
+        st(packed.as_mut_ptr(), raw0);
+        st(unsafe { packed.as_mut_ptr().add(64) }, raw1);
+        for (i, digit) in packed.iter().enumerate() {
+            let expected = if i < 90 {
+                (raw[i / 5] >> (8 * (i % 5))) as u8
+            } else {
+                0
+            };
+            assert_eq!(*digit, expected, "{i}");
+        }
+        assert_eq!(store_terms(expand_raw_90(raw0, raw1)), raw);
+    }
+
+    #[test]
+    fn test_count_leading_zeros_90() {
+        if !available() {
+            return;
+        }
+        for leading_zeros in 0..=90 {
+            let mut packed = [0u8; 128];
+            packed[leading_zeros..90].fill(1);
+            let raw0 = ld(packed.as_ptr());
+            let raw1 = ld(unsafe { packed.as_ptr().add(64) });
+            assert_eq!(count_leading_zeros_90(raw0, raw1), leading_zeros as u64);
+        }
+    }
+
+    #[test]
+    fn test_chars_to_raw_90() {
+        if !available() {
+            return;
+        }
+        let chars: [u8; 128] = core::array::from_fn(|i| i as u8 + 128 * (i % 3 == 0) as u8);
+        for alphabet in [Alphabet::BITCOIN, Alphabet::RIPPLE] {
+            for start in (0..128).step_by(40) {
+                let encoded = &chars[start..(start + 89).min(128)];
+                let (raw0, raw1, invalid) =
+                    chars_to_raw_90(encoded, encoded.len(), alphabet.decode_table());
+                let mut packed = [0u8; 128];
+                st(packed.as_mut_ptr(), raw0);
+                st(unsafe { packed.as_mut_ptr().add(64) }, raw1);
+                let prepend_0 = 90 - encoded.len();
+                assert_eq!(invalid & ((1 << prepend_0) - 1), 0);
+                assert!(packed[..prepend_0].iter().all(|d| *d == 0));
+                for (i, c) in encoded.iter().enumerate() {
+                    let pos = prepend_0 + i;
+                    match alphabet.digit(*c) {
+                        None => assert_ne!(invalid & (1 << pos), 0, "{c}"),
+                        Some(digit) => {
+                            assert_eq!(invalid & (1 << pos), 0, "{c}");
+                            assert_eq!(packed[pos], digit, "{c}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_intermediate_to_binary_64() {
+        if !available() {
+            return;
+        }
+        let table = widen_table::<18, 16, 16, 0>(&DEC_TABLE_64);
+        let mut intermediate = [0u64; 24];
+        for (i, term) in intermediate.iter_mut().take(18).enumerate() {
+            *term = 656356767 - 1000 * i as u64;
+        }
+        let mut expected = [0u64; 16];
+        for (i, term) in intermediate.iter().take(18).enumerate() {
+            for (j, out) in expected.iter_mut().enumerate() {
+                *out += term * DEC_TABLE_64[i][j] as u64;
+            }
+        }
+        assert_eq!(intermediate_to_binary_64(&intermediate, &table), expected);
+    }
+
+    fn adjust_intermediate_scalar(terms: &mut [u64; 24]) {
+        for i in (1..18).rev() {
+            terms[i - 1] += terms[i] / 656356768;
+            terms[i] %= 656356768;
+        }
+    }
+
+    #[test]
+    fn test_adjust_intermediate_64() {
+        if !available() {
+            return;
+        }
+        let mut x = 0x1234_5678_9abc_def0u64;
+        let mut cases = [[0u64; 24]; 4];
+        for term in cases[0].iter_mut().take(18) {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            *term = x >> 1;
+        }
+        cases[1][..18].fill(u64::MAX - (u64::MAX >> 6));
+        /* every carry ripples all the way to the first term */
+        cases[2][..18].fill(656356767);
+        cases[2][17] = 656356768;
+        cases[3][..18].fill(656356768 * 656356768 - 1);
+        for case in cases {
+            let mut expected = case;
+            adjust_intermediate_scalar(&mut expected);
+            let adjusted = store_terms(adjust_intermediate_64(load_terms(&case)));
+            assert_eq!(adjusted, expected);
+        }
+    }
+}
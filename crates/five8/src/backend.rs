@@ -0,0 +1,216 @@
+use core::fmt;
+
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+use crate::cpu::avx512_available;
+#[cfg(target_arch = "x86_64")]
+use crate::cpu::{avx2_available, ssse3_available};
+
+/// An implementation of the encoding and decoding functions.
+///
+/// [`encode_32`](crate::encode_32) and the other functions pick the fastest
+/// backend available on the current CPU, which [`active_backend`] reports.
+/// The `*_with` functions, e.g. [`encode_32_with`](crate::encode_32_with),
+/// run a specific one instead.
+///
+/// Every variant exists on every target, but only the ones for the target
+/// architecture (and the enabled crate features) can be available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Plain Rust code, available everywhere.
+    Scalar,
+    /// 128-bit SSSE3 code, on x86_64.
+    Ssse3,
+    /// AVX2 code, on x86_64.
+    Avx2,
+    /// AVX-512 code, on x86_64 with the `avx512` feature and a CPU with the
+    /// F, BW, DQ, IFMA and VBMI extensions. Only `encode_64` and `decode_64`
+    /// have AVX-512 versions; the 32-byte functions use the AVX2 code.
+    Avx512,
+    /// NEON code, on aarch64.
+    Neon,
+}
+
+impl Backend {
+    /// All the backends. When several are available, the later one is
+    /// faster.
+    pub const ALL: &'static [Backend] = &[
+        Backend::Scalar,
+        Backend::Ssse3,
+        Backend::Avx2,
+        Backend::Avx512,
+        Backend::Neon,
+    ];
+
+    /// Whether the backend can be used in this build on the current CPU.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use five8::Backend;
+    ///
+    /// assert!(Backend::Scalar.is_available());
+    /// for backend in Backend::ALL.iter().filter(|b| b.is_available()) {
+    ///     let mut out = [0u8; 44];
+    ///     let len = five8::encode_32_with(*backend, &[0; 32], &mut out);
+    ///     assert_eq!(&out[..len as usize], b"11111111111111111111111111111111");
+    /// }
+    /// ```
+    #[inline]
+    pub fn is_available(self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            Backend::Ssse3 => ssse3_available(),
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => avx2_available(),
+            #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+            Backend::Avx512 => avx512_available(),
+            #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+            Backend::Neon => true,
+            _ => false,
+        }
+    }
+
+    /// A short lowercase name for the backend, e.g. `"avx2"`. This is also
+    /// what the [`Display`](fmt::Display) implementation prints.
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Scalar => "scalar",
+            Backend::Ssse3 => "ssse3",
+            Backend::Avx2 => "avx2",
+            Backend::Avx512 => "avx512",
+            Backend::Neon => "neon",
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The backend that [`encode_64`](crate::encode_64) and
+/// [`decode_64`](crate::decode_64) use in this build on the current CPU.
+///
+/// The 32-byte functions use the same backend, except that they use
+/// [`Backend::Avx2`] when this is [`Backend::Avx512`].
+///
+/// # Examples
+///
+/// ```
+/// let backend = five8::active_backend();
+/// assert!(backend.is_available());
+/// println!("five8 backend: {backend}");
+/// ```
+#[inline]
+pub fn active_backend() -> Backend {
+    Backend::ALL
+        .iter()
+        .rev()
+        .copied()
+        .find(|backend| backend.is_available())
+        .unwrap_or(Backend::Scalar)
+}
+
+#[inline(always)]
+pub(crate) fn assert_available(backend: Backend) {
+    assert!(
+        backend.is_available(),
+        "the {backend} backend is not available on this CPU or in this build"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode_32, decode_32_with, decode_64, decode_64_with, encode_32, encode_32_with, encode_64,
+        encode_64_with, DecodeError,
+    };
+    #[cfg(not(miri))]
+    use prop::array::uniform32;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::string::ToString;
+
+    #[test]
+    fn test_active_backend() {
+        let active = active_backend();
+        assert!(active.is_available());
+        #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+        if std::is_x86_feature_detected!("avx2") {
+            assert!(matches!(active, Backend::Avx512 | Backend::Avx2));
+        }
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        assert_eq!(active, Backend::Neon);
+        assert_eq!(Backend::Avx512.to_string(), "avx512");
+    }
+
+    fn check_backends_match(bytes: &[u8; 64]) {
+        let bytes_32: &[u8; 32] = bytes[..32].try_into().unwrap();
+        let mut expected_32 = [0u8; 44];
+        let expected_len_32 = encode_32(bytes_32, &mut expected_32);
+        let encoded_32 = &expected_32[..expected_len_32 as usize];
+        let mut expected_64 = [0u8; 88];
+        let expected_len_64 = encode_64(bytes, &mut expected_64);
+        let encoded_64 = &expected_64[..expected_len_64 as usize];
+        let mut bad = encoded_64.to_vec();
+        bad[10] = b'0';
+        let expected_err = decode_64(&bad, &mut [0u8; 64]);
+        assert_eq!(expected_err, Err(DecodeError::InvalidChar(b'0')));
+        for backend in Backend::ALL.iter().copied().filter(|b| b.is_available()) {
+            let mut out = [0u8; 44];
+            let len = encode_32_with(backend, bytes_32, &mut out);
+            assert_eq!(&out[..len as usize], encoded_32, "{backend}");
+            let mut out = [0u8; 88];
+            let len = encode_64_with(backend, bytes, &mut out);
+            assert_eq!(&out[..len as usize], encoded_64, "{backend}");
+            let mut decoded = [0u8; 32];
+            decode_32_with(backend, encoded_32, &mut decoded).unwrap();
+            assert_eq!(&decoded, bytes_32, "{backend}");
+            let mut decoded = [0u8; 64];
+            decode_64_with(backend, encoded_64, &mut decoded).unwrap();
+            assert_eq!(&decoded, bytes, "{backend}");
+            assert_eq!(decode_64_with(backend, &bad, &mut decoded), expected_err);
+            assert_eq!(
+                decode_32_with(backend, &bad, &mut [0u8; 32]),
+                decode_32(&bad, &mut [0; 32])
+            );
+        }
+    }
+
+    #[test]
+    fn test_backends_match() {
+        check_backends_match(&[0u8; 64]);
+        check_backends_match(&[255u8; 64]);
+        let mut bytes = [0u8; 64];
+        bytes[3] = 1;
+        bytes[40] = 7;
+        check_backends_match(&bytes);
+    }
+
+    #[test]
+    #[should_panic(expected = "not available")]
+    fn test_unavailable_backend_panics() {
+        let unavailable = Backend::ALL
+            .iter()
+            .copied()
+            .find(|b| !b.is_available())
+            .unwrap();
+        encode_32_with(unavailable, &[0; 32], &mut [0; 44]);
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_backends_match(first_half in uniform32(0u8..), second_half in uniform32(0u8..)) {
+            let mut combined = [0u8; 64];
+            combined[..32].copy_from_slice(&first_half);
+            combined[32..].copy_from_slice(&second_half);
+            check_backends_match(&combined);
+        }
+    }
+}
@@ -0,0 +1,293 @@
+/* Radix conversion for long inputs to encode_to_string and decode_to_vec.
+
+Numbers are little-endian Vecs of u32 digits in radix BASE, which is
+either 2^32 (the binary form) or 58^5 (the intermediate form of encode.rs
+and decode.rs, five base58 digits per term). Converting one digit at a
+time is quadratic, so above CONVERT_THRESHOLD digits the conversion splits
+the input in two, converts the halves and joins them as hi * FROM^k + lo,
+with the multiplication done in the target radix. With Karatsuba
+multiplication this is O(n^1.58). The powers FROM^k are squares of each
+other, so they are computed once per call and used at every level. */
+
+use alloc::{vec, vec::Vec};
+
+use crate::encode::R1DIV;
+
+pub(crate) const BINARY: u64 = 1 << 32;
+pub(crate) const INTERMEDIATE: u64 = R1DIV;
+
+/* Inputs with at most this many digits (about 512 bytes) are converted one
+digit at a time */
+const CONVERT_THRESHOLD: usize = 128;
+/* Products where either side has fewer digits use schoolbook
+multiplication */
+const KARATSUBA_THRESHOLD: usize = 64;
+/* A product of two 58^5 digits is less than 2^58.6, so 32 rows of them can
+be added to a normalized digit without overflowing a u64 */
+const ROWS_PER_REDUCTION: usize = 32;
+
+/// Converts `src`, little-endian digits in radix `FROM`, to little-endian
+/// digits in radix `TO` with no leading (high) zeros.
+pub(crate) fn convert<const FROM: u64, const TO: u64>(src: &[u32]) -> Vec<u32> {
+    if src.len() <= CONVERT_THRESHOLD {
+        return convert_small::<FROM, TO>(src);
+    }
+    /* powers[k] is FROM^(CONVERT_THRESHOLD * 2^k) */
+    let mut powers = Vec::new();
+    let mut power_len = CONVERT_THRESHOLD;
+    let mut one = vec![0u32; CONVERT_THRESHOLD + 1];
+    one[CONVERT_THRESHOLD] = 1;
+    powers.push(convert_small::<FROM, TO>(&one));
+    while 2 * power_len < src.len() {
+        let last = powers.last().unwrap();
+        powers.push(mul::<TO>(last, last));
+        power_len *= 2;
+    }
+    convert_rec::<FROM, TO>(src, &powers)
+}
+
+fn convert_rec<const FROM: u64, const TO: u64>(src: &[u32], powers: &[Vec<u32>]) -> Vec<u32> {
+    if src.len() <= CONVERT_THRESHOLD {
+        return convert_small::<FROM, TO>(src);
+    }
+    /* Split at the largest CONVERT_THRESHOLD * 2^k below the length */
+    let mut k = 0;
+    while CONVERT_THRESHOLD << (k + 1) < src.len() {
+        k += 1;
+    }
+    let (lo, hi) = src.split_at(CONVERT_THRESHOLD << k);
+    let lo = convert_rec::<FROM, TO>(lo, &powers[..k]);
+    let hi = convert_rec::<FROM, TO>(hi, &powers[..k]);
+    let mut out = mul::<TO>(&hi, &powers[k]);
+    if out.len() < lo.len() {
+        out.resize(lo.len(), 0);
+    }
+    add_at::<TO>(&mut out, &lo, 0);
+    trim(&mut out);
+    out
+}
+
+/* Horner's method, one source digit at a time. Each digit is less than
+TO and the carry is less than FROM + 1, so x < 2^62. */
+fn convert_small<const FROM: u64, const TO: u64>(src: &[u32]) -> Vec<u32> {
+    let mut out: Vec<u32> = Vec::with_capacity(src.len() + 1);
+    for digit in src.iter().rev() {
+        let mut carry = *digit as u64;
+        for d in out.iter_mut() {
+            let x = *d as u64 * FROM + carry;
+            *d = (x % TO) as u32;
+            carry = x / TO;
+        }
+        while carry != 0 {
+            out.push((carry % TO) as u32);
+            carry /= TO;
+        }
+    }
+    out
+}
+
+fn trim(a: &mut Vec<u32>) {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+}
+
+/* a[offset..] += b. The sum must fit in a. */
+fn add_at<const BASE: u64>(a: &mut [u32], b: &[u32], offset: usize) {
+    let mut carry = 0u64;
+    let mut i = offset;
+    for d in b {
+        let s = a[i] as u64 + *d as u64 + carry;
+        carry = (s >= BASE) as u64;
+        a[i] = (s - carry * BASE) as u32;
+        i += 1;
+    }
+    while carry != 0 {
+        let s = a[i] as u64 + 1;
+        carry = (s >= BASE) as u64;
+        a[i] = (s - carry * BASE) as u32;
+        i += 1;
+    }
+}
+
+/* a -= b. a must be at least b. */
+fn sub_in_place<const BASE: u64>(a: &mut [u32], b: &[u32]) {
+    let mut borrow = 0u64;
+    let mut i = 0;
+    for d in b {
+        let s = (a[i] as u64 + BASE) - *d as u64 - borrow;
+        borrow = (s < BASE) as u64;
+        a[i] = (s - (1 - borrow) * BASE) as u32;
+        i += 1;
+    }
+    while borrow != 0 {
+        let s = a[i] as u64 + BASE - 1;
+        borrow = (s < BASE) as u64;
+        a[i] = (s - (1 - borrow) * BASE) as u32;
+        i += 1;
+    }
+}
+
+fn sum<const BASE: u64>(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    out.extend_from_slice(long);
+    out.push(0);
+    add_at::<BASE>(&mut out, short, 0);
+    trim(&mut out);
+    out
+}
+
+/// The product of `a` and `b`, with no leading zeros.
+pub(crate) fn mul<const BASE: u64>(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_schoolbook::<BASE>(a, b);
+    }
+    /* a*b = z2 * BASE^2m + z1 * BASE^m + z0 */
+    let m = a.len().max(b.len()) / 2;
+    let (a0, a1) = a.split_at(m.min(a.len()));
+    let (b0, b1) = b.split_at(m.min(b.len()));
+    let z0 = mul::<BASE>(a0, b0);
+    let z2 = mul::<BASE>(a1, b1);
+    let mut z1 = mul::<BASE>(&sum::<BASE>(a0, a1), &sum::<BASE>(b0, b1));
+    sub_in_place::<BASE>(&mut z1, &z0);
+    sub_in_place::<BASE>(&mut z1, &z2);
+    trim(&mut z1);
+
+    let mut out = vec![0u32; a.len() + b.len()];
+    out[..z0.len()].copy_from_slice(&z0);
+    add_at::<BASE>(&mut out, &z1, m);
+    add_at::<BASE>(&mut out, &z2, 2 * m);
+    trim(&mut out);
+    out
+}
+
+fn mul_schoolbook<const BASE: u64>(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = vec![0u32; a.len() + b.len()];
+    if BASE == BINARY {
+        /* With u64 limbs there are a quarter as many products, and
+        x*y + 2 * (2^64 - 1) fits in a u128 */
+        let a = to_u64_limbs(a);
+        let b = to_u64_limbs(b);
+        let mut wide = vec![0u64; a.len() + b.len()];
+        for (i, x) in a.iter().enumerate() {
+            let mut carry = 0u128;
+            for (o, y) in wide[i..].iter_mut().zip(&b) {
+                let t = *o as u128 + *x as u128 * *y as u128 + carry;
+                *o = t as u64;
+                carry = t >> 64;
+            }
+            wide[i + b.len()] = carry as u64;
+        }
+        for (o, w) in out.chunks_mut(2).zip(&wide) {
+            o[0] = *w as u32;
+            if let Some(o) = o.get_mut(1) {
+                *o = (*w >> 32) as u32;
+            }
+        }
+    } else {
+        /* Add up the products without carrying and reduce the sums every
+        ROWS_PER_REDUCTION rows */
+        let mut acc = vec![0u64; a.len() + b.len()];
+        for (rows, a) in a.chunks(ROWS_PER_REDUCTION).enumerate() {
+            for (i, x) in a.iter().enumerate() {
+                let row = rows * ROWS_PER_REDUCTION + i;
+                for (s, y) in acc[row..].iter_mut().zip(b) {
+                    *s += *x as u64 * *y as u64;
+                }
+            }
+            let mut carry = 0u64;
+            for s in acc.iter_mut() {
+                let t = *s + carry;
+                *s = t % BASE;
+                carry = t / BASE;
+            }
+        }
+        for (o, s) in out.iter_mut().zip(&acc) {
+            *o = *s as u32;
+        }
+    }
+    trim(&mut out);
+    out
+}
+
+fn to_u64_limbs(a: &[u32]) -> Vec<u64> {
+    a.chunks(2)
+        .map(|l| l[0] as u64 | (*l.get(1).unwrap_or(&0) as u64) << 32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+
+    fn digits(len: usize, base: u64, seed: u64) -> Vec<u32> {
+        let mut x = seed | 1;
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                (x % base) as u32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mul_matches_schoolbook() {
+        for (la, lb) in [
+            (64, 64),
+            (65, 200),
+            (200, 65),
+            (300, 301),
+            (63, 900),
+            (900, 128),
+        ] {
+            for base in [BINARY, INTERMEDIATE] {
+                let a = digits(la, base, la as u64);
+                let b = digits(lb, base, lb as u64 + 7);
+                if base == BINARY {
+                    assert_eq!(mul::<BINARY>(&a, &b), mul_schoolbook::<BINARY>(&a, &b));
+                } else {
+                    assert_eq!(
+                        mul::<INTERMEDIATE>(&a, &b),
+                        mul_schoolbook::<INTERMEDIATE>(&a, &b)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_matches_small() {
+        for len in [0, 1, 128, 129, 256, 257, 600, 2000] {
+            let binary = digits(len, BINARY, len as u64);
+            let mut expected = convert_small::<BINARY, INTERMEDIATE>(&binary);
+            assert_eq!(convert::<BINARY, INTERMEDIATE>(&binary), expected);
+            let mut intermediate = digits(len, INTERMEDIATE, len as u64);
+            /* High zero digits */
+            intermediate.extend_from_slice(&[0; 3]);
+            expected = convert_small::<INTERMEDIATE, BINARY>(&intermediate);
+            assert_eq!(convert::<INTERMEDIATE, BINARY>(&intermediate), expected);
+            assert_eq!(convert::<BINARY, INTERMEDIATE>(&expected), {
+                let mut i = intermediate.clone();
+                trim(&mut i);
+                i
+            });
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_convert_roundtrip(binary in prop::collection::vec(0u32.., 0..400)) {
+            let intermediate = convert::<BINARY, INTERMEDIATE>(&binary);
+            assert_eq!(intermediate, convert_small::<BINARY, INTERMEDIATE>(&binary));
+            let mut expected = binary.clone();
+            trim(&mut expected);
+            assert_eq!(convert::<INTERMEDIATE, BINARY>(&intermediate), expected);
+        }
+    }
+}
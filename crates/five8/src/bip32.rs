@@ -0,0 +1,440 @@
+//! BIP32 extended keys: Base58Check of the 78-byte serialization
+//!
+//! ```text
+//! version (4) || depth (1) || parent fingerprint (4) || child number (4)
+//!     || chain code (32) || key (33)
+//! ```
+//!
+//! where the key is a compressed public key or 0x00 and a private key. With
+//! the checksum the data is 82 bytes, and every xpub, xprv, tpub and tprv
+//! is 111 characters long. Public keys are converted by
+//! [`encode`](crate::encode) and [`decode`](crate::decode) specialized for
+//! that length, which use AVX2 when it's available. Private keys go through
+//! their scalar code instead, since the AVX2 code's arrays aren't cleared.
+//! As for WIF keys, the scalar code's arrays and the buffers here that hold
+//! the key are cleared with `zeroize` when they're dropped, though not
+//! copies in registers or the SHA-256 state of the checksum.
+
+use core::fmt;
+
+use five8_core::base58_encoded_max_len;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::{
+    check::{checksum, verify_check, CHECKSUM_LEN},
+    decode, encode,
+    generic::{decode_secret, encode_secret},
+    CheckDecodeError,
+};
+
+const VERSION_LEN: usize = 4;
+const DATA_LEN: usize = 78 + CHECKSUM_LEN;
+const CHAIN_CODE_START: usize = VERSION_LEN + 1 + 4 + 4;
+const KEY_START: usize = CHAIN_CODE_START + 32;
+
+/// The buffer size for [`encode_extended_key`]. The encodings are one
+/// character shorter.
+pub const EXTENDED_KEY_ENCODED_MAX_LEN: usize = base58_encoded_max_len(DATA_LEN);
+
+/// The network an extended key is for, given with its type by its version
+/// bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExtendedKeyNetwork {
+    /// `xpub...` and `xprv...`
+    Mainnet,
+    /// `tpub...` and `tprv...`
+    Testnet,
+}
+
+impl ExtendedKeyNetwork {
+    /// The version bytes of public (`private == false`) or private keys for
+    /// the network.
+    pub const fn version(self, private: bool) -> [u8; VERSION_LEN] {
+        match (self, private) {
+            (ExtendedKeyNetwork::Mainnet, false) => [0x04, 0x88, 0xb2, 0x1e],
+            (ExtendedKeyNetwork::Mainnet, true) => [0x04, 0x88, 0xad, 0xe4],
+            (ExtendedKeyNetwork::Testnet, false) => [0x04, 0x35, 0x87, 0xcf],
+            (ExtendedKeyNetwork::Testnet, true) => [0x04, 0x35, 0x83, 0x94],
+        }
+    }
+
+    /// The network and whether the key is private for the given version
+    /// bytes, if they're one of the four above.
+    pub const fn from_version(version: [u8; VERSION_LEN]) -> Option<(Self, bool)> {
+        match version {
+            [0x04, 0x88, 0xb2, 0x1e] => Some((ExtendedKeyNetwork::Mainnet, false)),
+            [0x04, 0x88, 0xad, 0xe4] => Some((ExtendedKeyNetwork::Mainnet, true)),
+            [0x04, 0x35, 0x87, 0xcf] => Some((ExtendedKeyNetwork::Testnet, false)),
+            [0x04, 0x35, 0x83, 0x94] => Some((ExtendedKeyNetwork::Testnet, true)),
+            _ => None,
+        }
+    }
+}
+
+/// The key of an extended key.
+#[derive(Clone, PartialEq, Eq)]
+pub enum ExtendedKeyData {
+    /// A compressed secp256k1 public key, starting with 0x02 or 0x03.
+    Public([u8; 33]),
+    /// A secp256k1 private key, which is serialized after a 0x00 byte.
+    Private([u8; 32]),
+}
+
+impl fmt::Debug for ExtendedKeyData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExtendedKeyData::Public(key) => f.debug_tuple("Public").field(key).finish(),
+            ExtendedKeyData::Private(_) => f.write_str("Private(..)"),
+        }
+    }
+}
+
+/// A parsed BIP32 extended key.
+///
+/// The chain code and the private key are cleared when the `ExtendedKey`
+/// is dropped, and the private key isn't printed by `Debug`. The fields are
+/// only reachable through the methods, so they can't be moved out of it
+/// without being cleared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtendedKey {
+    network: ExtendedKeyNetwork,
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    key: ExtendedKeyData,
+}
+
+impl ExtendedKey {
+    pub const fn new(
+        network: ExtendedKeyNetwork,
+        depth: u8,
+        parent_fingerprint: [u8; 4],
+        child_number: u32,
+        chain_code: [u8; 32],
+        key: ExtendedKeyData,
+    ) -> Self {
+        Self {
+            network,
+            depth,
+            parent_fingerprint,
+            child_number,
+            chain_code,
+            key,
+        }
+    }
+
+    pub const fn network(&self) -> ExtendedKeyNetwork {
+        self.network
+    }
+
+    /// 0 for master keys.
+    pub const fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// The first 4 bytes of the hash160 of the parent's public key.
+    pub const fn parent_fingerprint(&self) -> [u8; 4] {
+        self.parent_fingerprint
+    }
+
+    /// The index of the key in its parent's children, with the top bit set
+    /// for hardened keys.
+    pub const fn child_number(&self) -> u32 {
+        self.child_number
+    }
+
+    pub const fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    pub const fn key(&self) -> &ExtendedKeyData {
+        &self.key
+    }
+
+    pub const fn is_private(&self) -> bool {
+        matches!(self.key, ExtendedKeyData::Private(_))
+    }
+}
+
+impl Drop for ExtendedKey {
+    fn drop(&mut self) {
+        self.chain_code.zeroize();
+        if let ExtendedKeyData::Private(key) = &mut self.key {
+            key.zeroize();
+        }
+    }
+}
+
+impl ZeroizeOnDrop for ExtendedKey {}
+
+/// Encode an extended key and return the length of the encoding, which
+/// is always 111.
+///
+/// # Examples
+///
+/// ```
+/// use five8::{ExtendedKey, ExtendedKeyData, ExtendedKeyNetwork};
+///
+/// // The master public key of the first BIP32 test vector.
+/// let chain_code = [
+///     135, 61, 255, 129, 192, 47, 82, 86, 35, 253, 31, 229, 22, 126, 172, 58, 85, 160, 73, 222,
+///     61, 49, 75, 180, 46, 226, 39, 255, 237, 55, 213, 8,
+/// ];
+/// let public = [
+///     3, 57, 163, 96, 19, 48, 21, 151, 218, 239, 65, 251, 229, 147, 160, 44, 197, 19, 208, 181,
+///     85, 39, 236, 45, 241, 5, 14, 46, 143, 244, 156, 133, 194,
+/// ];
+/// let key = ExtendedKey::new(
+///     ExtendedKeyNetwork::Mainnet,
+///     0,
+///     [0; 4],
+///     0,
+///     chain_code,
+///     ExtendedKeyData::Public(public),
+/// );
+/// let mut out = [0u8; five8::EXTENDED_KEY_ENCODED_MAX_LEN];
+/// let len = five8::encode_extended_key(&key, &mut out);
+/// assert_eq!(
+///     &out[..len as usize],
+///     b"xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+/// );
+/// ```
+pub fn encode_extended_key(key: &ExtendedKey, out: &mut [u8; EXTENDED_KEY_ENCODED_MAX_LEN]) -> u8 {
+    let mut data = Zeroizing::new([0u8; DATA_LEN]);
+    data[..VERSION_LEN].copy_from_slice(&key.network.version(key.is_private()));
+    data[VERSION_LEN] = key.depth;
+    data[VERSION_LEN + 1..VERSION_LEN + 5].copy_from_slice(&key.parent_fingerprint);
+    data[VERSION_LEN + 5..CHAIN_CODE_START].copy_from_slice(&key.child_number.to_be_bytes());
+    data[CHAIN_CODE_START..KEY_START].copy_from_slice(&key.chain_code);
+    match &key.key {
+        ExtendedKeyData::Public(public) => {
+            data[KEY_START..DATA_LEN - CHECKSUM_LEN].copy_from_slice(public)
+        }
+        ExtendedKeyData::Private(private) => {
+            data[KEY_START + 1..DATA_LEN - CHECKSUM_LEN].copy_from_slice(private)
+        }
+    }
+    let checksum = checksum(&[], &data[..DATA_LEN - CHECKSUM_LEN]);
+    data[DATA_LEN - CHECKSUM_LEN..].copy_from_slice(&checksum);
+    if key.is_private() {
+        encode_secret(&data, out)
+    } else {
+        encode(&data, out)
+    }
+}
+
+/// Decode an xpub, xprv, tpub or tprv.
+///
+/// Besides the errors of [`decode_check`](crate::decode_check), other
+/// version bytes give [`CheckDecodeError::InvalidVersion`], and a key that
+/// doesn't start with 0x00 for a private version or with 0x02 or 0x03 for
+/// a public one gives [`CheckDecodeError::KeyTypeMismatch`].
+///
+/// # Examples
+///
+/// ```
+/// use five8::{ExtendedKeyData, ExtendedKeyNetwork};
+///
+/// let key = five8::decode_extended_key(
+///     "tprv8ZgxMBicQKsPeDgjzdC36fs6bMjGApWDNLR9erAXMs5skhMv36j9MV5ecvfavji5khqjWaWSFhN3YcCUUdiKH6isR4Pwy3U5y5egddBr16m",
+/// )
+/// .unwrap();
+/// assert_eq!(key.network(), ExtendedKeyNetwork::Testnet);
+/// assert_eq!(key.depth(), 0);
+/// let ExtendedKeyData::Private(private) = key.key() else { panic!() };
+/// assert_eq!(private[..4], [232, 243, 46, 114]);
+/// ```
+pub fn decode_extended_key(encoded: impl AsRef<[u8]>) -> Result<ExtendedKey, CheckDecodeError> {
+    let encoded = encoded.as_ref();
+    let mut data = Zeroizing::new([0u8; DATA_LEN]);
+    /* The encodings of all private keys start with xprv or tprv, so only
+    public keys, and data that isn't a key, take the AVX2 code */
+    if encoded.starts_with(b"xpub") || encoded.starts_with(b"tpub") {
+        decode(encoded, &mut data)?;
+    } else {
+        decode_secret(encoded, &mut data)?;
+    }
+    let checked = verify_check(&*data, &[])?;
+    let (network, private) =
+        ExtendedKeyNetwork::from_version(checked[..VERSION_LEN].try_into().unwrap())
+            .ok_or(CheckDecodeError::InvalidVersion)?;
+    let key = &checked[KEY_START..];
+    let key = match (private, key[0]) {
+        (true, 0x00) => ExtendedKeyData::Private(key[1..].try_into().unwrap()),
+        (false, 0x02 | 0x03) => ExtendedKeyData::Public(key.try_into().unwrap()),
+        _ => return Err(CheckDecodeError::KeyTypeMismatch),
+    };
+    Ok(ExtendedKey {
+        network,
+        depth: checked[VERSION_LEN],
+        parent_fingerprint: checked[VERSION_LEN + 1..VERSION_LEN + 5]
+            .try_into()
+            .unwrap(),
+        child_number: u32::from_be_bytes(
+            checked[VERSION_LEN + 5..CHAIN_CODE_START]
+                .try_into()
+                .unwrap(),
+        ),
+        chain_code: checked[CHAIN_CODE_START..KEY_START].try_into().unwrap(),
+        key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use five8_core::DecodeError;
+    use std::{format, vec::Vec};
+
+    /* The master keys of the first BIP32 test vector */
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const XPRV: &str = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+
+    fn serialize(key: &ExtendedKey) -> Vec<u8> {
+        let mut data = key.network.version(key.is_private()).to_vec();
+        data.push(key.depth);
+        data.extend_from_slice(&key.parent_fingerprint);
+        data.extend_from_slice(&key.child_number.to_be_bytes());
+        data.extend_from_slice(&key.chain_code);
+        match &key.key {
+            ExtendedKeyData::Public(public) => data.extend_from_slice(public),
+            ExtendedKeyData::Private(private) => {
+                data.push(0);
+                data.extend_from_slice(private);
+            }
+        }
+        data
+    }
+
+    fn check_against_bs58(key: &ExtendedKey) {
+        let expected = bs58::encode(serialize(key)).with_check().into_vec();
+        assert_eq!(expected.len(), 111);
+        test_util::check_against_bs58(
+            &expected,
+            |out| encode_extended_key(key, out),
+            |encoded| decode_extended_key(encoded),
+            key.clone(),
+        );
+    }
+
+    #[test]
+    fn test_bip32_test_vector() {
+        let xpub = decode_extended_key(XPUB).unwrap();
+        let xprv = decode_extended_key(XPRV).unwrap();
+        for key in [&xpub, &xprv] {
+            assert_eq!(key.network, ExtendedKeyNetwork::Mainnet);
+            assert_eq!(key.depth, 0);
+            assert_eq!(key.parent_fingerprint, [0; 4]);
+            assert_eq!(key.child_number, 0);
+            assert_eq!(key.chain_code, xpub.chain_code);
+        }
+        assert!(!xpub.is_private());
+        assert!(xprv.is_private());
+        check_against_bs58(&xpub);
+        check_against_bs58(&xprv);
+        for network in [ExtendedKeyNetwork::Mainnet, ExtendedKeyNetwork::Testnet] {
+            check_against_bs58(&ExtendedKey {
+                network,
+                depth: 255,
+                parent_fingerprint: [0xff; 4],
+                child_number: u32::MAX,
+                chain_code: [0xff; 32],
+                key: ExtendedKeyData::Private([0xff; 32]),
+            });
+            check_against_bs58(&ExtendedKey {
+                network,
+                depth: 3,
+                parent_fingerprint: [1, 2, 3, 4],
+                child_number: 0x8000_0002,
+                chain_code: [0; 32],
+                key: ExtendedKeyData::Public([2; 33]),
+            });
+        }
+    }
+
+    #[test]
+    fn test_bip32_errors() {
+        let xpub = decode_extended_key(XPUB).unwrap();
+        let xprv = decode_extended_key(XPRV).unwrap();
+        /* Swap the versions of the test vector keys */
+        for (key, version) in [
+            (&xpub, [0x04, 0x88, 0xad, 0xe4]),
+            (&xprv, [0x04, 0x88, 0xb2, 0x1e]),
+        ] {
+            let mut data = serialize(key);
+            data[..4].copy_from_slice(&version);
+            let encoded = bs58::encode(data).with_check().into_vec();
+            assert_eq!(
+                decode_extended_key(&encoded),
+                Err(CheckDecodeError::KeyTypeMismatch)
+            );
+        }
+        /* A public key must start with 0x02 or 0x03 */
+        let mut data = serialize(&xpub);
+        data[45] = 0x04;
+        let encoded = bs58::encode(data).with_check().into_vec();
+        assert_eq!(
+            decode_extended_key(&encoded),
+            Err(CheckDecodeError::KeyTypeMismatch)
+        );
+        /* A zpub (BIP84) */
+        let mut data = serialize(&xpub);
+        data[..4].copy_from_slice(&[0x04, 0xb2, 0x47, 0x46]);
+        let encoded = bs58::encode(data).with_check().into_vec();
+        assert_eq!(
+            decode_extended_key(&encoded),
+            Err(CheckDecodeError::InvalidVersion)
+        );
+        let mut encoded = XPUB.as_bytes().to_vec();
+        test_util::corrupt(&mut encoded, 50);
+        assert!(matches!(
+            decode_extended_key(&encoded),
+            Err(CheckDecodeError::InvalidChecksum { .. })
+        ));
+        assert_eq!(
+            decode_extended_key(&XPUB[..110]),
+            Err(CheckDecodeError::Decode(DecodeError::TooShort))
+        );
+    }
+
+    #[test]
+    fn test_bip32_debug_hides_private_key() {
+        let xprv = decode_extended_key(XPRV).unwrap();
+        assert!(format!("{xprv:?}").ends_with("key: Private(..) }"));
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_bip32_roundtrip(
+            testnet in any::<bool>(),
+            depth in 0u8..,
+            parent_fingerprint in prop::array::uniform4(0u8..),
+            child_number in 0u32..,
+            chain_code in prop::array::uniform32(0u8..),
+            private in prop::array::uniform32(0u8..),
+            public in any::<bool>(),
+        ) {
+            let key = if public {
+                let mut key = [0x02; 33];
+                key[1..].copy_from_slice(&private);
+                ExtendedKeyData::Public(key)
+            } else {
+                ExtendedKeyData::Private(private)
+            };
+            check_against_bs58(&ExtendedKey {
+                network: if testnet { ExtendedKeyNetwork::Testnet } else { ExtendedKeyNetwork::Mainnet },
+                depth,
+                parent_fingerprint,
+                child_number,
+                chain_code,
+                key,
+            });
+        }
+    }
+}
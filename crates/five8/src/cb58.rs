@@ -0,0 +1,272 @@
+//! CB58, used by Avalanche for IDs and keys: base58 of a payload followed
+//! by the last 4 bytes of its SHA-256.
+//!
+//! Unlike Base58Check there's no version, and the checksum comes from a
+//! single SHA-256 rather than a double one.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use five8_core::DecodeError;
+use sha2::{Digest, Sha256};
+
+use crate::{decode, encode, CheckDecodeError};
+#[cfg(feature = "alloc")]
+use crate::{decode_to_vec, encode_to_string};
+
+const CHECKSUM_LEN: usize = 4;
+
+#[inline]
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    Sha256::digest(payload)[32 - CHECKSUM_LEN..]
+        .try_into()
+        .unwrap()
+}
+
+/* Checks the checksum at the end of `data` and returns the payload
+before it */
+#[inline]
+fn verify_cb58(data: &[u8]) -> Result<&[u8], CheckDecodeError> {
+    let Some(payload_len) = data.len().checked_sub(CHECKSUM_LEN) else {
+        return Err(DecodeError::TooShort.into());
+    };
+    let (payload, found) = data.split_at(payload_len);
+    let expected = checksum(payload);
+    if expected != found {
+        return Err(CheckDecodeError::InvalidChecksum {
+            expected,
+            found: found.try_into().unwrap(),
+        });
+    }
+    Ok(payload)
+}
+
+/// CB58-encode `payload` when it takes up `N` bytes together with the
+/// checksum.
+///
+/// Like [`encode_check`](crate::encode_check), `N` is the length of the
+/// encoded data, `payload.len() + 4`, and `out` must have room for
+/// [`base58_encoded_max_len(N)`](crate::base58_encoded_max_len). Avalanche's
+/// 20-byte short IDs (node and address IDs) and 32-byte IDs (transaction,
+/// asset and chain IDs) are 24 and 36 bytes with the checksum, and are
+/// converted by [`encode`] with the tables for those lengths, and its AVX2
+/// code sized for them when AVX2 is available.
+///
+/// # Panics
+///
+/// Panics if `payload` and the checksum aren't `N` bytes.
+///
+/// # Examples
+///
+/// ```
+/// // The AVAX asset ID.
+/// let id = [
+///     33, 230, 115, 23, 203, 196, 190, 42, 235, 0, 103, 122, 214, 70, 39, 120, 168, 245, 34, 116,
+///     185, 214, 5, 223, 37, 145, 178, 48, 39, 168, 125, 255,
+/// ];
+/// let mut out = [0u8; five8::base58_encoded_max_len(36)];
+/// let len = five8::encode_cb58::<36, 50>(&id, &mut out);
+/// assert_eq!(
+///     &out[..len as usize],
+///     b"FvwEAhmxKfeiG8SnEvq42hc6whRyY3EFYAvebMqDNDGCgxN5Z"
+/// );
+/// let mut out = [0u8; five8::base58_encoded_max_len(24)];
+/// let len = five8::encode_cb58::<24, 33>(&[0; 20], &mut out);
+/// assert_eq!(&out[..len as usize], b"111111111111111111116DBWJs");
+/// ```
+#[inline]
+pub fn encode_cb58<const N: usize, const M: usize>(payload: &[u8], out: &mut [u8; M]) -> u8 {
+    assert_eq!(
+        payload.len() + CHECKSUM_LEN,
+        N,
+        "the payload and checksum must be N bytes"
+    );
+    let mut data = [0u8; N];
+    let (data_payload, data_checksum) = data.split_at_mut(payload.len());
+    data_payload.copy_from_slice(payload);
+    data_checksum.copy_from_slice(&checksum(payload));
+    encode(&data, out)
+}
+
+/// Decode CB58 data that is `N` bytes long with the checksum, and return
+/// the payload.
+///
+/// The whole decoded data is written to `out` by [`decode`], and the
+/// returned payload is the part of it before the checksum. A wrong
+/// checksum gives [`CheckDecodeError::InvalidChecksum`]. Like [`encode_cb58`],
+/// 24- and 36-byte data is converted by [`decode`] sized for it.
+///
+/// # Examples
+///
+/// ```
+/// let mut out = [0u8; 24];
+/// let node_id = five8::decode_cb58("7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg", &mut out).unwrap();
+/// assert_eq!(node_id[..4], [71, 159, 102, 200]);
+/// assert!(matches!(
+///     five8::decode_cb58("7Xhw2mDxuDS44j42TCB6U5579esbSt3Lh", &mut out),
+///     Err(five8::CheckDecodeError::InvalidChecksum { .. })
+/// ));
+/// ```
+#[inline]
+pub fn decode_cb58<const N: usize>(
+    encoded: impl AsRef<[u8]>,
+    out: &mut [u8; N],
+) -> Result<&[u8], CheckDecodeError> {
+    const { assert!(N >= CHECKSUM_LEN) };
+    decode(encoded, out)?;
+    verify_cb58(out)
+}
+
+/// CB58-encode a payload of any length.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8::encode_cb58_to_string(&[0; 32]), "11111111111111111111111111111111LpoYY");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_cb58_to_string(payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(payload.len() + CHECKSUM_LEN);
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&checksum(payload));
+    encode_to_string(&data)
+}
+
+/// Decode CB58 data of any length and return the payload, with the same
+/// errors as [`decode_cb58`].
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(
+///     five8::decode_cb58_to_vec("11111111111111111111111111111111LpoYY"),
+///     Ok([0; 32].to_vec())
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_cb58_to_vec<I: AsRef<[u8]>>(encoded: I) -> Result<Vec<u8>, CheckDecodeError> {
+    let mut data = decode_to_vec(encoded)?;
+    let payload_len = verify_cb58(&data)?.len();
+    data.truncate(payload_len);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+
+    /* bs58 has no CB58 mode, so compute the checksum separately */
+    fn check_against_bs58<const N: usize, const M: usize>(payload: &[u8]) {
+        let mut data = payload.to_vec();
+        data.extend_from_slice(&Sha256::digest(payload)[28..]);
+        let expected = bs58::encode(&data).into_vec();
+        let decoded = &mut [0u8; N];
+        test_util::check_against_bs58::<M, _, _>(
+            &expected,
+            |out| encode_cb58::<N, M>(payload, out),
+            |encoded| decode_cb58(encoded, decoded),
+            payload,
+        );
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(encode_cb58_to_string(payload).as_bytes(), expected);
+            assert_eq!(decode_cb58_to_vec(&expected).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_cb58_vectors() {
+        let mut out = [0u8; 36];
+        let chain_id = decode_cb58(
+            "2oYMBNV4eNHyqk2fjjV5nVQLDbtmNJzq5s3qs3Lo6ftnC6FByM",
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(
+            chain_id,
+            [
+                237, 95, 56, 52, 30, 67, 110, 93, 70, 226, 187, 0, 180, 93, 98, 174, 151, 209, 176,
+                80, 198, 75, 198, 52, 174, 16, 98, 103, 57, 227, 92, 75
+            ]
+        );
+        let mut out = [0u8; 24];
+        assert_eq!(
+            decode_cb58("111111111111111111116DBWJs", &mut out),
+            Ok([0; 20].as_slice())
+        );
+    }
+
+    #[test]
+    fn test_cb58_matches_bs58() {
+        for payload in [
+            [0; 32],
+            [1; 32],
+            [0xff; 32],
+            core::array::from_fn(|i| i as u8),
+        ] {
+            check_against_bs58::<36, 50>(&payload);
+            check_against_bs58::<24, 33>(&payload[..20]);
+        }
+        /* Data decoded by the 32- and 64-byte fast paths */
+        check_against_bs58::<32, 44>(&[0xab; 28]);
+        check_against_bs58::<64, 88>(&[0x00; 60]);
+        check_against_bs58::<4, 6>(&[]);
+    }
+
+    #[test]
+    fn test_cb58_errors() {
+        let mut encoded = [0u8; 50];
+        let len = encode_cb58::<36, 50>(&[0x42; 32], &mut encoded) as usize;
+        let encoded = &mut encoded[..len];
+        let mut out = [0u8; 36];
+        /* The same data with a Base58Check checksum */
+        let mut data = [0x42; 36];
+        let hash = Sha256::digest(Sha256::digest(&data[..32]));
+        data[32..].copy_from_slice(&hash[..4]);
+        let err = decode_cb58(bs58::encode(data).into_vec(), &mut out).unwrap_err();
+        assert_eq!(
+            err,
+            CheckDecodeError::InvalidChecksum {
+                expected: checksum(&[0x42; 32]),
+                found: data[32..].try_into().unwrap()
+            }
+        );
+        assert_eq!(
+            decode_cb58(&*encoded, &mut [0u8; 35]),
+            Err(DecodeError::TooLong.into())
+        );
+        let mut corrupted = encoded.to_vec();
+        test_util::corrupt(&mut corrupted, 10);
+        assert!(matches!(
+            decode_cb58(&corrupted, &mut out),
+            Err(CheckDecodeError::InvalidChecksum { .. })
+        ));
+        encoded[0] = b'0';
+        assert_eq!(
+            decode_cb58(&*encoded, &mut out),
+            Err(DecodeError::InvalidChar(b'0').into())
+        );
+        assert_eq!(
+            decode_cb58("1", &mut [0u8; 4]),
+            Err(DecodeError::TooShort.into())
+        );
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(decode_cb58_to_vec(""), Err(DecodeError::TooShort.into()));
+            assert_eq!(decode_cb58_to_vec("2"), Err(DecodeError::TooShort.into()));
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_cb58_roundtrip(id in prop::array::uniform32(0u8..)) {
+            check_against_bs58::<36, 50>(&id);
+            check_against_bs58::<24, 33>(&id[..20]);
+        }
+    }
+}
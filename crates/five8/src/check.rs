@@ -0,0 +1,271 @@
+//! Base58Check: base58 of an optional version prefix and a payload,
+//! followed by the first 4 bytes of the double SHA-256 of both.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use five8_core::DecodeError;
+use sha2::{Digest, Sha256};
+
+use crate::{decode, encode, CheckDecodeError};
+#[cfg(feature = "alloc")]
+use crate::{decode_to_vec, encode_to_string};
+
+/// The length of the checksum at the end of Base58Check data.
+pub const CHECKSUM_LEN: usize = 4;
+
+#[inline]
+pub(crate) fn checksum(version: &[u8], payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let hash = Sha256::new()
+        .chain_update(version)
+        .chain_update(payload)
+        .finalize();
+    Sha256::digest(hash)[..CHECKSUM_LEN].try_into().unwrap()
+}
+
+/* Checks the checksum at the end of `data` and the version at the start
+and returns the payload between them */
+#[inline]
+pub(crate) fn verify_check<'a>(
+    data: &'a [u8],
+    version: &[u8],
+) -> Result<&'a [u8], CheckDecodeError> {
+    let Some(checked_len) = data.len().checked_sub(CHECKSUM_LEN) else {
+        return Err(DecodeError::TooShort.into());
+    };
+    let (checked, found) = data.split_at(checked_len);
+    let expected = checksum(&[], checked);
+    if expected != found {
+        return Err(CheckDecodeError::InvalidChecksum {
+            expected,
+            found: found.try_into().unwrap(),
+        });
+    }
+    checked
+        .strip_prefix(version)
+        .ok_or(CheckDecodeError::InvalidVersion)
+}
+
+/// Base58Check-encode `version` and `payload` when they take up `N` bytes
+/// together with the checksum.
+///
+/// `N` is the length of the encoded data, `version.len() + payload.len() +
+/// CHECKSUM_LEN`, which has to be given since it can't be computed from
+/// the other lengths at compile time. It can be up to
+/// [`MAX_GENERIC_LEN`](crate::MAX_GENERIC_LEN), and the data is encoded
+/// with [`encode`], so `out` must have room for
+/// [`base58_encoded_max_len(N)`](crate::base58_encoded_max_len). Use an
+/// empty `version` for data without one.
+///
+/// # Panics
+///
+/// Panics if `version`, `payload` and the checksum don't add up to `N`
+/// bytes.
+///
+/// # Examples
+///
+/// ```
+/// // The hash160 of the public key in the Bitcoin genesis block.
+/// let hash = [
+///     98, 233, 7, 177, 92, 191, 39, 213, 66, 83, 153, 235, 246, 240, 251, 80, 235, 184, 143, 24,
+/// ];
+/// let mut out = [0u8; five8::base58_encoded_max_len(25)];
+/// let len = five8::encode_check::<25, 35>(&[0x00], &hash, &mut out);
+/// assert_eq!(&out[..len as usize], b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+/// ```
+#[inline]
+pub fn encode_check<const N: usize, const M: usize>(
+    version: &[u8],
+    payload: &[u8],
+    out: &mut [u8; M],
+) -> u8 {
+    assert_eq!(
+        version.len() + payload.len() + CHECKSUM_LEN,
+        N,
+        "the version, payload and checksum must be N bytes"
+    );
+    let mut data = [0u8; N];
+    let (data_version, rest) = data.split_at_mut(version.len());
+    data_version.copy_from_slice(version);
+    let (data_payload, data_checksum) = rest.split_at_mut(payload.len());
+    data_payload.copy_from_slice(payload);
+    data_checksum.copy_from_slice(&checksum(version, payload));
+    encode(&data, out)
+}
+
+/// Decode Base58Check data that is `N` bytes long with the checksum, and
+/// return the payload after `version`.
+///
+/// The whole decoded data, with the version and checksum, is written to
+/// `out` by [`decode`], and the returned payload is the part of it in
+/// between. A wrong checksum gives [`CheckDecodeError::InvalidChecksum`]
+/// and data that doesn't start with `version` gives
+/// [`CheckDecodeError::InvalidVersion`].
+///
+/// # Examples
+///
+/// ```
+/// use five8::CheckDecodeError;
+///
+/// let mut out = [0u8; 25];
+/// let hash = five8::decode_check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", &[0x00], &mut out).unwrap();
+/// assert_eq!(hash[..4], [98, 233, 7, 177]);
+/// assert!(matches!(
+///     five8::decode_check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb", &[0x00], &mut out),
+///     Err(CheckDecodeError::InvalidChecksum { .. })
+/// ));
+/// assert_eq!(
+///     five8::decode_check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", &[0x05], &mut out),
+///     Err(CheckDecodeError::InvalidVersion)
+/// );
+/// ```
+#[inline]
+pub fn decode_check<'a, const N: usize>(
+    encoded: impl AsRef<[u8]>,
+    version: &[u8],
+    out: &'a mut [u8; N],
+) -> Result<&'a [u8], CheckDecodeError> {
+    const { assert!(N >= CHECKSUM_LEN) };
+    decode(encoded, out)?;
+    verify_check(out, version)
+}
+
+/// Base58Check-encode `version` and `payload` of any length.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8::encode_check_to_string(&[], b"hello world"), "3vQB7B6MrGQZaxCuFg4oh");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_check_to_string(version: &[u8], payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(version.len() + payload.len() + CHECKSUM_LEN);
+    data.extend_from_slice(version);
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&checksum(version, payload));
+    encode_to_string(&data)
+}
+
+/// Decode Base58Check data of any length and return the payload after
+/// `version`, with the same errors as [`decode_check`].
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8::decode_check_to_vec("3vQB7B6MrGQZaxCuFg4oh", &[]).unwrap(), b"hello world");
+/// assert_eq!(
+///     five8::decode_check_to_vec("3vQB7B6MrGQZaxCuFg4oh", b"h"),
+///     Ok(b"ello world".to_vec())
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_check_to_vec<I: AsRef<[u8]>>(
+    encoded: I,
+    version: &[u8],
+) -> Result<Vec<u8>, CheckDecodeError> {
+    let mut data = decode_to_vec(encoded)?;
+    let payload_len = verify_check(&data, version)?.len();
+    data.truncate(version.len() + payload_len);
+    data.drain(..version.len());
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    use core::array::from_fn;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::string::ToString;
+
+    fn check_against_bs58<const N: usize, const M: usize>(version: &[u8], payload: &[u8]) {
+        let mut data = version.to_vec();
+        data.extend_from_slice(payload);
+        let expected = bs58::encode(&data).with_check().into_vec();
+        let decoded = &mut [0u8; N];
+        test_util::check_against_bs58(
+            &expected,
+            |out: &mut [u8; M]| encode_check::<N, M>(version, payload, out),
+            |encoded| decode_check(encoded, version, decoded),
+            payload,
+        );
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(
+                encode_check_to_string(version, payload).as_bytes(),
+                expected
+            );
+            assert_eq!(decode_check_to_vec(&expected, version).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_check_matches_bs58() {
+        let hash: [u8; 20] = from_fn(|i| (i * 13 + 7) as u8);
+        check_against_bs58::<25, 35>(&[0x00], &hash);
+        check_against_bs58::<25, 35>(&[0x05], &hash);
+        check_against_bs58::<26, 36>(&[0x1c, 0xb8], &hash);
+        check_against_bs58::<24, 33>(&[], &hash);
+        check_against_bs58::<25, 35>(&[], &[0; 21]);
+        check_against_bs58::<38, 52>(&[0x80], &[0xff; 33]);
+        /* Data decoded by the 32- and 64-byte fast paths */
+        check_against_bs58::<32, 44>(&[0x01], &[0xab; 27]);
+        check_against_bs58::<64, 88>(&[], &[0x00; 60]);
+        check_against_bs58::<4, 6>(&[], &[]);
+    }
+
+    #[test]
+    fn test_check_errors() {
+        let hash = [0x42u8; 20];
+        let mut encoded = [0u8; 35];
+        let len = encode_check::<25, 35>(&[0x00], &hash, &mut encoded) as usize;
+        let encoded = &mut encoded[..len];
+        let mut out = [0u8; 25];
+        assert_eq!(
+            decode_check(&*encoded, &[0x6f], &mut out),
+            Err(CheckDecodeError::InvalidVersion)
+        );
+        /* The version is only a prefix of the checked data */
+        assert_eq!(
+            decode_check(&*encoded, &[0x00, 0x42], &mut out),
+            Ok(&hash[1..])
+        );
+        test_util::corrupt(encoded, len - 1);
+        let err = decode_check(&*encoded, &[0x00], &mut out).unwrap_err();
+        let CheckDecodeError::InvalidChecksum { expected, found } = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(expected, checksum(&[0x00], &hash));
+        assert_ne!(found, expected);
+        assert!(err.to_string().starts_with("Invalid checksum: expected ["));
+        assert_eq!(
+            decode_check("1", &[], &mut [0u8; 4]),
+            Err(DecodeError::TooShort.into())
+        );
+        assert_eq!(
+            decode_check("0", &[], &mut [0u8; 4]),
+            Err(CheckDecodeError::Decode(DecodeError::InvalidChar(b'0')))
+        );
+        #[cfg(feature = "alloc")]
+        {
+            let too_short = Err(CheckDecodeError::Decode(DecodeError::TooShort));
+            assert_eq!(decode_check_to_vec("", &[]), too_short);
+            assert_eq!(decode_check_to_vec("2", &[]), too_short);
+            assert_eq!(
+                decode_check_to_vec("0", &[]).unwrap_err().to_string(),
+                "Illegal base58 char number: 48"
+            );
+            assert_eq!(decode_check_to_vec(&*encoded, &[0x00]).unwrap_err(), err);
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_check_roundtrip(version in 0u8.., payload in prop::array::uniform20(0u8..)) {
+            check_against_bs58::<25, 35>(&[version], &payload);
+        }
+    }
+}
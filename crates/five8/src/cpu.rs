@@ -0,0 +1,195 @@
+/* Runtime detection of the x86_64 features used by the SIMD code paths.
+
+The cpuid results are computed once and cached, so the per-call cost of
+dispatching is a relaxed load and a branch.  When the crate is compiled
+with the feature already enabled (e.g. RUSTFLAGS='-C target-cpu=native')
+or with the runtime-dispatch feature disabled, no detection happens at
+all and the choice is made at compile time. */
+
+/* Not every bit is read in every configuration, e.g. AVX2 when it is
+enabled at compile time but AVX-512 is not. */
+#[cfg(feature = "runtime-dispatch")]
+#[allow(dead_code)]
+mod detect {
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNINIT: u8 = 1 << 7;
+    pub(super) const AVX2: u8 = 1 << 0;
+    pub(super) const SSSE3: u8 = 1 << 1;
+    pub(super) const AVX512: u8 = 1 << 2;
+
+    static FEATURES: AtomicU8 = AtomicU8::new(UNINIT);
+
+    // cpuid became a safe function in newer compilers
+    #[allow(unused_unsafe)]
+    #[cold]
+    fn detect() -> u8 {
+        let mut features = 0;
+        let max_leaf = unsafe { __cpuid(0) }.eax;
+        if max_leaf >= 1 && unsafe { __cpuid(1) }.ecx & (1 << 9) != 0 {
+            features |= SSSE3;
+        }
+        if max_leaf >= 7 {
+            let leaf1 = unsafe { __cpuid(1) };
+            let leaf7 = unsafe { __cpuid_count(7, 0) };
+            let osxsave = leaf1.ecx & (1 << 27) != 0;
+            let avx = leaf1.ecx & (1 << 28) != 0;
+            if osxsave && avx {
+                /* The OS has to save the XMM (bit 1) and YMM (bit 2) state on
+                context switches, otherwise the 256-bit registers are
+                unusable even though cpuid reports them. */
+                let xcr0 = unsafe { _xgetbv(0) };
+                if xcr0 & 0b110 == 0b110 && leaf7.ebx & (1 << 5) != 0 {
+                    features |= AVX2;
+                }
+                /* Likewise for the opmask (bit 5) and upper ZMM (bits 6 and
+                7) state.  The AVX-512 code needs F (ebx bit 16), DQ (ebx
+                bit 17), IFMA (ebx bit 21), BW (ebx bit 30) and VBMI (ecx bit
+                1).  AVX2 (ebx bit 5) is checked as well, since the 32-byte
+                functions run the AVX2 code for Backend::Avx512.  Every CPU
+                with AVX-512 has it. */
+                let avx512_ebx = (1 << 5) | (1 << 16) | (1 << 17) | (1 << 21) | (1 << 30);
+                if xcr0 & 0xe6 == 0xe6
+                    && leaf7.ebx & avx512_ebx == avx512_ebx
+                    && leaf7.ecx & (1 << 1) != 0
+                {
+                    features |= AVX512;
+                }
+            }
+        }
+        FEATURES.store(features, Ordering::Relaxed);
+        features
+    }
+
+    #[inline(always)]
+    pub(super) fn features() -> u8 {
+        let features = FEATURES.load(Ordering::Relaxed);
+        if features & UNINIT != 0 {
+            detect()
+        } else {
+            features
+        }
+    }
+}
+
+/// Whether the AVX2 code paths can be used.
+#[inline(always)]
+pub(crate) fn avx2_available() -> bool {
+    #[cfg(target_feature = "avx2")]
+    {
+        true
+    }
+    #[cfg(all(feature = "runtime-dispatch", not(target_feature = "avx2")))]
+    {
+        detect::features() & detect::AVX2 != 0
+    }
+    #[cfg(all(not(feature = "runtime-dispatch"), not(target_feature = "avx2")))]
+    {
+        false
+    }
+}
+
+/// Whether the SSSE3 code paths can be used.
+#[inline(always)]
+pub(crate) fn ssse3_available() -> bool {
+    #[cfg(target_feature = "ssse3")]
+    {
+        true
+    }
+    #[cfg(all(feature = "runtime-dispatch", not(target_feature = "ssse3")))]
+    {
+        detect::features() & detect::SSSE3 != 0
+    }
+    #[cfg(all(not(feature = "runtime-dispatch"), not(target_feature = "ssse3")))]
+    {
+        false
+    }
+}
+
+/// Whether the AVX-512 code paths can be used. They need the F, BW, DQ,
+/// IFMA and VBMI extensions.
+#[cfg(feature = "avx512")]
+#[inline(always)]
+pub(crate) fn avx512_available() -> bool {
+    #[cfg(all(
+        target_feature = "avx512f",
+        target_feature = "avx512bw",
+        target_feature = "avx512dq",
+        target_feature = "avx512ifma",
+        target_feature = "avx512vbmi"
+    ))]
+    {
+        true
+    }
+    #[cfg(all(
+        feature = "runtime-dispatch",
+        not(all(
+            target_feature = "avx512f",
+            target_feature = "avx512bw",
+            target_feature = "avx512dq",
+            target_feature = "avx512ifma",
+            target_feature = "avx512vbmi"
+        ))
+    ))]
+    {
+        detect::features() & detect::AVX512 != 0
+    }
+    #[cfg(all(
+        not(feature = "runtime-dispatch"),
+        not(all(
+            target_feature = "avx512f",
+            target_feature = "avx512bw",
+            target_feature = "avx512dq",
+            target_feature = "avx512ifma",
+            target_feature = "avx512vbmi"
+        ))
+    ))]
+    {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    #[cfg(any(feature = "runtime-dispatch", target_feature = "avx2"))]
+    #[test]
+    fn test_avx2_available() {
+        let expected = std::is_x86_feature_detected!("avx2");
+        assert_eq!(super::avx2_available(), expected);
+        // the second call reads the cached result
+        assert_eq!(super::avx2_available(), expected);
+    }
+
+    #[cfg(any(feature = "runtime-dispatch", target_feature = "ssse3"))]
+    #[test]
+    fn test_ssse3_available() {
+        let expected = std::is_x86_feature_detected!("ssse3");
+        assert_eq!(super::ssse3_available(), expected);
+    }
+
+    #[cfg(all(
+        feature = "avx512",
+        any(
+            feature = "runtime-dispatch",
+            all(
+                target_feature = "avx512f",
+                target_feature = "avx512bw",
+                target_feature = "avx512dq",
+                target_feature = "avx512ifma",
+                target_feature = "avx512vbmi"
+            )
+        )
+    ))]
+    #[test]
+    fn test_avx512_available() {
+        let expected = std::is_x86_feature_detected!("avx512f")
+            && std::is_x86_feature_detected!("avx512bw")
+            && std::is_x86_feature_detected!("avx512dq")
+            && std::is_x86_feature_detected!("avx512ifma")
+            && std::is_x86_feature_detected!("avx512vbmi");
+        assert_eq!(super::avx512_available(), expected);
+    }
+}
@@ -1,17 +1,33 @@
 #![allow(clippy::missing_transmute_annotations)]
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 use core::mem::transmute;
 
-use core::array::from_fn;
+use core::{array::from_fn, fmt};
 
 use five8_core::{
     DecodeError, BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN, BASE58_INVALID_CHAR,
-    BASE58_INVERSE, BASE58_INVERSE_TABLE_OFFSET, BASE58_INVERSE_TABLE_SENTINEL, BINARY_SZ_32,
-    BINARY_SZ_64, DEC_TABLE_32, DEC_TABLE_64, INTERMEDIATE_SZ_32, INTERMEDIATE_SZ_64, N_32, N_64,
-    RAW58_SZ_32, RAW58_SZ_64,
+    BINARY_SZ_32, BINARY_SZ_64, DEC_TABLE_32, DEC_TABLE_64, INTERMEDIATE_SZ_32, INTERMEDIATE_SZ_64,
+    N_32, N_64, RAW58_SZ_32, RAW58_SZ_64,
 };
 
-use crate::unlikely::unlikely;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use crate::neon;
+use crate::{
+    alphabet::{Bitcoin, Charset},
+    backend::{assert_available, Backend},
+    unlikely::unlikely,
+};
+#[cfg(target_arch = "x86_64")]
+use crate::{
+    avx::{self, intermediate_to_binary, raw_to_intermediate, wuc_stu},
+    cpu::{avx2_available, ssse3_available},
+    sse,
+};
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+use crate::{
+    avx512::{self, widen_table, Aligned64},
+    cpu::avx512_available,
+};
 
 #[cfg(feature = "dev-utils")]
 pub fn truncate_and_swap_u64s_scalar_pub<const BINARY_SZ: usize, const N: usize>(
@@ -21,7 +37,6 @@ pub fn truncate_and_swap_u64s_scalar_pub<const BINARY_SZ: usize, const N: usize>
     truncate_and_swap_u64s_scalar(out, binary);
 }
 
-#[cfg(any(not(target_feature = "avx2"), feature = "dev-utils"))]
 #[inline(always)]
 fn truncate_and_swap_u64s_scalar<const BINARY_SZ: usize, const N: usize>(
     out: &mut [u8; N],
@@ -53,13 +68,14 @@ fn truncate_and_swap_u64s_scalar<const BINARY_SZ: usize, const N: usize>(
 }
 
 #[inline(always)]
-fn base58_decode_after_be_convert<const N: usize>(
+pub(crate) fn base58_decode_after_be_convert<const N: usize>(
     out: &[u8; N],
     encoded: &[u8],
+    zero: u8,
 ) -> Result<(), DecodeError> {
     /* Make sure the encoded version has the same number of leading '1's
-    as the decoded version has leading 0s. The check doesn't read past
-    the end of encoded, because '\0' != '1', so it will return NULL. */
+    (or whatever the alphabet's zero digit is) as the decoded version has
+    leading 0s. */
     let mut leading_zero_cnt = 0u64;
     while leading_zero_cnt < N as u64 {
         if unlikely(leading_zero_cnt as usize >= encoded.len()) {
@@ -69,7 +85,7 @@ fn base58_decode_after_be_convert<const N: usize>(
         if out_val != 0 {
             break;
         }
-        if unlikely(unsafe { *encoded.get_unchecked(leading_zero_cnt as usize) != b'1' }) {
+        if unlikely(unsafe { *encoded.get_unchecked(leading_zero_cnt as usize) != zero }) {
             return Err(DecodeError::TooShort);
         }
         leading_zero_cnt += 1;
@@ -77,50 +93,203 @@ fn base58_decode_after_be_convert<const N: usize>(
     if unlikely(
         encoded
             .get(leading_zero_cnt as usize)
-            .is_some_and(|x| *x == b'1'),
+            .is_some_and(|x| *x == zero),
     ) {
         return Err(DecodeError::OutputTooLong);
     }
     Ok(())
 }
 
+/* The AVX2 path converts the raw base58 digits to intermediate terms 4
+at a time, so it pads the intermediate array to a multiple of 4 terms,
+and raw_to_intermediate reads 6 bytes past the last group it converts.
+This is also enough padding to look up the digits a whole register at a
+time. */
+#[cfg(target_arch = "x86_64")]
+const INTERMEDIATE_SZ_W_PADDING_32: usize = INTERMEDIATE_SZ_32.next_multiple_of(4);
+#[cfg(target_arch = "x86_64")]
+const INTERMEDIATE_SZ_W_PADDING_64: usize = INTERMEDIATE_SZ_64.next_multiple_of(4);
+#[cfg(target_arch = "x86_64")]
+const RAW58_SZ_W_PADDING_32: usize = 5 * INTERMEDIATE_SZ_W_PADDING_32 + 6;
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+static DEC_TABLE_64_AVX512: Aligned64<[[u64; BINARY_SZ_64]; INTERMEDIATE_SZ_64]> =
+    widen_table::<INTERMEDIATE_SZ_64, BINARY_SZ_64, BINARY_SZ_64, 0>(&DEC_TABLE_64);
+#[cfg(target_arch = "x86_64")]
+const RAW58_SZ_W_PADDING_64: usize = 5 * INTERMEDIATE_SZ_W_PADDING_64 + 6;
+
+/// Validates the characters of `encoded` against an alphabet's decode
+/// table and returns how many there are, or [`DecodeError::TooLong`] if
+/// there are more than `encoded_len`.
 #[inline(always)]
-fn base58_decode_before_be_convert<
-    const ENCODED_LEN: usize,
-    const RAW58_SZ: usize,
-    const INTERMEDIATE_SZ: usize,
-    const BINARY_SZ: usize,
->(
+pub(crate) fn count_chars(
     encoded: &[u8],
-    dec_table: &[[u32; BINARY_SZ]; INTERMEDIATE_SZ],
-) -> Result<[u64; BINARY_SZ], DecodeError> {
+    encoded_len: usize,
+    decode: &[u8; 128],
+) -> Result<usize, DecodeError> {
     let mut char_cnt = 0usize;
-    while char_cnt < (ENCODED_LEN + 1).min(encoded.len()) {
+    while char_cnt < (encoded_len + 1).min(encoded.len()) {
         let c = encoded[char_cnt];
-        /* If c<'1', this will underflow and idx will be huge */
-        let idx = (c as u64).wrapping_sub(BASE58_INVERSE_TABLE_OFFSET as u64);
-        let idx = idx.min(BASE58_INVERSE_TABLE_SENTINEL as u64);
         char_cnt += 1;
-        if unlikely(unsafe { *BASE58_INVERSE.get_unchecked(idx as usize) } == BASE58_INVALID_CHAR) {
+        /* Bytes >= 0x80 aren't in the table and are never valid */
+        if unlikely(*decode.get(c as usize).unwrap_or(&BASE58_INVALID_CHAR) == BASE58_INVALID_CHAR)
+        {
             return Err(DecodeError::InvalidChar(c));
         }
     }
-    if unlikely(char_cnt == ENCODED_LEN + 1) {
+    if unlikely(char_cnt == encoded_len + 1) {
         /* too long */
         return Err(DecodeError::TooLong);
     }
+    Ok(char_cnt)
+}
+
+/// Converts the (already validated) characters to their digit values,
+/// right-aligned in `RAW58_SZ` bytes.
+#[inline(always)]
+fn make_raw_base58<const RAW58_SZ: usize>(
+    encoded: &[u8],
+    char_cnt: usize,
+    decode: &[u8; 128],
+) -> [u8; RAW58_SZ] {
     let prepend_0 = RAW58_SZ - char_cnt;
-    let raw_base58: [u8; RAW58_SZ] = from_fn(|j| {
+    from_fn(|j| {
         if j < prepend_0 {
             0
         } else {
-            unsafe {
-                *BASE58_INVERSE.get_unchecked(
-                    (*encoded.get_unchecked(j - prepend_0) - BASE58_INVERSE_TABLE_OFFSET) as usize,
-                )
-            }
+            unsafe { *decode.get_unchecked(*encoded.get_unchecked(j - prepend_0) as usize) }
         }
-    });
+    })
+}
+
+/* The SIMD paths validate the characters and convert them to digits a
+whole register at a time, in place: copy_chars_padded sets up the
+buffer, the caller converts it, collecting a bitmask of the bad bytes,
+and check_chars reports the errors the same way as count_chars. */
+
+/// Copies the first `ENCODED_LEN + 1` characters of `encoded` (enough to
+/// tell that it is too long) right-aligned into the first `RAW58_SZ`
+/// bytes of a zeroed buffer, and returns it along with the number of
+/// characters copied. The zeros on either side convert to zero digits,
+/// so after the conversion the digits are exactly where
+/// [`make_raw_base58`] puts them.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn copy_chars_padded<
+    const ENCODED_LEN: usize,
+    const RAW58_SZ: usize,
+    const RAW58_SZ_W_PADDING: usize,
+>(
+    encoded: &[u8],
+) -> ([u8; RAW58_SZ_W_PADDING], usize) {
+    const {
+        assert!(ENCODED_LEN < RAW58_SZ);
+        assert!(RAW58_SZ <= 128);
+    }
+    let char_cnt = encoded.len().min(ENCODED_LEN + 1);
+    let prepend_0 = RAW58_SZ - char_cnt;
+    let mut buf = [0u8; RAW58_SZ_W_PADDING];
+    unsafe {
+        buf.get_unchecked_mut(prepend_0..RAW58_SZ)
+            .copy_from_slice(encoded.get_unchecked(..char_cnt));
+    }
+    (buf, char_cnt)
+}
+
+/// `invalid` has bit `i` set if byte `i` of the buffer set up by
+/// [`copy_chars_padded`] is not a base58 character.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn check_chars<const ENCODED_LEN: usize, const RAW58_SZ: usize>(
+    encoded: &[u8],
+    char_cnt: usize,
+    invalid: u128,
+) -> Result<(), DecodeError> {
+    /* The padding doesn't count, and the lowest remaining bit is the
+    first bad character */
+    let invalid = (invalid >> (RAW58_SZ - char_cnt)) & ((1u128 << char_cnt) - 1);
+    if unlikely(invalid != 0) {
+        let c = unsafe { *encoded.get_unchecked(invalid.trailing_zeros() as usize) };
+        return Err(DecodeError::InvalidChar(c));
+    }
+    if unlikely(char_cnt == ENCODED_LEN + 1) {
+        /* too long */
+        return Err(DecodeError::TooLong);
+    }
+    Ok(())
+}
+
+#[inline(always)]
+fn propagate_carries<const BINARY_SZ: usize>(
+    mut binary: [u64; BINARY_SZ],
+) -> Result<[u64; BINARY_SZ], DecodeError> {
+    for i in (1..BINARY_SZ).rev() {
+        unsafe {
+            *binary.get_unchecked_mut(i - 1) += binary.get_unchecked(i) >> 32;
+        }
+        unsafe {
+            *binary.get_unchecked_mut(i) &= 0xFFFFFFFF;
+        }
+    }
+    if unlikely(unsafe { *binary.get_unchecked(0) } > 0xFFFFFFFF) {
+        return Err(DecodeError::LargestTermTooHigh);
+    }
+    Ok(binary)
+}
+
+#[inline(always)]
+fn base58_decode_before_be_convert<
+    const ENCODED_LEN: usize,
+    const RAW58_SZ: usize,
+    const INTERMEDIATE_SZ: usize,
+    const BINARY_SZ: usize,
+>(
+    encoded: &[u8],
+    dec_table: &[[u32; BINARY_SZ]; INTERMEDIATE_SZ],
+    decode: &[u8; 128],
+) -> Result<[u64; BINARY_SZ], DecodeError> {
+    let char_cnt = count_chars(encoded, ENCODED_LEN, decode)?;
+    let raw_base58 = make_raw_base58::<RAW58_SZ>(encoded, char_cnt, decode);
+    propagate_carries(raw_to_binary_scalar(&raw_base58, dec_table))
+}
+
+/// Same as [`base58_decode_before_be_convert`], with the characters
+/// validated and converted 16 at a time using SSSE3.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn base58_decode_before_be_convert_ssse3<
+    C: Charset,
+    const ENCODED_LEN: usize,
+    const RAW58_SZ: usize,
+    const RAW58_SZ_W_PADDING: usize,
+    const INTERMEDIATE_SZ: usize,
+    const BINARY_SZ: usize,
+>(
+    charset: C,
+    encoded: &[u8],
+    dec_table: &[[u32; BINARY_SZ]; INTERMEDIATE_SZ],
+) -> Result<[u64; BINARY_SZ], DecodeError> {
+    let (mut raw_base58, char_cnt) =
+        copy_chars_padded::<ENCODED_LEN, RAW58_SZ, RAW58_SZ_W_PADDING>(encoded);
+    const { assert!(RAW58_SZ.next_multiple_of(16) <= RAW58_SZ_W_PADDING) };
+    let digits = sse::digits_table(charset.alphabet().decode_table());
+    let mut invalid = 0u128;
+    for k in 0..RAW58_SZ.div_ceil(16) {
+        let p = unsafe { raw_base58.as_mut_ptr().add(16 * k) };
+        invalid |= (sse::chars_to_digits::<C>(p, &digits) as u128) << (16 * k);
+    }
+    check_chars::<ENCODED_LEN, RAW58_SZ>(encoded, char_cnt, invalid)?;
+    propagate_carries(raw_to_binary_scalar(&raw_base58, dec_table))
+}
+
+#[inline(always)]
+fn raw_to_binary_scalar<
+    const RAW_LEN: usize,
+    const INTERMEDIATE_SZ: usize,
+    const BINARY_SZ: usize,
+>(
+    raw_base58: &[u8; RAW_LEN],
+    dec_table: &[[u32; BINARY_SZ]; INTERMEDIATE_SZ],
+) -> [u64; BINARY_SZ] {
     let intermediate: [u64; INTERMEDIATE_SZ] = from_fn(|i| unsafe {
         *raw_base58.get_unchecked(5 * i) as u64 * 11316496
             + *raw_base58.get_unchecked(5 * i + 1) as u64 * 195112
@@ -128,7 +297,7 @@ fn base58_decode_before_be_convert<
             + *raw_base58.get_unchecked(5 * i + 3) as u64 * 58
             + *raw_base58.get_unchecked(5 * i + 4) as u64
     });
-    let mut binary: [u64; BINARY_SZ] = from_fn(|j| {
+    from_fn(|j| {
         let mut acc = 0u64;
         for i in 0..INTERMEDIATE_SZ {
             acc += unsafe {
@@ -136,19 +305,53 @@ fn base58_decode_before_be_convert<
             };
         }
         acc
-    });
-    for i in (1..BINARY_SZ).rev() {
-        unsafe {
-            *binary.get_unchecked_mut(i - 1) += binary.get_unchecked(i) >> 32;
-        }
-        unsafe {
-            *binary.get_unchecked_mut(i) &= 0xFFFFFFFF;
-        }
+    })
+}
+
+/// Same as [`base58_decode_before_be_convert`], with the characters
+/// validated and converted 32 at a time, and the conversion to the
+/// intermediate form and the multiplication by the decode table done in
+/// AVX2 registers.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn base58_decode_before_be_convert_avx<
+    C: Charset,
+    const ENCODED_LEN: usize,
+    const RAW58_SZ: usize,
+    const RAW58_SZ_W_PADDING: usize,
+    const INTERMEDIATE_SZ: usize,
+    const INTERMEDIATE_SZ_W_PADDING: usize,
+    const BINARY_SZ: usize,
+    const N_BLOCKS: usize,
+>(
+    charset: C,
+    encoded: &[u8],
+    dec_table: &[[u32; BINARY_SZ]; INTERMEDIATE_SZ],
+) -> Result<[u64; BINARY_SZ], DecodeError> {
+    let (mut raw_base58, char_cnt) =
+        copy_chars_padded::<ENCODED_LEN, RAW58_SZ, RAW58_SZ_W_PADDING>(encoded);
+    const { assert!(RAW58_SZ.next_multiple_of(32) <= RAW58_SZ_W_PADDING) };
+    let digits = avx::digits_table(charset.alphabet().decode_table());
+    let mut invalid = 0u128;
+    for k in 0..RAW58_SZ.div_ceil(32) {
+        let p = unsafe { raw_base58.as_mut_ptr().add(32 * k) };
+        invalid |= (avx::chars_to_digits::<C>(p, &digits) as u128) << (32 * k);
     }
-    if unlikely(unsafe { *binary.get_unchecked(0) } > 0xFFFFFFFF) {
-        return Err(DecodeError::LargestTermTooHigh);
+    check_chars::<ENCODED_LEN, RAW58_SZ>(encoded, char_cnt, invalid)?;
+    let mut intermediate = [0u64; INTERMEDIATE_SZ_W_PADDING];
+    for k in 0..INTERMEDIATE_SZ_W_PADDING / 4 {
+        let terms = raw_to_intermediate(unsafe { raw_base58.as_ptr().add(20 * k) });
+        wuc_stu(
+            unsafe { intermediate.as_mut_ptr().add(4 * k) } as *mut u8,
+            terms,
+        );
     }
-    Ok(binary)
+    let binary =
+        intermediate_to_binary::<INTERMEDIATE_SZ, INTERMEDIATE_SZ_W_PADDING, BINARY_SZ, N_BLOCKS>(
+            &intermediate,
+            dec_table,
+        );
+    propagate_carries(binary)
 }
 
 /// Decode base58 data onto a 32-byte array.
@@ -169,19 +372,188 @@ fn base58_decode_before_be_convert<
 /// ```
 #[inline]
 pub fn decode_32<I: AsRef<[u8]>>(encoded: I, out: &mut [u8; N_32]) -> Result<(), DecodeError> {
-    let as_ref = encoded.as_ref();
+    decode_32_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+/* decode_32 with the characters of any alphabet */
+#[inline]
+pub(crate) fn decode_32_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        return unsafe { decode_32_avx2_charset(charset, encoded, out) };
+    } else if ssse3_available() {
+        return unsafe { decode_32_ssse3_charset(charset, encoded, out) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        decode_32_neon(charset, encoded, out)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        decode_32_scalar(charset, encoded, out)
+    }
+}
+
+/// Decode base58 data onto a 32-byte array with a specific [`Backend`]
+/// instead of the fastest available one. Otherwise the same as
+/// [`decode_32`].
+///
+/// [`Backend::Avx512`] runs the AVX2 code, since there is no AVX-512
+/// version of this function.
+///
+/// # Panics
+///
+/// Panics if the backend is not [available](Backend::is_available).
+///
+/// # Examples
+///
+/// ```
+/// let mut out = [0u8; 32];
+/// five8::decode_32_with(five8::Backend::Scalar, "1".repeat(32), &mut out).unwrap();
+/// assert_eq!(out, [0; 32]);
+/// ```
+#[inline]
+pub fn decode_32_with<I: AsRef<[u8]>>(
+    backend: Backend,
+    encoded: I,
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    decode_32_with_charset(Bitcoin, backend, encoded.as_ref(), out)
+}
+
+/* decode_32_with with the characters of any alphabet */
+#[inline]
+pub(crate) fn decode_32_with_charset<C: Charset>(
+    charset: C,
+    backend: Backend,
+    encoded: &[u8],
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    assert_available(backend);
+    match backend {
+        Backend::Scalar => decode_32_scalar(charset, encoded, out),
+        #[cfg(target_arch = "x86_64")]
+        Backend::Ssse3 => unsafe { decode_32_ssse3_charset(charset, encoded, out) },
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx2 | Backend::Avx512 => unsafe { decode_32_avx2_charset(charset, encoded, out) },
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        Backend::Neon => decode_32_neon(charset, encoded, out),
+        _ => unreachable!(),
+    }
+}
+
+#[inline(always)]
+fn decode_32_scalar<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
     let binary = base58_decode_before_be_convert::<
         BASE58_ENCODED_32_MAX_LEN,
         RAW58_SZ_32,
         INTERMEDIATE_SZ_32,
         BINARY_SZ_32,
-    >(as_ref, &DEC_TABLE_32)?;
+    >(encoded, &DEC_TABLE_32, charset.alphabet().decode_table())?;
     /* Convert each term to big endian for the final output */
-    #[cfg(target_feature = "avx2")]
-    truncate_and_swap_u64s_32(out, &binary);
-    #[cfg(not(target_feature = "avx2"))]
     truncate_and_swap_u64s_scalar(out, &binary);
-    base58_decode_after_be_convert(out, as_ref)
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn decode_32_neon<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    let binary = base58_decode_before_be_convert::<
+        BASE58_ENCODED_32_MAX_LEN,
+        RAW58_SZ_32,
+        INTERMEDIATE_SZ_32,
+        BINARY_SZ_32,
+    >(encoded, &DEC_TABLE_32, charset.alphabet().decode_table())?;
+    truncate_and_swap_u64s_neon(out, &binary);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+/// [`decode_32`] using SSSE3, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "ssse3")]`.
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+pub unsafe fn decode_32_ssse3<I: AsRef<[u8]>>(
+    encoded: I,
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    decode_32_ssse3_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+unsafe fn decode_32_ssse3_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    let binary = base58_decode_before_be_convert_ssse3::<
+        C,
+        BASE58_ENCODED_32_MAX_LEN,
+        RAW58_SZ_32,
+        RAW58_SZ_W_PADDING_32,
+        INTERMEDIATE_SZ_32,
+        BINARY_SZ_32,
+    >(charset, encoded, &DEC_TABLE_32)?;
+    truncate_and_swap_u64s_scalar(out, &binary);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+/// [`decode_32`] using AVX2, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "avx2")]`.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+pub unsafe fn decode_32_avx2<I: AsRef<[u8]>>(
+    encoded: I,
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    decode_32_avx2_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn decode_32_avx2_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_32],
+) -> Result<(), DecodeError> {
+    let binary = base58_decode_before_be_convert_avx::<
+        C,
+        BASE58_ENCODED_32_MAX_LEN,
+        RAW58_SZ_32,
+        RAW58_SZ_W_PADDING_32,
+        INTERMEDIATE_SZ_32,
+        INTERMEDIATE_SZ_W_PADDING_32,
+        BINARY_SZ_32,
+        1,
+    >(charset, encoded, &DEC_TABLE_32)?;
+    truncate_and_swap_u64s_32(out, &binary);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
 }
 
 /// Decode base58 data onto a 64-byte array.
@@ -203,22 +575,455 @@ pub fn decode_32<I: AsRef<[u8]>>(encoded: I, out: &mut [u8; N_32]) -> Result<(),
 /// ```
 #[inline]
 pub fn decode_64<I: AsRef<[u8]>>(encoded: I, out: &mut [u8; N_64]) -> Result<(), DecodeError> {
-    let as_ref = encoded.as_ref();
+    decode_64_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+/* decode_64 with the characters of any alphabet */
+#[inline]
+pub(crate) fn decode_64_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+    if avx512_available() {
+        return unsafe { decode_64_avx512_charset(charset, encoded, out) };
+    }
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        return unsafe { decode_64_avx2_charset(charset, encoded, out) };
+    } else if ssse3_available() {
+        return unsafe { decode_64_ssse3_charset(charset, encoded, out) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        decode_64_neon(charset, encoded, out)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        decode_64_scalar(charset, encoded, out)
+    }
+}
+
+/// Decode base58 data onto a 64-byte array with a specific [`Backend`]
+/// instead of the fastest available one. Otherwise the same as
+/// [`decode_64`].
+///
+/// # Panics
+///
+/// Panics if the backend is not [available](Backend::is_available).
+///
+/// # Examples
+///
+/// ```
+/// let mut out = [0u8; 64];
+/// five8::decode_64_with(five8::Backend::Scalar, "1".repeat(64), &mut out).unwrap();
+/// assert_eq!(out, [0; 64]);
+/// ```
+#[inline]
+pub fn decode_64_with<I: AsRef<[u8]>>(
+    backend: Backend,
+    encoded: I,
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    decode_64_with_charset(Bitcoin, backend, encoded.as_ref(), out)
+}
+
+/* decode_64_with with the characters of any alphabet */
+#[inline]
+pub(crate) fn decode_64_with_charset<C: Charset>(
+    charset: C,
+    backend: Backend,
+    encoded: &[u8],
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    assert_available(backend);
+    match backend {
+        Backend::Scalar => decode_64_scalar(charset, encoded, out),
+        #[cfg(target_arch = "x86_64")]
+        Backend::Ssse3 => unsafe { decode_64_ssse3_charset(charset, encoded, out) },
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx2 => unsafe { decode_64_avx2_charset(charset, encoded, out) },
+        #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+        Backend::Avx512 => unsafe { decode_64_avx512_charset(charset, encoded, out) },
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        Backend::Neon => decode_64_neon(charset, encoded, out),
+        _ => unreachable!(),
+    }
+}
+
+#[inline(always)]
+fn decode_64_scalar<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
     let binary = base58_decode_before_be_convert::<
         BASE58_ENCODED_64_MAX_LEN,
         RAW58_SZ_64,
         INTERMEDIATE_SZ_64,
         BINARY_SZ_64,
-    >(as_ref, &DEC_TABLE_64)?;
+    >(encoded, &DEC_TABLE_64, charset.alphabet().decode_table())?;
     /* Convert each term to big endian for the final output */
-    #[cfg(target_feature = "avx2")]
-    truncate_and_swap_u64s_64(out, &binary);
-    #[cfg(not(target_feature = "avx2"))]
     truncate_and_swap_u64s_scalar(out, &binary);
-    base58_decode_after_be_convert(out, as_ref)
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn decode_64_neon<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    let binary = base58_decode_before_be_convert::<
+        BASE58_ENCODED_64_MAX_LEN,
+        RAW58_SZ_64,
+        INTERMEDIATE_SZ_64,
+        BINARY_SZ_64,
+    >(encoded, &DEC_TABLE_64, charset.alphabet().decode_table())?;
+    truncate_and_swap_u64s_neon(out, &binary);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+/// [`decode_64`] using SSSE3, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "ssse3")]`.
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+pub unsafe fn decode_64_ssse3<I: AsRef<[u8]>>(
+    encoded: I,
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    decode_64_ssse3_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+unsafe fn decode_64_ssse3_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    let binary = base58_decode_before_be_convert_ssse3::<
+        C,
+        BASE58_ENCODED_64_MAX_LEN,
+        RAW58_SZ_64,
+        RAW58_SZ_W_PADDING_64,
+        INTERMEDIATE_SZ_64,
+        BINARY_SZ_64,
+    >(charset, encoded, &DEC_TABLE_64)?;
+    truncate_and_swap_u64s_scalar(out, &binary);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+/// [`decode_64`] using AVX2, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "avx2")]`.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+pub unsafe fn decode_64_avx2<I: AsRef<[u8]>>(
+    encoded: I,
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    decode_64_avx2_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn decode_64_avx2_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    let binary = base58_decode_before_be_convert_avx::<
+        C,
+        BASE58_ENCODED_64_MAX_LEN,
+        RAW58_SZ_64,
+        RAW58_SZ_W_PADDING_64,
+        INTERMEDIATE_SZ_64,
+        INTERMEDIATE_SZ_W_PADDING_64,
+        BINARY_SZ_64,
+        2,
+    >(charset, encoded, &DEC_TABLE_64)?;
+    truncate_and_swap_u64s_64(out, &binary);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+/// [`decode_64`] using AVX-512, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512ifma,avx512vbmi")]`.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512 F, BW, DQ, IFMA and VBMI.
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512ifma,avx512vbmi")]
+#[inline]
+pub unsafe fn decode_64_avx512<I: AsRef<[u8]>>(
+    encoded: I,
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    decode_64_avx512_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512ifma,avx512vbmi")]
+#[inline]
+unsafe fn decode_64_avx512_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N_64],
+) -> Result<(), DecodeError> {
+    let char_cnt = encoded.len().min(BASE58_ENCODED_64_MAX_LEN + 1);
+    let (raw0, raw1, invalid) =
+        avx512::chars_to_raw_90(encoded, char_cnt, charset.alphabet().decode_table());
+    check_chars::<BASE58_ENCODED_64_MAX_LEN, RAW58_SZ_64>(encoded, char_cnt, invalid)?;
+    let terms = avx512::expand_raw_90(raw0, raw1);
+    let intermediate = avx512::store_terms([
+        avx512::raw_to_intermediate(terms[0]),
+        avx512::raw_to_intermediate(terms[1]),
+        avx512::raw_to_intermediate(terms[2]),
+    ]);
+    let binary = propagate_carries(avx512::intermediate_to_binary_64(
+        &intermediate,
+        &DEC_TABLE_64_AVX512,
+    ))?;
+    truncate_and_swap_u64s_64(out, &binary);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+/// The error [`decode_32_batch`] and [`decode_64_batch`] return when an
+/// input fails to decode: the index of the first such input and its error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchDecodeError {
+    /// The index of the input in the batch.
+    pub index: usize,
+    /// Why it failed to decode.
+    pub error: DecodeError,
+}
+
+impl core::error::Error for BatchDecodeError {}
+
+impl fmt::Display for BatchDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid base58 string at index {}: {}",
+            self.index, self.error
+        )
+    }
+}
+
+#[inline(always)]
+fn first_batch_error(results: &[Result<(), DecodeError>]) -> Result<(), BatchDecodeError> {
+    match results.iter().position(Result::is_err) {
+        Some(index) => Err(BatchDecodeError {
+            index,
+            error: results[index].unwrap_err(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Decode many base58 strings onto 32-byte arrays.
+///
+/// Decodes `encoded[i]` onto `out[i]` and stores the result in
+/// `results[i]`, exactly like [`decode_32`] would. The CPU features are
+/// checked once for the whole batch and the loop over the inputs is
+/// compiled for them, so the SIMD code is inlined into the loop instead of
+/// being dispatched to once per input as with [`decode_32`].
+///
+/// # Errors
+///
+/// If any input fails to decode, returns the index and error of the first
+/// one. All the inputs are decoded either way, so `results` has the errors
+/// of the others.
+///
+/// # Panics
+///
+/// Panics if `encoded`, `out` and `results` don't have the same length.
+///
+/// # Examples
+///
+/// ```
+/// use five8::{BatchDecodeError, DecodeError};
+///
+/// let encoded = ["11111111111111111111111111111112", "1111", "1111111111111111111111111111111l"];
+/// let mut out = [[0u8; 32]; 3];
+/// let mut results = [Ok(()); 3];
+/// let err = five8::decode_32_batch(&encoded, &mut out, &mut results).unwrap_err();
+/// assert_eq!(err, BatchDecodeError { index: 1, error: DecodeError::TooShort });
+/// assert_eq!(out[0][31], 1);
+/// assert_eq!(results, [Ok(()), Err(DecodeError::TooShort), Err(DecodeError::InvalidChar(b'l'))]);
+/// ```
+#[inline]
+pub fn decode_32_batch<I: AsRef<[u8]>>(
+    encoded: &[I],
+    out: &mut [[u8; N_32]],
+    results: &mut [Result<(), DecodeError>],
+) -> Result<(), BatchDecodeError> {
+    assert!(
+        encoded.len() == out.len() && encoded.len() == results.len(),
+        "encoded, out and results must have the same length"
+    );
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        unsafe { decode_32_batch_avx2(encoded, out, results) };
+        return first_batch_error(results);
+    } else if ssse3_available() {
+        unsafe { decode_32_batch_ssse3(encoded, out, results) };
+        return first_batch_error(results);
+    }
+    for ((encoded, out), result) in encoded.iter().zip(out).zip(results.iter_mut()) {
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            *result = decode_32_neon(Bitcoin, encoded.as_ref(), out);
+        }
+        #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+        {
+            *result = decode_32_scalar(Bitcoin, encoded.as_ref(), out);
+        }
+    }
+    first_batch_error(results)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_32_batch_avx2<I: AsRef<[u8]>>(
+    encoded: &[I],
+    out: &mut [[u8; N_32]],
+    results: &mut [Result<(), DecodeError>],
+) {
+    for ((encoded, out), result) in encoded.iter().zip(out).zip(results) {
+        *result = decode_32_avx2(encoded, out);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn decode_32_batch_ssse3<I: AsRef<[u8]>>(
+    encoded: &[I],
+    out: &mut [[u8; N_32]],
+    results: &mut [Result<(), DecodeError>],
+) {
+    for ((encoded, out), result) in encoded.iter().zip(out).zip(results) {
+        *result = decode_32_ssse3(encoded, out);
+    }
+}
+
+/// Decode many base58 strings onto 64-byte arrays.
+///
+/// Decodes `encoded[i]` onto `out[i]` and stores the result in
+/// `results[i]`, exactly like [`decode_64`] would. The CPU features are
+/// checked once for the whole batch and the loop over the inputs is
+/// compiled for them, so the SIMD code is inlined into the loop instead of
+/// being dispatched to once per input as with [`decode_64`].
+///
+/// # Errors
+///
+/// If any input fails to decode, returns the index and error of the first
+/// one. All the inputs are decoded either way, so `results` has the errors
+/// of the others.
+///
+/// # Panics
+///
+/// Panics if `encoded`, `out` and `results` don't have the same length.
+///
+/// # Examples
+///
+/// ```
+/// let encoded = vec!["1".repeat(64); 10];
+/// let mut out = vec![[1u8; 64]; encoded.len()];
+/// let mut results = vec![Ok(()); encoded.len()];
+/// five8::decode_64_batch(&encoded, &mut out, &mut results).unwrap();
+/// assert!(out.iter().all(|out| *out == [0; 64]));
+/// ```
+#[inline]
+pub fn decode_64_batch<I: AsRef<[u8]>>(
+    encoded: &[I],
+    out: &mut [[u8; N_64]],
+    results: &mut [Result<(), DecodeError>],
+) -> Result<(), BatchDecodeError> {
+    assert!(
+        encoded.len() == out.len() && encoded.len() == results.len(),
+        "encoded, out and results must have the same length"
+    );
+    #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+    if avx512_available() {
+        unsafe { decode_64_batch_avx512(encoded, out, results) };
+        return first_batch_error(results);
+    }
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        unsafe { decode_64_batch_avx2(encoded, out, results) };
+        return first_batch_error(results);
+    } else if ssse3_available() {
+        unsafe { decode_64_batch_ssse3(encoded, out, results) };
+        return first_batch_error(results);
+    }
+    for ((encoded, out), result) in encoded.iter().zip(out).zip(results.iter_mut()) {
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            *result = decode_64_neon(Bitcoin, encoded.as_ref(), out);
+        }
+        #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+        {
+            *result = decode_64_scalar(Bitcoin, encoded.as_ref(), out);
+        }
+    }
+    first_batch_error(results)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512ifma,avx512vbmi")]
+unsafe fn decode_64_batch_avx512<I: AsRef<[u8]>>(
+    encoded: &[I],
+    out: &mut [[u8; N_64]],
+    results: &mut [Result<(), DecodeError>],
+) {
+    for ((encoded, out), result) in encoded.iter().zip(out).zip(results) {
+        *result = decode_64_avx512(encoded, out);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_64_batch_avx2<I: AsRef<[u8]>>(
+    encoded: &[I],
+    out: &mut [[u8; N_64]],
+    results: &mut [Result<(), DecodeError>],
+) {
+    for ((encoded, out), result) in encoded.iter().zip(out).zip(results) {
+        *result = decode_64_avx2(encoded, out);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn decode_64_batch_ssse3<I: AsRef<[u8]>>(
+    encoded: &[I],
+    out: &mut [[u8; N_64]],
+    results: &mut [Result<(), DecodeError>],
+) {
+    for ((encoded, out), result) in encoded.iter().zip(out).zip(results) {
+        *result = decode_64_ssse3(encoded, out);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn truncate_and_swap_u64s_32(out: &mut [u8; N_32], nums: &[u64; BINARY_SZ_32]) {
     let res = truncate_and_swap_u64s_registers::<BINARY_SZ_32, N_32, 2>(nums);
@@ -227,18 +1032,47 @@ fn truncate_and_swap_u64s_32(out: &mut [u8; N_32], nums: &[u64; BINARY_SZ_32]) {
 
 #[cfg(feature = "dev-utils")]
 pub fn truncate_and_swap_u64s_64_pub(out: &mut [u8; N_64], nums: &[u64; BINARY_SZ_64]) {
-    truncate_and_swap_u64s_64(out, nums)
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        #[target_feature(enable = "avx2")]
+        unsafe fn truncate_and_swap_u64s_64_avx2(out: &mut [u8; N_64], nums: &[u64; BINARY_SZ_64]) {
+            truncate_and_swap_u64s_64(out, nums)
+        }
+        return unsafe { truncate_and_swap_u64s_64_avx2(out, nums) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        truncate_and_swap_u64s_neon(out, nums)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        truncate_and_swap_u64s_scalar(out, nums)
+    }
 }
 
-#[cfg(any(target_feature = "avx2", feature = "dev-utils"))]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn truncate_and_swap_u64s_64(out: &mut [u8; N_64], nums: &[u64; BINARY_SZ_64]) {
     let res = truncate_and_swap_u64s_registers::<BINARY_SZ_64, N_64, 4>(nums);
     *out = unsafe { core::mem::transmute(res) }
 }
 
+/// Same as [`truncate_and_swap_u64s_scalar`], 4 terms at a time.
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn truncate_and_swap_u64s_neon<const BINARY_SZ: usize, const N: usize>(
+    out: &mut [u8; N],
+    nums: &[u64; BINARY_SZ],
+) {
+    const { assert!(N == 4 * BINARY_SZ && BINARY_SZ.is_multiple_of(4)) };
+    for i in 0..BINARY_SZ / 4 {
+        let swapped = neon::truncate_and_swap_u64s(unsafe { nums.as_ptr().add(4 * i) });
+        neon::st(unsafe { out.as_mut_ptr().add(16 * i) }, swapped);
+    }
+}
+
 // unclear if this helps performance
-#[cfg(any(target_feature = "avx2", feature = "dev-utils"))]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn truncate_and_swap_u64s_registers<
     const BINARY_SZ: usize,
@@ -290,12 +1124,26 @@ mod tests {
         let mut decoded = [0u8; 32];
         let err = decode_32(encoded.as_bytes(), &mut decoded).unwrap_err();
         assert_eq!(err, expected_err);
+        let err = decode_32_scalar(Bitcoin, encoded.as_bytes(), &mut decoded).unwrap_err();
+        assert_eq!(err, expected_err);
+        #[cfg(target_arch = "x86_64")]
+        if std::is_x86_feature_detected!("ssse3") {
+            let err = unsafe { decode_32_ssse3(encoded.as_bytes(), &mut decoded) }.unwrap_err();
+            assert_eq!(err, expected_err);
+        }
     }
 
     fn check_bad_decode_64(expected_err: DecodeError, encoded: &str) {
         let mut decoded = [0u8; 64];
         let err = decode_64(encoded.as_bytes(), &mut decoded).unwrap_err();
         assert_eq!(err, expected_err);
+        let err = decode_64_scalar(Bitcoin, encoded.as_bytes(), &mut decoded).unwrap_err();
+        assert_eq!(err, expected_err);
+        #[cfg(target_arch = "x86_64")]
+        if std::is_x86_feature_detected!("ssse3") {
+            let err = unsafe { decode_64_ssse3(encoded.as_bytes(), &mut decoded) }.unwrap_err();
+            assert_eq!(err, expected_err);
+        }
     }
 
     #[test]
@@ -502,4 +1350,235 @@ mod tests {
             assert_eq!(const_res, out);
         }
     }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    fn check_simd_matches_scalar(encoded: &[u8]) {
+        let mut scalar_out = [0u8; 32];
+        let scalar_res = decode_32_scalar(Bitcoin, encoded, &mut scalar_out);
+        if std::is_x86_feature_detected!("ssse3") {
+            let mut ssse3_out = [0u8; 32];
+            let ssse3_res = unsafe { decode_32_ssse3(encoded, &mut ssse3_out) };
+            assert_eq!(scalar_res, ssse3_res);
+            assert_eq!(scalar_out, ssse3_out);
+        }
+        if std::is_x86_feature_detected!("avx2") {
+            let mut avx2_out = [0u8; 32];
+            let avx2_res = unsafe { decode_32_avx2(encoded, &mut avx2_out) };
+            assert_eq!(scalar_res, avx2_res);
+            assert_eq!(scalar_out, avx2_out);
+        }
+        let mut scalar_out = [0u8; 64];
+        let scalar_res = decode_64_scalar(Bitcoin, encoded, &mut scalar_out);
+        if std::is_x86_feature_detected!("ssse3") {
+            let mut ssse3_out = [0u8; 64];
+            let ssse3_res = unsafe { decode_64_ssse3(encoded, &mut ssse3_out) };
+            assert_eq!(scalar_res, ssse3_res);
+            assert_eq!(scalar_out, ssse3_out);
+        }
+        if std::is_x86_feature_detected!("avx2") {
+            let mut avx2_out = [0u8; 64];
+            let avx2_res = unsafe { decode_64_avx2(encoded, &mut avx2_out) };
+            assert_eq!(scalar_res, avx2_res);
+            assert_eq!(scalar_out, avx2_out);
+        }
+        #[cfg(feature = "avx512")]
+        if crate::cpu::avx512_available() {
+            let mut avx512_out = [0u8; 64];
+            let avx512_res = unsafe { decode_64_avx512(encoded, &mut avx512_out) };
+            assert_eq!(scalar_res, avx512_res);
+            assert_eq!(scalar_out, avx512_out);
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_decode_simd_matches_scalar_on_errors(
+            encoded_32 in "[1-9A-HJ-NP-Za-km-z]{44}",
+            encoded_64 in "[1-9A-HJ-NP-Za-km-z]{88}",
+        ) {
+            // most full-length strings are out of range
+            check_simd_matches_scalar(encoded_32.as_bytes());
+            check_simd_matches_scalar(encoded_64.as_bytes());
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_decode_simd_matches_scalar_on_bad_chars(
+            encoded in "[1-9A-HJ-NP-Za-km-z]{0,100}",
+            bad in prop::collection::vec((0usize..100, any::<u8>()), 0..3),
+        ) {
+            let mut encoded = encoded.into_bytes();
+            for (idx, c) in bad {
+                if idx < encoded.len() {
+                    encoded[idx] = c;
+                }
+            }
+            check_simd_matches_scalar(&encoded);
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_decode_32_avx2_matches_scalar(key in uniform32(0u8..)) {
+            if std::is_x86_feature_detected!("avx2") {
+                let encoded = bs58::encode(key).into_vec();
+                let mut scalar_out = [0u8; 32];
+                let mut avx2_out = [0u8; 32];
+                decode_32_scalar(Bitcoin, &encoded, &mut scalar_out).unwrap();
+                unsafe { decode_32_avx2(&encoded, &mut avx2_out) }.unwrap();
+                assert_eq!(scalar_out, avx2_out);
+            }
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_decode_64_avx2_matches_scalar(first_half in uniform32(0u8..), second_half in uniform32(0u8..)) {
+            if std::is_x86_feature_detected!("avx2") {
+                let mut combined = [0u8; 64];
+                combined[..32].copy_from_slice(&first_half);
+                combined[32..].copy_from_slice(&second_half);
+                let encoded = bs58::encode(combined).into_vec();
+                let mut scalar_out = [0u8; 64];
+                let mut avx2_out = [0u8; 64];
+                decode_64_scalar(Bitcoin, &encoded, &mut scalar_out).unwrap();
+                unsafe { decode_64_avx2(&encoded, &mut avx2_out) }.unwrap();
+                assert_eq!(scalar_out, avx2_out);
+            }
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64", feature = "avx512"))]
+    proptest! {
+        #[test]
+        fn proptest_decode_64_avx512_matches_scalar(first_half in uniform32(0u8..), second_half in uniform32(0u8..)) {
+            if crate::cpu::avx512_available() {
+                let mut combined = [0u8; 64];
+                combined[..32].copy_from_slice(&first_half);
+                combined[32..].copy_from_slice(&second_half);
+                let encoded = bs58::encode(combined).into_vec();
+                let mut scalar_out = [0u8; 64];
+                let mut avx512_out = [0u8; 64];
+                decode_64_scalar(Bitcoin, &encoded, &mut scalar_out).unwrap();
+                unsafe { decode_64_avx512(&encoded, &mut avx512_out) }.unwrap();
+                assert_eq!(scalar_out, avx512_out);
+            }
+        }
+    }
+
+    fn check_decode_32_batch(encoded: &[&[u8]]) {
+        let mut out = std::vec![[0u8; 32]; encoded.len()];
+        let mut results = std::vec![Ok(()); encoded.len()];
+        let batch_res = decode_32_batch(encoded, &mut out, &mut results);
+        for (i, ((encoded, out), result)) in encoded.iter().zip(&out).zip(&results).enumerate() {
+            let mut expected = [0u8; 32];
+            let expected_res = decode_32(encoded, &mut expected);
+            assert_eq!(*result, expected_res, "index {i}");
+            if expected_res.is_ok() {
+                assert_eq!(*out, expected, "index {i}");
+            }
+        }
+        let first_err = results
+            .iter()
+            .position(Result::is_err)
+            .map(|index| BatchDecodeError {
+                index,
+                error: results[index].unwrap_err(),
+            });
+        assert_eq!(batch_res.err(), first_err);
+    }
+
+    fn check_decode_64_batch(encoded: &[&[u8]]) {
+        let mut out = std::vec![[0u8; 64]; encoded.len()];
+        let mut results = std::vec![Ok(()); encoded.len()];
+        let batch_res = decode_64_batch(encoded, &mut out, &mut results);
+        for (i, ((encoded, out), result)) in encoded.iter().zip(&out).zip(&results).enumerate() {
+            let mut expected = [0u8; 64];
+            let expected_res = decode_64(encoded, &mut expected);
+            assert_eq!(*result, expected_res, "index {i}");
+            if expected_res.is_ok() {
+                assert_eq!(*out, expected, "index {i}");
+            }
+        }
+        let first_err = results
+            .iter()
+            .position(Result::is_err)
+            .map(|index| BatchDecodeError {
+                index,
+                error: results[index].unwrap_err(),
+            });
+        assert_eq!(batch_res.err(), first_err);
+    }
+
+    #[test]
+    fn test_decode_batch_errors() {
+        let encoded: [&[u8]; 6] = [
+            b"2gPihUTjt3FJqf1VpidgrY5cZ6PuyMccGVwQHRfjMPZG",
+            b"11111111111111111111111111111111",
+            b"1",
+            b"2gPihUTjt3FJqf1VpidgrY5cZ6PuyMccGVwQHRfjMPZ0",
+            b"JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFJ",
+            b"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz",
+        ];
+        check_decode_32_batch(&encoded);
+        let mut out = [[0u8; 32]; 6];
+        let mut results = [Ok(()); 6];
+        let err = decode_32_batch(&encoded, &mut out, &mut results).unwrap_err();
+        assert_eq!(
+            err,
+            BatchDecodeError {
+                index: 2,
+                error: DecodeError::TooShort
+            }
+        );
+        assert_eq!(results[3], Err(DecodeError::InvalidChar(b'0')));
+        assert_eq!(results[5], Err(DecodeError::LargestTermTooHigh));
+        check_decode_64_batch(&encoded);
+        check_decode_32_batch(&[]);
+        let ones = "1".repeat(64);
+        check_decode_64_batch(&[ones.as_bytes(); 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_decode_batch_length_mismatch() {
+        decode_64_batch(&["1"; 2], &mut [[0; 64]; 2], &mut [Ok(()); 3]).unwrap();
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_decode_32_batch(keys in prop::collection::vec(uniform32(0u8..), 0..10), bad in 0usize..20) {
+            let mut encoded: std::vec::Vec<std::vec::Vec<u8>> =
+                keys.iter().map(|key| bs58::encode(key).into_vec()).collect();
+            if let Some(item) = encoded.get_mut(bad) {
+                item[0] = b'l';
+            }
+            let encoded: std::vec::Vec<&[u8]> = encoded.iter().map(|e| e.as_slice()).collect();
+            check_decode_32_batch(&encoded);
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_decode_64_batch(halves in prop::collection::vec((uniform32(0u8..), uniform32(0u8..)), 0..10)) {
+            let encoded: std::vec::Vec<std::vec::Vec<u8>> = halves
+                .iter()
+                .map(|(first_half, second_half)| {
+                    let mut combined = [0u8; 64];
+                    combined[..32].copy_from_slice(first_half);
+                    combined[32..].copy_from_slice(second_half);
+                    bs58::encode(combined).into_vec()
+                })
+                .collect();
+            let encoded: std::vec::Vec<&[u8]> = encoded.iter().map(|e| e.as_slice()).collect();
+            check_decode_64_batch(&encoded);
+        }
+    }
 }
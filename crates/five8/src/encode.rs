@@ -1,128 +1,98 @@
+#[cfg(target_arch = "x86_64")]
+use crate::cpu::{avx2_available, ssse3_available};
+use crate::{
+    alphabet::{Bitcoin, Charset},
+    backend::{assert_available, Backend},
+};
 use five8_core::{
-    BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN, BINARY_SZ_32, BINARY_SZ_64,
-    INTERMEDIATE_SZ_32, INTERMEDIATE_SZ_64, N_32, N_64, RAW58_SZ_32, RAW58_SZ_64,
+    BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN, BINARY_SZ_32, BINARY_SZ_64, ENC_TABLE_32,
+    ENC_TABLE_64, INTERMEDIATE_SZ_32, INTERMEDIATE_SZ_64, N_32, N_64, RAW58_SZ_32, RAW58_SZ_64,
 };
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 use {
     crate::avx::{
-        count_leading_zeros_26, count_leading_zeros_32, count_leading_zeros_45,
-        count_leading_zeros_64, intermediate_to_raw, raw_to_base58, ten_per_slot_down_32,
-        ten_per_slot_down_64, wl, wl_and, wl_bcast, wl_eq, wl_gt, wl_ld, wl_shl, wl_shru,
-        wl_shru_vector, wuc_ldu, wuc_stu,
+        self, compact_raw_per_ulong, count_leading_zeros_26, count_leading_zeros_32,
+        count_leading_zeros_45, count_leading_zeros_64, intermediate_to_raw,
+        intermediate_to_raw_per_ulong, ten_per_slot_down_32, ten_per_slot_down_64, transpose_4x4,
+        wl, wl_add, wl_and, wl_bcast, wl_eq, wl_gt, wl_ld, wl_shl, wl_shru, wl_shru_vector,
+        wuc_ldu, wuc_stu,
     },
+    crate::sse,
     core::arch::x86_64::{
         __m128i, __m256i, _mm256_extractf128_si256, _mm256_loadu_si256, _mm256_maskstore_epi64,
-        _mm256_set_epi8, _mm256_shuffle_epi8, _mm_bslli_si128, _mm_storeu_si128,
+        _mm256_mul_epu32, _mm256_set_epi8, _mm256_shuffle_epi8, _mm_bslli_si128, _mm_storeu_si128,
+    },
+};
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use {crate::neon, core::arch::aarch64::uint8x16_t};
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+use {
+    crate::{
+        avx512::{self, widen_table, Aligned64},
+        cpu::avx512_available,
     },
+    core::arch::x86_64::{_mm512_loadu_si512, _mm512_mask_storeu_epi8, _mm512_storeu_si512},
 };
 
-#[cfg(any(not(target_feature = "avx2"), feature = "dev-utils"))]
-const BASE58_CHARS: [u8; 58] = [
+pub(crate) const BASE58_CHARS: [u8; 58] = [
     b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'A', b'B', b'C', b'D', b'E', b'F', b'G',
     b'H', b'J', b'K', b'L', b'M', b'N', b'P', b'Q', b'R', b'S', b'T', b'U', b'V', b'W', b'X', b'Y',
     b'Z', b'a', b'b', b'c', b'd', b'e', b'f', b'g', b'h', b'i', b'j', b'k', b'm', b'n', b'o', b'p',
     b'q', b'r', b's', b't', b'u', b'v', b'w', b'x', b'y', b'z',
 ];
 
-const R1DIV: u64 = 656356768u64;
-
-/* Contains the unique values less than 58^5 such that:
-  2^(32*(7-j)) = sum_k table[j][k]*58^(5*(7-k))
-
-The second dimension of this table is actually ceil(log_(58^5)
-(2^(32*(BINARY_SZ-1))), but that's almost always INTERMEDIATE_SZ-1 */
-
-const ENC_TABLE_32: [[u32; INTERMEDIATE_SZ_32 - 1]; BINARY_SZ_32] = [
-    [
-        513735, 77223048, 437087610, 300156666, 605448490, 214625350, 141436834, 379377856,
-    ],
-    [
-        0, 78508, 646269101, 118408823, 91512303, 209184527, 413102373, 153715680,
-    ],
-    [
-        0, 0, 11997, 486083817, 3737691, 294005210, 247894721, 289024608,
-    ],
-    [0, 0, 0, 1833, 324463681, 385795061, 551597588, 21339008],
-    [0, 0, 0, 0, 280, 127692781, 389432875, 357132832],
-    [0, 0, 0, 0, 0, 42, 537767569, 410450016],
-    [0, 0, 0, 0, 0, 0, 6, 356826688],
-    [0, 0, 0, 0, 0, 0, 0, 1],
-];
+pub(crate) const R1DIV: u64 = 656356768u64;
 
-const ENC_TABLE_64: [[u32; INTERMEDIATE_SZ_64 - 1]; BINARY_SZ_64] = [
-    [
-        2631, 149457141, 577092685, 632289089, 81912456, 221591423, 502967496, 403284731,
-        377738089, 492128779, 746799, 366351977, 190199623, 38066284, 526403762, 650603058,
-        454901440,
-    ],
-    [
-        0, 402, 68350375, 30641941, 266024478, 208884256, 571208415, 337765723, 215140626,
-        129419325, 480359048, 398051646, 635841659, 214020719, 136986618, 626219915, 49699360,
-    ],
-    [
-        0, 0, 61, 295059608, 141201404, 517024870, 239296485, 527697587, 212906911, 453637228,
-        467589845, 144614682, 45134568, 184514320, 644355351, 104784612, 308625792,
-    ],
-    [
-        0, 0, 0, 9, 256449755, 500124311, 479690581, 372802935, 413254725, 487877412, 520263169,
-        176791855, 78190744, 291820402, 74998585, 496097732, 59100544,
-    ],
-    [
-        0, 0, 0, 0, 1, 285573662, 455976778, 379818553, 100001224, 448949512, 109507367, 117185012,
-        347328982, 522665809, 36908802, 577276849, 64504928,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 143945778, 651677945, 281429047, 535878743, 264290972, 526964023,
-        199595821, 597442702, 499113091, 424550935, 458949280,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 21997789, 294590275, 148640294, 595017589, 210481832, 404203788,
-        574729546, 160126051, 430102516, 44963712,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 3361701, 325788598, 30977630, 513969330, 194569730, 164019635,
-        136596846, 626087230, 503769920,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 513735, 77223048, 437087610, 300156666, 605448490, 214625350,
-        141436834, 379377856,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 78508, 646269101, 118408823, 91512303, 209184527, 413102373,
-        153715680,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 11997, 486083817, 3737691, 294005210, 247894721, 289024608,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1833, 324463681, 385795061, 551597588, 21339008,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 280, 127692781, 389432875, 357132832,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 42, 537767569, 410450016,
-    ],
-    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6, 356826688],
-    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-];
+/* Splits a radix 58^5 term (< R1DIV) into its 5 base58 digits, most
+significant first */
+#[inline(always)]
+pub(crate) fn term_to_digits(v: u32, digits: &mut [u8; 5]) {
+    digits[4] = (v % 58) as u8;
+    digits[3] = ((v / 58) % 58) as u8;
+    digits[2] = ((v / 3364) % 58) as u8;
+    digits[1] = ((v / 195112) % 58) as u8;
+    digits[0] = (v / 11316496) as u8;
+}
 
-#[cfg(target_feature = "avx2")]
+/* ENC_TABLE_64 as ulongs for the IFMA multiplies, with row i shifted
+right by one so that column j lines up with intermediate[j]. */
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+static ENC_TABLE_64_AVX512: Aligned64<[[u64; 24]; BINARY_SZ_64]> =
+    widen_table::<BINARY_SZ_64, { INTERMEDIATE_SZ_64 - 1 }, 24, 1>(&ENC_TABLE_64);
+
+#[cfg(any(
+    target_arch = "x86_64",
+    all(target_arch = "aarch64", target_feature = "neon")
+))]
 const fn fd_ulong_align_up(x: usize, a: usize) -> usize {
     ((x) + ((a) - 1)) & (!((a) - 1))
 }
 
-#[cfg(target_feature = "avx2")]
+// The AVX2 path may be picked at runtime, so the padding and alignment
+// it needs are always present on x86_64.
+#[cfg(target_arch = "x86_64")]
 const INTERMEDIATE_SZ_W_PADDING_32: usize = fd_ulong_align_up(INTERMEDIATE_SZ_32, 4);
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 const INTERMEDIATE_SZ_W_PADDING_64: usize = fd_ulong_align_up(INTERMEDIATE_SZ_64, 4);
 
-#[cfg(not(target_feature = "avx2"))]
+// The NEON path converts the terms two at a time.
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+const INTERMEDIATE_SZ_W_PADDING_32: usize = fd_ulong_align_up(INTERMEDIATE_SZ_32, 2);
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+const INTERMEDIATE_SZ_W_PADDING_64: usize = fd_ulong_align_up(INTERMEDIATE_SZ_64, 2);
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
 const INTERMEDIATE_SZ_W_PADDING_32: usize = INTERMEDIATE_SZ_32;
-#[cfg(not(target_feature = "avx2"))]
+#[cfg(not(any(
+    target_arch = "x86_64",
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
 const INTERMEDIATE_SZ_W_PADDING_64: usize = INTERMEDIATE_SZ_64;
 
-#[cfg_attr(target_feature = "avx2", repr(align(32)))]
+#[cfg_attr(target_arch = "x86_64", repr(align(32)))]
 #[repr(C)]
 struct Intermediate<const INTERMEDIATE_SZ_W_PADDING: usize>([u64; INTERMEDIATE_SZ_W_PADDING]);
 
@@ -132,14 +102,14 @@ pub struct IntermediatePub<const INTERMEDIATE_SZ_W_PADDING: usize>(
     Intermediate<INTERMEDIATE_SZ_W_PADDING>,
 );
 
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn in_leading_0s_32_avx(bytes: *const u8) -> u64 {
     let bytes_ = wuc_ldu(bytes);
     count_leading_zeros_32(bytes_)
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn in_leading_0s_64_avx(bytes: *const u8) -> u64 {
     let bytes_0 = wuc_ldu(bytes);
@@ -147,7 +117,39 @@ fn in_leading_0s_64_avx(bytes: *const u8) -> u64 {
     count_leading_zeros_64(bytes_0, bytes_1)
 }
 
-#[cfg(any(not(target_feature = "avx2"), feature = "dev-utils"))]
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn in_leading_0s_32_sse(bytes: *const u8) -> u64 {
+    let bytes_ = [sse::ld(bytes), sse::ld(unsafe { bytes.add(16) })];
+    sse::count_leading_zeros_32(&bytes_)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn in_leading_0s_64_sse(bytes: *const u8) -> u64 {
+    let bytes_ = [
+        sse::ld(bytes),
+        sse::ld(unsafe { bytes.add(16) }),
+        sse::ld(unsafe { bytes.add(32) }),
+        sse::ld(unsafe { bytes.add(48) }),
+    ];
+    sse::count_leading_zeros_64(&bytes_)
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn in_leading_0s_32_neon(bytes: *const u8) -> u64 {
+    let bytes_ = [neon::ld(bytes), neon::ld(unsafe { bytes.add(16) })];
+    neon::count_leading_zeros_32(&bytes_)
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn in_leading_0s_64_neon(bytes: *const u8) -> u64 {
+    let bytes_ = core::array::from_fn(|i| neon::ld(unsafe { bytes.add(16 * i) }));
+    neon::count_leading_zeros_64(&bytes_)
+}
+
 #[inline(always)]
 fn in_leading_0s_scalar<const BYTE_CNT: usize>(bytes: *const u8) -> u64 {
     let mut in_leading_0s = 0;
@@ -166,12 +168,27 @@ pub fn in_leading_0s_scalar_pub<const BYTE_CNT: usize>(bytes: *const u8) -> u64
 }
 
 #[cfg(feature = "dev-utils")]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn in_leading_0s_32_pub(bytes: *const u8) -> u64 {
-    #[cfg(target_feature = "avx2")]
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        #[target_feature(enable = "avx2")]
+        unsafe fn in_leading_0s_32_avx2(bytes: *const u8) -> u64 {
+            in_leading_0s_32_avx(bytes)
+        }
+        return unsafe { in_leading_0s_32_avx2(bytes) };
+    } else if ssse3_available() {
+        #[target_feature(enable = "ssse3")]
+        unsafe fn in_leading_0s_32_ssse3(bytes: *const u8) -> u64 {
+            in_leading_0s_32_sse(bytes)
+        }
+        return unsafe { in_leading_0s_32_ssse3(bytes) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
     {
-        in_leading_0s_32_avx(bytes)
+        in_leading_0s_32_neon(bytes)
     }
-    #[cfg(not(target_feature = "avx2"))]
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
     {
         in_leading_0s_scalar::<N_32>(bytes)
     }
@@ -190,7 +207,6 @@ fn add_binary_to_intermediate<const INTERMEDIATE_SZ_W_PADDING: usize, const BINA
     }
 }
 
-#[cfg(any(not(target_feature = "avx2"), feature = "dev-utils"))]
 #[inline(always)]
 fn intermediate_to_base58_scalar<
     const INTERMEDIATE_SZ_W_PADDING: usize,
@@ -200,6 +216,7 @@ fn intermediate_to_base58_scalar<
     intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING>,
     in_leading_0s: u64,
     out: &mut [u8],
+    chars: &[u8; 58],
 ) -> usize {
     /* Convert intermediate form to base 58.  This form of conversion
     exposes tons of ILP, but it's more than the CPU can take advantage
@@ -262,7 +279,7 @@ fn intermediate_to_base58_scalar<
     let skip = raw_leading_0s - in_leading_0s as usize;
     for i in 0..(RAW58_SZ - skip) {
         unsafe {
-            *out.get_unchecked_mut(i) = BASE58_CHARS[raw_base58[skip + i] as usize];
+            *out.get_unchecked_mut(i) = *chars.get_unchecked(raw_base58[skip + i] as usize);
         }
     }
     skip
@@ -278,6 +295,7 @@ pub fn intermediate_to_base58_scalar_64_pub(
         &intermediate.0,
         in_leading_0s,
         out,
+        &BASE58_CHARS,
     )
 }
 
@@ -287,7 +305,41 @@ pub fn intermediate_to_base58_32_pub(
     in_leading_0s: u64,
     out: &mut [u8],
 ) -> usize {
-    intermediate_to_base58_32(&intermediate.0, in_leading_0s, out)
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        #[target_feature(enable = "avx2")]
+        unsafe fn intermediate_to_base58_32_avx2(
+            intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_32>,
+            in_leading_0s: u64,
+            out: &mut [u8],
+        ) -> usize {
+            intermediate_to_base58_32_avx(Bitcoin, intermediate, in_leading_0s, out)
+        }
+        return unsafe { intermediate_to_base58_32_avx2(&intermediate.0, in_leading_0s, out) };
+    } else if ssse3_available() {
+        #[target_feature(enable = "ssse3")]
+        unsafe fn intermediate_to_base58_32_ssse3(
+            intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_32>,
+            in_leading_0s: u64,
+            out: &mut [u8],
+        ) -> usize {
+            intermediate_to_base58_32_sse(Bitcoin, intermediate, in_leading_0s, out)
+        }
+        return unsafe { intermediate_to_base58_32_ssse3(&intermediate.0, in_leading_0s, out) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        intermediate_to_base58_32_neon(Bitcoin, &intermediate.0, in_leading_0s, out)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        intermediate_to_base58_scalar::<INTERMEDIATE_SZ_W_PADDING_32, RAW58_SZ_32, INTERMEDIATE_SZ_32>(
+            &intermediate.0,
+            in_leading_0s,
+            out,
+            &BASE58_CHARS,
+        )
+    }
 }
 
 #[inline(always)]
@@ -325,7 +377,7 @@ fn init_intermediate_array<const INTERMEDIATE_SZ_W_PADDING: usize>(
     Intermediate([0u64; INTERMEDIATE_SZ_W_PADDING])
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn u8s_to_u32s_swapped_mask_32() -> __m256i {
     unsafe {
@@ -336,7 +388,6 @@ fn u8s_to_u32s_swapped_mask_32() -> __m256i {
     }
 }
 
-#[cfg(not(target_feature = "avx2"))]
 #[inline(always)]
 fn u8s_to_u32s_scalar<const N: usize, const BINARY_SZ: usize>(
     out: &mut [u8; N],
@@ -361,14 +412,14 @@ fn u8s_to_u32s_scalar<const N: usize, const BINARY_SZ: usize>(
     }
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn u8s_to_u32s_swapped_32_register(bytes: &[u8; N_32]) -> __m256i {
     let mask = u8s_to_u32s_swapped_mask_32();
     unsafe { _mm256_shuffle_epi8(_mm256_loadu_si256(bytes.as_ptr() as *const __m256i), mask) }
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn u8s_to_u32s_swapped_64_register(bytes: &[u8; N_64]) -> [__m256i; 2] {
     let mask = u8s_to_u32s_swapped_mask_32();
@@ -383,7 +434,7 @@ fn u8s_to_u32s_swapped_64_register(bytes: &[u8; N_64]) -> [__m256i; 2] {
     }]
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn u8s_to_u32s_swapped_32(bytes: &[u8; N_32], out: &mut [u8; N_32]) {
     let res_m256i = u8s_to_u32s_swapped_32_register(bytes);
@@ -393,7 +444,7 @@ fn u8s_to_u32s_swapped_32(bytes: &[u8; N_32], out: &mut [u8; N_32]) {
 
 // replacing this func with the scalar version worsened the
 // encode_64 benchmark by 150%.
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn u8s_to_u32s_swapped_64(bytes: &[u8; N_64], out: &mut [u8; N_64]) {
     let res_nested = u8s_to_u32s_swapped_64_register(bytes);
@@ -417,49 +468,123 @@ fn u8s_to_u32s_swapped_64(bytes: &[u8; N_64], out: &mut [u8; N_64]) {
 // }
 
 #[inline(always)]
-fn make_binary_array_32(bytes: &[u8; N_32]) -> [u32; BINARY_SZ_32] {
+fn make_binary_array_32_scalar(bytes: &[u8; N_32]) -> [u32; BINARY_SZ_32] {
     // on LE take four-byte blocks and reverse them
     // 3 2 1 0 7 6 5 4 etc
     // on BE just take four-byte blocks
 
     let mut out = [0u8; N_32];
-    #[cfg(target_feature = "avx2")]
-    {
-        u8s_to_u32s_swapped_32(bytes, &mut out);
+    u8s_to_u32s_scalar::<N_32, BINARY_SZ_32>(&mut out, bytes);
+    unsafe { core::mem::transmute(out) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn make_binary_array_32_avx(bytes: &[u8; N_32]) -> [u32; BINARY_SZ_32] {
+    let mut out = [0u8; N_32];
+    u8s_to_u32s_swapped_32(bytes, &mut out);
+    unsafe { core::mem::transmute(out) }
+}
+
+/// Same as `make_binary_array_{32,64}_scalar`, 16 bytes at a time.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn make_binary_array_sse<const N: usize, const BINARY_SZ: usize>(
+    bytes: &[u8; N],
+) -> [u32; BINARY_SZ] {
+    const { assert!(N == 4 * BINARY_SZ && N.is_multiple_of(16)) };
+    let mut out = [0u32; BINARY_SZ];
+    for i in 0..N / 16 {
+        let swapped = sse::u8s_to_u32s_swapped(unsafe { bytes.as_ptr().add(16 * i) });
+        sse::st(unsafe { out.as_mut_ptr().add(4 * i) } as *mut u8, swapped);
     }
-    #[cfg(not(target_feature = "avx2"))]
-    {
-        u8s_to_u32s_scalar::<N_32, BINARY_SZ_32>(&mut out, bytes);
+    out
+}
+
+/// Same as `make_binary_array_{32,64}_scalar`, 16 bytes at a time.
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn make_binary_array_neon<const N: usize, const BINARY_SZ: usize>(
+    bytes: &[u8; N],
+) -> [u32; BINARY_SZ] {
+    const { assert!(N == 4 * BINARY_SZ && N.is_multiple_of(16)) };
+    let mut out = [0u32; BINARY_SZ];
+    for i in 0..N / 16 {
+        let swapped = neon::u8s_to_u32s_swapped(unsafe { bytes.as_ptr().add(16 * i) });
+        neon::st(unsafe { out.as_mut_ptr().add(4 * i) } as *mut u8, swapped);
     }
-    unsafe { core::mem::transmute(out) }
+    out
 }
 
 #[inline(always)]
-fn make_binary_array_64(bytes: &[u8; N_64]) -> [u32; BINARY_SZ_64] {
+fn make_binary_array_64_scalar(bytes: &[u8; N_64]) -> [u32; BINARY_SZ_64] {
     // on LE take four-byte blocks and reverse them
     // 3 2 1 0 7 6 5 4 etc
     // on BE this is a noop
 
     let mut out = [0u8; N_64];
-    #[cfg(target_feature = "avx2")]
-    {
-        u8s_to_u32s_swapped_64(bytes, &mut out);
-    }
-    #[cfg(not(target_feature = "avx2"))]
-    {
-        u8s_to_u32s_scalar::<N_64, BINARY_SZ_64>(&mut out, bytes);
-    }
+    u8s_to_u32s_scalar::<N_64, BINARY_SZ_64>(&mut out, bytes);
+    unsafe { core::mem::transmute(out) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn make_binary_array_64_avx(bytes: &[u8; N_64]) -> [u32; BINARY_SZ_64] {
+    let mut out = [0u8; N_64];
+    u8s_to_u32s_swapped_64(bytes, &mut out);
     unsafe { core::mem::transmute(out) }
 }
 
 #[cfg(feature = "dev-utils")]
 pub fn make_binary_array_32_pub(bytes: &[u8; N_32]) -> [u32; BINARY_SZ_32] {
-    make_binary_array_32(bytes)
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        #[target_feature(enable = "avx2")]
+        unsafe fn make_binary_array_32_avx2(bytes: &[u8; N_32]) -> [u32; BINARY_SZ_32] {
+            make_binary_array_32_avx(bytes)
+        }
+        return unsafe { make_binary_array_32_avx2(bytes) };
+    } else if ssse3_available() {
+        #[target_feature(enable = "ssse3")]
+        unsafe fn make_binary_array_32_ssse3(bytes: &[u8; N_32]) -> [u32; BINARY_SZ_32] {
+            make_binary_array_sse::<N_32, BINARY_SZ_32>(bytes)
+        }
+        return unsafe { make_binary_array_32_ssse3(bytes) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        make_binary_array_neon::<N_32, BINARY_SZ_32>(bytes)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        make_binary_array_32_scalar(bytes)
+    }
 }
 
 #[cfg(feature = "dev-utils")]
 pub fn make_binary_array_64_pub(bytes: &[u8; N_64]) -> [u32; BINARY_SZ_64] {
-    make_binary_array_64(bytes)
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        #[target_feature(enable = "avx2")]
+        unsafe fn make_binary_array_64_avx2(bytes: &[u8; N_64]) -> [u32; BINARY_SZ_64] {
+            make_binary_array_64_avx(bytes)
+        }
+        return unsafe { make_binary_array_64_avx2(bytes) };
+    } else if ssse3_available() {
+        #[target_feature(enable = "ssse3")]
+        unsafe fn make_binary_array_64_ssse3(bytes: &[u8; N_64]) -> [u32; BINARY_SZ_64] {
+            make_binary_array_sse::<N_64, BINARY_SZ_64>(bytes)
+        }
+        return unsafe { make_binary_array_64_ssse3(bytes) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        make_binary_array_neon::<N_64, BINARY_SZ_64>(bytes)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        make_binary_array_64_scalar(bytes)
+    }
 }
 
 /// Encode a 64-byte array.
@@ -492,96 +617,396 @@ pub fn make_binary_array_64_pub(bytes: &[u8; N_64]) -> [u32; BINARY_SZ_64] {
 /// assert_eq!(len, 86);
 #[inline]
 pub fn encode_64(bytes: &[u8; N_64], out: &mut [u8; BASE58_ENCODED_64_MAX_LEN]) -> u8 {
-    let bytes_ptr = bytes as *const u8;
-    let in_leading_0s = {
-        #[cfg(target_feature = "avx2")]
-        {
-            in_leading_0s_64_avx(bytes_ptr)
-        }
-        #[cfg(not(target_feature = "avx2"))]
-        {
-            in_leading_0s_scalar::<N_64>(bytes_ptr)
-        }
-    };
-    let binary = make_binary_array_64(bytes);
+    encode_64_charset(Bitcoin, bytes, out)
+}
+
+/* encode_64 with the digits converted to the characters of any alphabet */
+#[inline]
+pub(crate) fn encode_64_charset<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+    if avx512_available() {
+        return unsafe { encode_64_avx512_charset(charset, bytes, out) };
+    }
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        return unsafe { encode_64_avx2_charset(charset, bytes, out) };
+    } else if ssse3_available() {
+        return unsafe { encode_64_ssse3_charset(charset, bytes, out) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        encode_64_neon(charset, bytes, out)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        encode_64_scalar(charset, bytes, out)
+    }
+}
+
+/// Encode a 64-byte array with a specific [`Backend`] instead of the fastest
+/// available one. Otherwise the same as [`encode_64`].
+///
+/// # Panics
+///
+/// Panics if the backend is not [available](Backend::is_available).
+///
+/// # Examples
+/// ```
+/// let mut buf = [0u8; 88];
+/// let len = five8::encode_64_with(five8::Backend::Scalar, &[1; 64], &mut buf);
+/// let mut expected = [0u8; 88];
+/// let expected_len = five8::encode_64(&[1; 64], &mut expected);
+/// assert_eq!(buf[..len as usize], expected[..expected_len as usize]);
+/// ```
+#[inline]
+pub fn encode_64_with(
+    backend: Backend,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    encode_64_with_charset(Bitcoin, backend, bytes, out)
+}
+
+/* encode_64_with with the characters of any alphabet */
+#[inline]
+pub(crate) fn encode_64_with_charset<C: Charset>(
+    charset: C,
+    backend: Backend,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    assert_available(backend);
+    match backend {
+        Backend::Scalar => encode_64_scalar(charset, bytes, out),
+        #[cfg(target_arch = "x86_64")]
+        Backend::Ssse3 => unsafe { encode_64_ssse3_charset(charset, bytes, out) },
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx2 => unsafe { encode_64_avx2_charset(charset, bytes, out) },
+        #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+        Backend::Avx512 => unsafe { encode_64_avx512_charset(charset, bytes, out) },
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        Backend::Neon => encode_64_neon(charset, bytes, out),
+        _ => unreachable!(),
+    }
+}
+
+#[inline(always)]
+fn encode_64_scalar<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_scalar::<N_64>(bytes as *const u8);
+    let binary = make_binary_array_64_scalar(bytes);
     let intermediate = make_intermediate_array_64(binary);
-    let skip = {
-        #[cfg(not(target_feature = "avx2"))]
-        {
-            intermediate_to_base58_scalar::<
-                INTERMEDIATE_SZ_W_PADDING_64,
-                RAW58_SZ_64,
-                INTERMEDIATE_SZ_64,
-            >(&intermediate, in_leading_0s, out)
-        }
-        #[cfg(target_feature = "avx2")]
-        {
-            let intermediate_ptr = intermediate.0.as_ptr() as *const i64;
-            let raw0 = intermediate_to_raw(wl_ld(intermediate_ptr));
-            let raw1 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(4) }));
-            let raw2 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(8) }));
-            let raw3 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(12) }));
-            let raw4 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(16) }));
-            let (compact0, compact1, compact2) = ten_per_slot_down_64(raw0, raw1, raw2, raw3, raw4);
-            let raw_leading_0s_part1 = count_leading_zeros_64(compact0, compact1);
-            let raw_leading_0s_part2 = count_leading_zeros_26(compact2);
-            let raw_leading_0s = if raw_leading_0s_part1 < 64 {
-                raw_leading_0s_part1
-            } else {
-                64 + raw_leading_0s_part2
-            };
-            let base58_0 = raw_to_base58(compact0);
-            let base58_1 = raw_to_base58(compact1);
-            let base58_2 = raw_to_base58(compact2);
-            let skip = raw_leading_0s - in_leading_0s;
-            /* We'll do something similar.  The final string is between 64 and 88
-            characters, so skip is [2, 26].
-            */
-            let w_skip = wl_bcast(skip as i64);
-            let mod8_mask = wl_bcast(7);
-            let compare = wl(0, 1, 2, 3);
-            let shift_qty = wl_shl::<3>(wl_and(w_skip, mod8_mask)); /* bytes->bits */
-            let shifted = wl_shru_vector(base58_0, shift_qty);
-            let skip_div8 = wl_shru::<3>(w_skip);
-            let mask1 = wl_eq(skip_div8, compare);
-            let mask2 = wl_gt(compare, skip_div8);
-            let out_ptr = out.as_mut_ptr();
-            unsafe {
-                _mm256_maskstore_epi64(
-                    (out_ptr.offset(-8 * (skip as isize / 8))) as *mut i64,
-                    mask1,
-                    shifted,
-                )
-            };
-            unsafe {
-                _mm256_maskstore_epi64(
-                    out_ptr.offset(-(skip as isize)) as *mut i64,
-                    mask2,
-                    base58_0,
-                )
-            };
-
-            unsafe { wuc_stu(out_ptr.offset(32 - skip as isize), base58_1) };
-
-            let last = unsafe { _mm_bslli_si128(_mm256_extractf128_si256(base58_2, 1), 6) };
-            unsafe {
-                _mm_storeu_si128(
-                    out_ptr.offset(64 + 16 - 6 - skip as isize) as *mut __m128i,
-                    last,
-                )
-            };
-            unsafe {
-                _mm_storeu_si128(
-                    out_ptr.offset(64 - skip as isize) as *mut __m128i,
-                    _mm256_extractf128_si256(base58_2, 0),
-                )
-            };
-            skip
-        }
-    };
+    let skip = intermediate_to_base58_scalar::<
+        INTERMEDIATE_SZ_W_PADDING_64,
+        RAW58_SZ_64,
+        INTERMEDIATE_SZ_64,
+    >(
+        &intermediate,
+        in_leading_0s,
+        out,
+        charset.alphabet().as_bytes(),
+    );
+    RAW58_SZ_64 as u8 - skip as u8
+}
+
+/// [`encode_64`] using AVX2, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "avx2")]`.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+pub unsafe fn encode_64_avx2(bytes: &[u8; N_64], out: &mut [u8; BASE58_ENCODED_64_MAX_LEN]) -> u8 {
+    encode_64_avx2_charset(Bitcoin, bytes, out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn encode_64_avx2_charset<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_64_avx(bytes as *const u8);
+    let binary = make_binary_array_64_avx(bytes);
+    let intermediate = make_intermediate_array_64(binary);
+    let skip = intermediate_to_base58_64_avx(charset, &intermediate, in_leading_0s, out);
     RAW58_SZ_64 as u8 - skip as u8
 }
 
+/// [`encode_64`] using SSSE3, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "ssse3")]`.
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+pub unsafe fn encode_64_ssse3(bytes: &[u8; N_64], out: &mut [u8; BASE58_ENCODED_64_MAX_LEN]) -> u8 {
+    encode_64_ssse3_charset(Bitcoin, bytes, out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+unsafe fn encode_64_ssse3_charset<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_64_sse(bytes as *const u8);
+    let binary = make_binary_array_sse::<N_64, BINARY_SZ_64>(bytes);
+    let intermediate = make_intermediate_array_64(binary);
+    let skip = intermediate_to_base58_64_sse(charset, &intermediate, in_leading_0s, out);
+    RAW58_SZ_64 as u8 - skip as u8
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn encode_64_neon<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_64_neon(bytes as *const u8);
+    let binary = make_binary_array_neon::<N_64, BINARY_SZ_64>(bytes);
+    let intermediate = make_intermediate_array_64(binary);
+    let skip = intermediate_to_base58_64_neon(charset, &intermediate, in_leading_0s, out);
+    RAW58_SZ_64 as u8 - skip as u8
+}
+
+/// [`encode_64`] using AVX-512, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512ifma,avx512vbmi")]`.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512 F, BW, DQ, IFMA and VBMI.
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512ifma,avx512vbmi")]
+#[inline]
+pub unsafe fn encode_64_avx512(
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    encode_64_avx512_charset(Bitcoin, bytes, out)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+#[target_feature(enable = "avx512f,avx512bw,avx512dq,avx512ifma,avx512vbmi")]
+#[inline]
+unsafe fn encode_64_avx512_charset<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_64],
+    out: &mut [u8; BASE58_ENCODED_64_MAX_LEN],
+) -> u8 {
+    let in_leading_0s =
+        avx512::count_leading_zeros_64(_mm512_loadu_si512(bytes.as_ptr() as *const _));
+    let mut binary = [0u32; BINARY_SZ_64];
+    avx512::store_u32s(&mut binary, avx512::bytes_to_binary_64(bytes.as_ptr()));
+    let intermediate = avx512::adjust_intermediate_64(avx512::binary_to_intermediate_64(
+        &binary,
+        &ENC_TABLE_64_AVX512,
+    ));
+    let (raw0, raw1) = avx512::compact_raw_90([
+        avx512::intermediate_to_raw(intermediate[0]),
+        avx512::intermediate_to_raw(intermediate[1]),
+        avx512::intermediate_to_raw(intermediate[2]),
+    ]);
+    let raw_leading_0s = avx512::count_leading_zeros_90(raw0, raw1);
+    let chars = avx512::chars_table(charset.alphabet().as_bytes());
+    let base58_0 = avx512::raw_to_base58(raw0, chars);
+    let base58_1 = avx512::raw_to_base58(raw1, chars);
+    let skip = (raw_leading_0s - in_leading_0s) as usize;
+    /* The final string is between 64 and 88 characters, so skip is in
+    [2, 26].  Shifting the characters down by skip lines them up with
+    out, so both stores start inside it: the first fills out[..64] and
+    the masked second one writes the remaining 26 - skip characters,
+    which end at out[90 - skip] <= out[88]. */
+    let (base58_0, base58_1) = avx512::shift_down_90(base58_0, base58_1, skip);
+    let out_ptr = out.as_mut_ptr();
+    _mm512_storeu_si512(out_ptr as *mut _, base58_0);
+    _mm512_mask_storeu_epi8(
+        out_ptr.add(64) as *mut _,
+        (1 << (RAW58_SZ_64 - 64 - skip)) - 1,
+        base58_1,
+    );
+    RAW58_SZ_64 as u8 - skip as u8
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn intermediate_to_base58_64_avx<C: Charset>(
+    charset: C,
+    intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_64>,
+    in_leading_0s: u64,
+    out: &mut [u8],
+) -> usize {
+    let intermediate_ptr = intermediate.0.as_ptr() as *const i64;
+    let raw0 = intermediate_to_raw(wl_ld(intermediate_ptr));
+    let raw1 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(4) }));
+    let raw2 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(8) }));
+    let raw3 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(12) }));
+    let raw4 = intermediate_to_raw(wl_ld(unsafe { intermediate_ptr.offset(16) }));
+    raw_to_base58_64_avx(charset, [raw0, raw1, raw2, raw3, raw4], in_leading_0s, out)
+}
+
+/// The part of `intermediate_to_base58_64_avx` after `intermediate_to_raw`,
+/// shared with the batch functions.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn raw_to_base58_64_avx<C: Charset>(
+    charset: C,
+    raw: [__m256i; 5],
+    in_leading_0s: u64,
+    out: &mut [u8],
+) -> usize {
+    let [raw0, raw1, raw2, raw3, raw4] = raw;
+    let (compact0, compact1, compact2) = ten_per_slot_down_64(raw0, raw1, raw2, raw3, raw4);
+    let raw_leading_0s_part1 = count_leading_zeros_64(compact0, compact1);
+    let raw_leading_0s_part2 = count_leading_zeros_26(compact2);
+    let raw_leading_0s = if raw_leading_0s_part1 < 64 {
+        raw_leading_0s_part1
+    } else {
+        64 + raw_leading_0s_part2
+    };
+    let chars = avx::chars_table(charset.alphabet().as_bytes());
+    let base58_0 = avx::raw_to_chars::<C>(compact0, &chars);
+    let base58_1 = avx::raw_to_chars::<C>(compact1, &chars);
+    let base58_2 = avx::raw_to_chars::<C>(compact2, &chars);
+    let skip = raw_leading_0s - in_leading_0s;
+    /* We'll do something similar.  The final string is between 64 and 88
+    characters, so skip is [2, 26].
+    */
+    let w_skip = wl_bcast(skip as i64);
+    let mod8_mask = wl_bcast(7);
+    let compare = wl(0, 1, 2, 3);
+    let shift_qty = wl_shl::<3>(wl_and(w_skip, mod8_mask)); /* bytes->bits */
+    let shifted = wl_shru_vector(base58_0, shift_qty);
+    let skip_div8 = wl_shru::<3>(w_skip);
+    let mask1 = wl_eq(skip_div8, compare);
+    let mask2 = wl_gt(compare, skip_div8);
+    let out_ptr = out.as_mut_ptr();
+    unsafe {
+        _mm256_maskstore_epi64(
+            (out_ptr.offset(-8 * (skip as isize / 8))) as *mut i64,
+            mask1,
+            shifted,
+        )
+    };
+    unsafe {
+        _mm256_maskstore_epi64(
+            out_ptr.offset(-(skip as isize)) as *mut i64,
+            mask2,
+            base58_0,
+        )
+    };
+
+    unsafe { wuc_stu(out_ptr.offset(32 - skip as isize), base58_1) };
+
+    let last = unsafe { _mm_bslli_si128(_mm256_extractf128_si256(base58_2, 1), 6) };
+    unsafe {
+        _mm_storeu_si128(
+            out_ptr.offset(64 + 16 - 6 - skip as isize) as *mut __m128i,
+            last,
+        )
+    };
+    unsafe {
+        _mm_storeu_si128(
+            out_ptr.offset(64 - skip as isize) as *mut __m128i,
+            _mm256_extractf128_si256(base58_2, 0),
+        )
+    };
+    skip as usize
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn intermediate_to_base58_64_sse<C: Charset>(
+    charset: C,
+    intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_64>,
+    in_leading_0s: u64,
+    out: &mut [u8],
+) -> usize {
+    let intermediate_ptr = intermediate.0.as_ptr() as *const u8;
+    let raw = [
+        sse::intermediate_to_raw(sse::ld(intermediate_ptr)),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(16) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(32) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(48) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(64) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(80) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(96) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(112) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(128) })),
+    ];
+    let compact = sse::ten_per_slot_down_64(&raw);
+    let raw_leading_0s = sse::count_leading_zeros_90(&compact);
+    let mut base58 = [0u8; 96];
+    let chars = sse::chars_table(charset.alphabet().as_bytes());
+    for (i, compact) in compact.iter().enumerate() {
+        sse::st(
+            unsafe { base58.as_mut_ptr().add(16 * i) },
+            sse::raw_to_chars::<C>(*compact, &chars),
+        );
+    }
+    let skip = (raw_leading_0s - in_leading_0s) as usize;
+    /* Without AVX2 there are no masked stores, and storing whole
+    registers at out-skip would write before out, so copy the characters
+    out of a buffer instead. */
+    unsafe {
+        out.get_unchecked_mut(..RAW58_SZ_64 - skip)
+            .copy_from_slice(base58.get_unchecked(skip..RAW58_SZ_64))
+    };
+    skip
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn intermediate_to_base58_64_neon<C: Charset>(
+    charset: C,
+    intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_64>,
+    in_leading_0s: u64,
+    out: &mut [u8],
+) -> usize {
+    let intermediate_ptr = intermediate.0.as_ptr();
+    let raw: [uint8x16_t; 9] = core::array::from_fn(|i| {
+        neon::intermediate_to_raw(neon::ld_u64(unsafe { intermediate_ptr.add(2 * i) }))
+    });
+    let compact = neon::ten_per_slot_down_64(&raw);
+    let raw_leading_0s = neon::count_leading_zeros_90(&compact);
+    let mut base58 = [0u8; 96];
+    let chars = neon::chars_table(charset.alphabet().as_bytes());
+    for (i, compact) in compact.iter().enumerate() {
+        neon::st(
+            unsafe { base58.as_mut_ptr().add(16 * i) },
+            neon::raw_to_chars::<C>(*compact, &chars),
+        );
+    }
+    let skip = (raw_leading_0s - in_leading_0s) as usize;
+    /* There are no masked stores on NEON, and storing whole registers
+    at out-skip would write before out, so copy the characters out of
+    a buffer instead. */
+    unsafe {
+        out.get_unchecked_mut(..RAW58_SZ_64 - skip)
+            .copy_from_slice(base58.get_unchecked(skip..RAW58_SZ_64))
+    };
+    skip
+}
+
 #[inline(always)]
 fn make_intermediate_array_64(
     binary: [u32; BINARY_SZ_64],
@@ -643,9 +1068,10 @@ pub fn make_intermediate_array_64_pub(
     IntermediatePub(make_intermediate_array_64(binary))
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(target_arch = "x86_64")]
 #[inline(always)]
-fn intermediate_to_base58_32_avx(
+fn intermediate_to_base58_32_avx<C: Charset>(
+    charset: C,
     intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_32>,
     in_leading_0s: u64,
     out: &mut [u8],
@@ -657,10 +1083,25 @@ fn intermediate_to_base58_32_avx(
     let raw0 = intermediate_to_raw(intermediate0);
     let raw1 = intermediate_to_raw(intermediate1);
     let raw2 = intermediate_to_raw(intermediate2);
+    raw_to_base58_32_avx(charset, [raw0, raw1, raw2], in_leading_0s, out)
+}
+
+/// The part of `intermediate_to_base58_32_avx` after `intermediate_to_raw`,
+/// shared with the batch functions.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn raw_to_base58_32_avx<C: Charset>(
+    charset: C,
+    raw: [__m256i; 3],
+    in_leading_0s: u64,
+    out: &mut [u8],
+) -> usize {
+    let [raw0, raw1, raw2] = raw;
     let (compact0, compact1) = ten_per_slot_down_32(raw0, raw1, raw2);
     let raw_leading_0s = count_leading_zeros_45(compact0, compact1);
-    let base58_0 = raw_to_base58(compact0);
-    let base58_1 = raw_to_base58(compact1);
+    let chars = avx::chars_table(charset.alphabet().as_bytes());
+    let base58_0 = avx::raw_to_chars::<C>(compact0, &chars);
+    let base58_1 = avx::raw_to_chars::<C>(compact1, &chars);
     let skip = raw_leading_0s as usize - in_leading_0s as usize;
     /* We know the final string is between 32 and 44 characters, so skip
      has to be in [1, 13].
@@ -716,6 +1157,72 @@ fn intermediate_to_base58_32_avx(
     skip
 }
 
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn intermediate_to_base58_32_sse<C: Charset>(
+    charset: C,
+    intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_32>,
+    in_leading_0s: u64,
+    out: &mut [u8],
+) -> usize {
+    let intermediate_ptr = intermediate.0.as_ptr() as *const u8;
+    let raw = [
+        sse::intermediate_to_raw(sse::ld(intermediate_ptr)),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(16) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(32) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(48) })),
+        sse::intermediate_to_raw(sse::ld(unsafe { intermediate_ptr.add(64) })),
+    ];
+    let compact = sse::ten_per_slot_down_32(&raw);
+    let raw_leading_0s = sse::count_leading_zeros_45(&compact);
+    let mut base58 = [0u8; 48];
+    let chars = sse::chars_table(charset.alphabet().as_bytes());
+    for (i, compact) in compact.iter().enumerate() {
+        sse::st(
+            unsafe { base58.as_mut_ptr().add(16 * i) },
+            sse::raw_to_chars::<C>(*compact, &chars),
+        );
+    }
+    let skip = (raw_leading_0s - in_leading_0s) as usize;
+    /* See intermediate_to_base58_64_sse. */
+    unsafe {
+        out.get_unchecked_mut(..RAW58_SZ_32 - skip)
+            .copy_from_slice(base58.get_unchecked(skip..RAW58_SZ_32))
+    };
+    skip
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+#[inline(always)]
+fn intermediate_to_base58_32_neon<C: Charset>(
+    charset: C,
+    intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_32>,
+    in_leading_0s: u64,
+    out: &mut [u8],
+) -> usize {
+    let intermediate_ptr = intermediate.0.as_ptr();
+    let raw: [uint8x16_t; 5] = core::array::from_fn(|i| {
+        neon::intermediate_to_raw(neon::ld_u64(unsafe { intermediate_ptr.add(2 * i) }))
+    });
+    let compact = neon::ten_per_slot_down_32(&raw);
+    let raw_leading_0s = neon::count_leading_zeros_45(&compact);
+    let mut base58 = [0u8; 48];
+    let chars = neon::chars_table(charset.alphabet().as_bytes());
+    for (i, compact) in compact.iter().enumerate() {
+        neon::st(
+            unsafe { base58.as_mut_ptr().add(16 * i) },
+            neon::raw_to_chars::<C>(*compact, &chars),
+        );
+    }
+    let skip = (raw_leading_0s - in_leading_0s) as usize;
+    /* See intermediate_to_base58_64_neon. */
+    unsafe {
+        out.get_unchecked_mut(..RAW58_SZ_32 - skip)
+            .copy_from_slice(base58.get_unchecked(skip..RAW58_SZ_32))
+    };
+    skip
+}
+
 /// Encode a 32-byte array.
 ///
 /// Mutates the provided `out` array and returns a u8 `len`
@@ -743,39 +1250,194 @@ fn intermediate_to_base58_32_avx(
 /// ```
 #[inline]
 pub fn encode_32(bytes: &[u8; N_32], out: &mut [u8; BASE58_ENCODED_32_MAX_LEN]) -> u8 {
-    let bytes_ptr = bytes as *const u8;
-    let in_leading_0s = {
-        #[cfg(target_feature = "avx2")]
-        {
-            in_leading_0s_32_avx(bytes_ptr)
-        }
-        #[cfg(not(target_feature = "avx2"))]
-        {
-            in_leading_0s_scalar::<N_32>(bytes_ptr)
-        }
-    };
-    let binary = make_binary_array_32(bytes);
+    encode_32_charset(Bitcoin, bytes, out)
+}
+
+/* encode_32 with the digits converted to the characters of any alphabet */
+#[inline]
+pub(crate) fn encode_32_charset<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        return unsafe { encode_32_avx2_charset(charset, bytes, out) };
+    } else if ssse3_available() {
+        return unsafe { encode_32_ssse3_charset(charset, bytes, out) };
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        encode_32_neon(charset, bytes, out)
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        encode_32_scalar(charset, bytes, out)
+    }
+}
+
+/// Encode a 32-byte array with a specific [`Backend`] instead of the fastest
+/// available one. Otherwise the same as [`encode_32`].
+///
+/// [`Backend::Avx512`] runs the AVX2 code, since there is no AVX-512
+/// version of this function.
+///
+/// # Panics
+///
+/// Panics if the backend is not [available](Backend::is_available).
+///
+/// # Examples
+/// ```
+/// let mut buf = [0u8; 44];
+/// let len = five8::encode_32_with(five8::Backend::Scalar, &[1; 32], &mut buf);
+/// let mut expected = [0u8; 44];
+/// let expected_len = five8::encode_32(&[1; 32], &mut expected);
+/// assert_eq!(buf[..len as usize], expected[..expected_len as usize]);
+/// ```
+#[inline]
+pub fn encode_32_with(
+    backend: Backend,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    encode_32_with_charset(Bitcoin, backend, bytes, out)
+}
+
+/* encode_32_with with the characters of any alphabet */
+#[inline]
+pub(crate) fn encode_32_with_charset<C: Charset>(
+    charset: C,
+    backend: Backend,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    assert_available(backend);
+    match backend {
+        Backend::Scalar => encode_32_scalar(charset, bytes, out),
+        #[cfg(target_arch = "x86_64")]
+        Backend::Ssse3 => unsafe { encode_32_ssse3_charset(charset, bytes, out) },
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx2 | Backend::Avx512 => unsafe { encode_32_avx2_charset(charset, bytes, out) },
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        Backend::Neon => encode_32_neon(charset, bytes, out),
+        _ => unreachable!(),
+    }
+}
+
+#[inline(always)]
+fn encode_32_scalar<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_scalar::<N_32>(bytes as *const u8);
+    let binary = make_binary_array_32_scalar(bytes);
     let intermediate = make_intermediate_array_32(binary);
-    let skip = intermediate_to_base58_32(&intermediate, in_leading_0s, out);
+    let skip = intermediate_to_base58_scalar::<
+        INTERMEDIATE_SZ_W_PADDING_32,
+        RAW58_SZ_32,
+        INTERMEDIATE_SZ_32,
+    >(
+        &intermediate,
+        in_leading_0s,
+        out,
+        charset.alphabet().as_bytes(),
+    );
+    RAW58_SZ_32 as u8 - skip as u8
+}
+
+/// [`encode_32`] using AVX2, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "avx2")]`.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(target_arch = "x86_64")]
+/// # {
+/// #[target_feature(enable = "avx2")]
+/// unsafe fn encode_keys_avx2(keys: &[[u8; 32]], out: &mut [[u8; 44]]) -> u8 {
+///     let mut max_len = 0;
+///     for (key, out) in keys.iter().zip(out) {
+///         max_len = max_len.max(five8::encode_32_avx2(key, out));
+///     }
+///     max_len
+/// }
+///
+/// if std::is_x86_feature_detected!("avx2") {
+///     let mut out = [[0u8; 44]; 2];
+///     let max_len = unsafe { encode_keys_avx2(&[[0; 32], [255; 32]], &mut out) };
+///     assert_eq!(max_len, 44);
+/// }
+/// # }
+/// ```
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+pub unsafe fn encode_32_avx2(bytes: &[u8; N_32], out: &mut [u8; BASE58_ENCODED_32_MAX_LEN]) -> u8 {
+    encode_32_avx2_charset(Bitcoin, bytes, out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn encode_32_avx2_charset<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_32_avx(bytes as *const u8);
+    let binary = make_binary_array_32_avx(bytes);
+    let intermediate = make_intermediate_array_32(binary);
+    let skip = intermediate_to_base58_32_avx(charset, &intermediate, in_leading_0s, out);
     RAW58_SZ_32 as u8 - skip as u8
 }
 
+/// [`encode_32`] using SSSE3, for callers that check the CPU features
+/// themselves. It inlines into functions compiled with the same target
+/// features, e.g. with `#[target_feature(enable = "ssse3")]`.
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+pub unsafe fn encode_32_ssse3(bytes: &[u8; N_32], out: &mut [u8; BASE58_ENCODED_32_MAX_LEN]) -> u8 {
+    encode_32_ssse3_charset(Bitcoin, bytes, out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+#[inline]
+unsafe fn encode_32_ssse3_charset<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_32_sse(bytes as *const u8);
+    let binary = make_binary_array_sse::<N_32, BINARY_SZ_32>(bytes);
+    let intermediate = make_intermediate_array_32(binary);
+    let skip = intermediate_to_base58_32_sse(charset, &intermediate, in_leading_0s, out);
+    RAW58_SZ_32 as u8 - skip as u8
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
 #[inline(always)]
-fn intermediate_to_base58_32(
-    intermediate: &Intermediate<INTERMEDIATE_SZ_W_PADDING_32>,
-    in_leading_0s: u64,
-    out: &mut [u8],
-) -> usize {
-    #[cfg(not(target_feature = "avx2"))]
-    {
-        intermediate_to_base58_scalar::<INTERMEDIATE_SZ_W_PADDING_32, RAW58_SZ_32, INTERMEDIATE_SZ_32>(
-            intermediate,
-            in_leading_0s,
-            out,
-        )
-    }
-    #[cfg(target_feature = "avx2")]
-    intermediate_to_base58_32_avx(intermediate, in_leading_0s, out)
+fn encode_32_neon<C: Charset>(
+    charset: C,
+    bytes: &[u8; N_32],
+    out: &mut [u8; BASE58_ENCODED_32_MAX_LEN],
+) -> u8 {
+    let in_leading_0s = in_leading_0s_32_neon(bytes as *const u8);
+    let binary = make_binary_array_neon::<N_32, BINARY_SZ_32>(bytes);
+    let intermediate = make_intermediate_array_32(binary);
+    let skip = intermediate_to_base58_32_neon(charset, &intermediate, in_leading_0s, out);
+    RAW58_SZ_32 as u8 - skip as u8
 }
 
 #[inline(always)]
@@ -799,10 +1461,325 @@ fn make_intermediate_array_32(
     intermediate
 }
 
+/// Encode many 32-byte arrays.
+///
+/// Writes the encoding of `bytes[i]` to `out[i]` and its length to
+/// `lens[i]`, exactly like [`encode_32`] would. With AVX2, four inputs are
+/// encoded at once, one in each 64-bit lane, which is faster than calling
+/// [`encode_32`] in a loop.
+///
+/// # Panics
+///
+/// Panics if `bytes`, `out` and `lens` don't have the same length.
+///
+/// # Examples
+/// ```
+/// let keys = [[0u8; 32], [1; 32], [255; 32]];
+/// let mut out = [[0u8; 44]; 3];
+/// let mut lens = [0u8; 3];
+/// five8::encode_32_batch(&keys, &mut out, &mut lens);
+/// for i in 0..3 {
+///     let mut expected = [0u8; 44];
+///     let len = five8::encode_32(&keys[i], &mut expected);
+///     assert_eq!(lens[i], len);
+///     assert_eq!(out[i][..len as usize], expected[..len as usize]);
+/// }
+/// ```
+#[inline]
+pub fn encode_32_batch(
+    bytes: &[[u8; N_32]],
+    out: &mut [[u8; BASE58_ENCODED_32_MAX_LEN]],
+    lens: &mut [u8],
+) {
+    assert!(
+        bytes.len() == out.len() && bytes.len() == lens.len(),
+        "bytes, out and lens must have the same length"
+    );
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        return unsafe { encode_32_batch_avx2(bytes, out, lens) };
+    }
+    for ((bytes, out), len) in bytes.iter().zip(out).zip(lens) {
+        *len = encode_32(bytes, out);
+    }
+}
+
+/// Encode many 64-byte arrays.
+///
+/// Writes the encoding of `bytes[i]` to `out[i]` and its length to
+/// `lens[i]`, exactly like [`encode_64`] would. With AVX2, four inputs are
+/// encoded at once, one in each 64-bit lane, which is faster than calling
+/// [`encode_64`] in a loop. With the `avx512` feature on a CPU that
+/// supports it, the AVX-512 [`encode_64`] is faster still and is used for
+/// each input instead.
+///
+/// # Panics
+///
+/// Panics if `bytes`, `out` and `lens` don't have the same length.
+///
+/// # Examples
+/// ```
+/// let signatures = [[0u8; 64], [1; 64], [255; 64]];
+/// let mut out = [[0u8; 88]; 3];
+/// let mut lens = [0u8; 3];
+/// five8::encode_64_batch(&signatures, &mut out, &mut lens);
+/// for i in 0..3 {
+///     let mut expected = [0u8; 88];
+///     let len = five8::encode_64(&signatures[i], &mut expected);
+///     assert_eq!(lens[i], len);
+///     assert_eq!(out[i][..len as usize], expected[..len as usize]);
+/// }
+/// ```
+#[inline]
+pub fn encode_64_batch(
+    bytes: &[[u8; N_64]],
+    out: &mut [[u8; BASE58_ENCODED_64_MAX_LEN]],
+    lens: &mut [u8],
+) {
+    assert!(
+        bytes.len() == out.len() && bytes.len() == lens.len(),
+        "bytes, out and lens must have the same length"
+    );
+    #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+    let use_avx2_batch = avx2_available() && !avx512_available();
+    #[cfg(all(target_arch = "x86_64", not(feature = "avx512")))]
+    let use_avx2_batch = avx2_available();
+    #[cfg(target_arch = "x86_64")]
+    if use_avx2_batch {
+        return unsafe { encode_64_batch_avx2(bytes, out, lens) };
+    }
+    for ((bytes, out), len) in bytes.iter().zip(out).zip(lens) {
+        *len = encode_64(bytes, out);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn encode_32_batch_avx2(
+    bytes: &[[u8; N_32]],
+    out: &mut [[u8; BASE58_ENCODED_32_MAX_LEN]],
+    lens: &mut [u8],
+) {
+    let mut bytes_chunks = bytes.chunks_exact(4);
+    let mut out_chunks = out.chunks_exact_mut(4);
+    let mut lens_chunks = lens.chunks_exact_mut(4);
+    let mut intermediate = IntermediateX4([[0u64; 4]; INTERMEDIATE_SZ_W_PADDING_32]);
+    for ((bytes, out), lens) in (&mut bytes_chunks)
+        .zip(&mut out_chunks)
+        .zip(&mut lens_chunks)
+    {
+        let binary = make_binary_array_32_x4_avx(bytes);
+        make_intermediate_array_32_x4_avx(&binary, &mut intermediate);
+        let raw = intermediate_to_raw_x4_avx::<INTERMEDIATE_SZ_W_PADDING_32, 3>(&intermediate);
+        for k in 0..4 {
+            let in_leading_0s = in_leading_0s_32_avx(bytes[k].as_ptr());
+            let skip = raw_to_base58_32_avx(Bitcoin, raw[k], in_leading_0s, &mut out[k]);
+            lens[k] = RAW58_SZ_32 as u8 - skip as u8;
+        }
+    }
+    for ((bytes, out), len) in bytes_chunks
+        .remainder()
+        .iter()
+        .zip(out_chunks.into_remainder())
+        .zip(lens_chunks.into_remainder())
+    {
+        *len = encode_32_avx2(bytes, out);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn encode_64_batch_avx2(
+    bytes: &[[u8; N_64]],
+    out: &mut [[u8; BASE58_ENCODED_64_MAX_LEN]],
+    lens: &mut [u8],
+) {
+    let mut bytes_chunks = bytes.chunks_exact(4);
+    let mut out_chunks = out.chunks_exact_mut(4);
+    let mut lens_chunks = lens.chunks_exact_mut(4);
+    let mut intermediate = IntermediateX4([[0u64; 4]; INTERMEDIATE_SZ_W_PADDING_64]);
+    for ((bytes, out), lens) in (&mut bytes_chunks)
+        .zip(&mut out_chunks)
+        .zip(&mut lens_chunks)
+    {
+        let binary = make_binary_array_64_x4_avx(bytes);
+        make_intermediate_array_64_x4_avx(&binary, &mut intermediate);
+        let raw = intermediate_to_raw_x4_avx::<INTERMEDIATE_SZ_W_PADDING_64, 5>(&intermediate);
+        for k in 0..4 {
+            let in_leading_0s = in_leading_0s_64_avx(bytes[k].as_ptr());
+            let skip = raw_to_base58_64_avx(Bitcoin, raw[k], in_leading_0s, &mut out[k]);
+            lens[k] = RAW58_SZ_64 as u8 - skip as u8;
+        }
+    }
+    for ((bytes, out), len) in bytes_chunks
+        .remainder()
+        .iter()
+        .zip(out_chunks.into_remainder())
+        .zip(lens_chunks.into_remainder())
+    {
+        *len = encode_64_avx2(bytes, out);
+    }
+}
+
+/// The intermediate arrays of 4 inputs, transposed so that ulong `k` of
+/// each term belongs to input `k`.
+#[cfg(target_arch = "x86_64")]
+#[repr(C, align(32))]
+struct IntermediateX4<const INTERMEDIATE_SZ_W_PADDING: usize>(
+    [[u64; 4]; INTERMEDIATE_SZ_W_PADDING],
+);
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn make_binary_array_32_x4_avx(bytes: &[[u8; N_32]]) -> [__m256i; BINARY_SZ_32] {
+    /* After the transpose, ulong k of words[m] holds binary[2m] of input
+    k in its low half and binary[2m+1] in its high half.  mul_epu32 only
+    reads the low half, so shifting right by 32 gives binary[2m+1]. */
+    let words = transpose_4x4(
+        u8s_to_u32s_swapped_32_register(&bytes[0]),
+        u8s_to_u32s_swapped_32_register(&bytes[1]),
+        u8s_to_u32s_swapped_32_register(&bytes[2]),
+        u8s_to_u32s_swapped_32_register(&bytes[3]),
+    );
+    let mut binary = [wl_bcast(0); BINARY_SZ_32];
+    for m in 0..4 {
+        binary[2 * m] = words[m];
+        binary[2 * m + 1] = wl_shru::<32>(words[m]);
+    }
+    binary
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn make_binary_array_64_x4_avx(bytes: &[[u8; N_64]]) -> [__m256i; BINARY_SZ_64] {
+    /* Same as make_binary_array_32_x4_avx, for each half. */
+    let [a0, a1] = u8s_to_u32s_swapped_64_register(&bytes[0]);
+    let [b0, b1] = u8s_to_u32s_swapped_64_register(&bytes[1]);
+    let [c0, c1] = u8s_to_u32s_swapped_64_register(&bytes[2]);
+    let [d0, d1] = u8s_to_u32s_swapped_64_register(&bytes[3]);
+    let words0 = transpose_4x4(a0, b0, c0, d0);
+    let words1 = transpose_4x4(a1, b1, c1, d1);
+    let mut binary = [wl_bcast(0); BINARY_SZ_64];
+    for m in 0..4 {
+        binary[2 * m] = words0[m];
+        binary[2 * m + 1] = wl_shru::<32>(words0[m]);
+        binary[2 * m + 8] = words1[m];
+        binary[2 * m + 9] = wl_shru::<32>(words1[m]);
+    }
+    binary
+}
+
+/// `intermediate[j + 1] += binary[i] * table[i][j]` for the rows `i` in
+/// `rows`, with the 4 inputs in the ulongs of `binary`.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn add_binary_to_intermediate_x4_avx<
+    const INTERMEDIATE_SZ_W_PADDING: usize,
+    const BINARY_SZ: usize,
+    const TABLE_W: usize,
+>(
+    intermediate: &mut IntermediateX4<INTERMEDIATE_SZ_W_PADDING>,
+    binary: &[__m256i; BINARY_SZ],
+    table: &[[u32; TABLE_W]; BINARY_SZ],
+    rows: core::ops::Range<usize>,
+) {
+    /* The products are the same as in the scalar code: binary[i] is a
+    uint in the low half of each ulong and the table entries are less
+    than 2^32, which is exactly what mul_epu32 multiplies.  Keeping one
+    term in a register at a time leaves the rest for binary. */
+    for j in 0..TABLE_W {
+        let term = unsafe { intermediate.0.get_unchecked_mut(j + 1) }.as_mut_ptr();
+        let mut sum = wl_ld(term as *const i64);
+        for i in rows.clone() {
+            let multiplier = wl_bcast(unsafe { *table.get_unchecked(i).get_unchecked(j) } as i64);
+            sum = wl_add(sum, unsafe {
+                _mm256_mul_epu32(*binary.get_unchecked(i), multiplier)
+            });
+        }
+        wuc_stu(term as *mut u8, sum);
+    }
+}
+
+/// Same as `adjust_intermediate_array`, for each of the 4 inputs.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn adjust_intermediate_array_x4<
+    const INTERMEDIATE_SZ_W_PADDING: usize,
+    const INTERMEDIATE_SZ: usize,
+>(
+    intermediate: &mut IntermediateX4<INTERMEDIATE_SZ_W_PADDING>,
+) {
+    /* AVX2 has no 64-bit division or high multiply, so this part is
+    scalar. */
+    for i in (1..=INTERMEDIATE_SZ - 1).rev() {
+        for k in 0..4 {
+            intermediate.0[i - 1][k] += intermediate.0[i][k] / R1DIV;
+            intermediate.0[i][k] %= R1DIV;
+        }
+    }
+}
+
+/// Same as `make_intermediate_array_32`, for 4 inputs at once. This writes
+/// to `intermediate` instead of returning it, which would cost a copy.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn make_intermediate_array_32_x4_avx(
+    binary: &[__m256i; BINARY_SZ_32],
+    intermediate: &mut IntermediateX4<INTERMEDIATE_SZ_W_PADDING_32>,
+) {
+    intermediate.0 = [[0u64; 4]; INTERMEDIATE_SZ_W_PADDING_32];
+    add_binary_to_intermediate_x4_avx(intermediate, binary, &ENC_TABLE_32, 0..BINARY_SZ_32);
+    adjust_intermediate_array_x4::<INTERMEDIATE_SZ_W_PADDING_32, INTERMEDIATE_SZ_32>(intermediate);
+}
+
+/// Same as `make_intermediate_array_64`, for 4 inputs at once.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn make_intermediate_array_64_x4_avx(
+    binary: &[__m256i; BINARY_SZ_64],
+    intermediate: &mut IntermediateX4<INTERMEDIATE_SZ_W_PADDING_64>,
+) {
+    intermediate.0 = [[0u64; 4]; INTERMEDIATE_SZ_W_PADDING_64];
+    add_binary_to_intermediate_x4_avx(intermediate, binary, &ENC_TABLE_64, 0..8);
+    /* Mini-reduction, see make_intermediate_array_64 */
+    for k in 0..4 {
+        intermediate.0[15][k] += intermediate.0[16][k] / R1DIV;
+        intermediate.0[16][k] %= R1DIV;
+    }
+    add_binary_to_intermediate_x4_avx(intermediate, binary, &ENC_TABLE_64, 8..BINARY_SZ_64);
+    adjust_intermediate_array_x4::<INTERMEDIATE_SZ_W_PADDING_64, INTERMEDIATE_SZ_64>(intermediate);
+}
+
+/// Converts the transposed intermediate arrays of 4 inputs to the
+/// registers `intermediate_to_raw` would give for each input.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn intermediate_to_raw_x4_avx<const INTERMEDIATE_SZ_W_PADDING: usize, const REGS: usize>(
+    intermediate: &IntermediateX4<INTERMEDIATE_SZ_W_PADDING>,
+) -> [[__m256i; REGS]; 4] {
+    const { assert!(INTERMEDIATE_SZ_W_PADDING == 4 * REGS) };
+    let mut raw = [[wl_bcast(0); REGS]; 4];
+    for r in 0..REGS {
+        let terms = unsafe { intermediate.0.as_ptr().add(4 * r) } as *const i64;
+        /* 4 consecutive terms of each input, with the inputs in the
+        ulongs.  Transposing gives the 4 terms of each input in one
+        register. */
+        let transposed = transpose_4x4(
+            intermediate_to_raw_per_ulong(wl_ld(terms)),
+            intermediate_to_raw_per_ulong(wl_ld(unsafe { terms.add(4) })),
+            intermediate_to_raw_per_ulong(wl_ld(unsafe { terms.add(8) })),
+            intermediate_to_raw_per_ulong(wl_ld(unsafe { terms.add(12) })),
+        );
+        for (raw, transposed) in raw.iter_mut().zip(transposed) {
+            raw[r] = compact_raw_per_ulong(transposed);
+        }
+    }
+    raw
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{decode_32, decode_64};
-    #[cfg(target_feature = "avx2")]
     use core::array::from_fn;
     use five8_const::{decode_32_const, decode_64_const};
     use five8_core::{BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN};
@@ -826,6 +1803,8 @@ mod tests {
         encoded: &str,
     ) {
         assert_eq!(&encode_32_to_string(bytes, buf), encoded);
+        let len = encode_32_scalar(Bitcoin, bytes, buf);
+        assert_eq!(&buf[..len as usize], encoded.as_bytes());
         let mut decoded = [0u8; 32];
         decode_32(encoded.as_bytes(), &mut decoded).unwrap();
         assert_eq!(&decoded, bytes);
@@ -838,6 +1817,8 @@ mod tests {
         encoded: &str,
     ) {
         assert_eq!(&encode_64_to_string(bytes, buf), encoded);
+        let len = encode_64_scalar(Bitcoin, bytes, buf);
+        assert_eq!(&buf[..len as usize], encoded.as_bytes());
         let mut decoded = [0u8; 64];
         decode_64(encoded.as_bytes(), &mut decoded).unwrap();
         assert_eq!(&decoded, bytes);
@@ -925,6 +1906,74 @@ mod tests {
         );
     }
 
+    fn check_encode_32_batch(keys: &[[u8; 32]]) {
+        let mut out = std::vec![[0u8; BASE58_ENCODED_32_MAX_LEN]; keys.len()];
+        let mut lens = std::vec![0u8; keys.len()];
+        encode_32_batch(keys, &mut out, &mut lens);
+        for ((key, out), len) in keys.iter().zip(&out).zip(&lens) {
+            let mut expected = [0u8; BASE58_ENCODED_32_MAX_LEN];
+            let expected_len = encode_32(key, &mut expected);
+            assert_eq!(out[..*len as usize], expected[..expected_len as usize]);
+        }
+    }
+
+    fn check_encode_64_batch(keys: &[[u8; 64]]) {
+        let mut out = std::vec![[0u8; BASE58_ENCODED_64_MAX_LEN]; keys.len()];
+        let mut lens = std::vec![0u8; keys.len()];
+        encode_64_batch(keys, &mut out, &mut lens);
+        for ((key, out), len) in keys.iter().zip(&out).zip(&lens) {
+            let mut expected = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let expected_len = encode_64(key, &mut expected);
+            assert_eq!(out[..*len as usize], expected[..expected_len as usize]);
+        }
+    }
+
+    #[test]
+    fn test_encode_batch_leading_zeros() {
+        /* 33 and 65 inputs, so the last one isn't part of a group of 4 */
+        let keys_32: std::vec::Vec<[u8; 32]> = (0..=32)
+            .map(|zeros| from_fn(|i| if i < zeros { 0 } else { 0xff }))
+            .collect();
+        check_encode_32_batch(&keys_32);
+        let keys_64: std::vec::Vec<[u8; 64]> = (0..=64)
+            .map(|zeros| from_fn(|i| if i < zeros { 0 } else { 0xff }))
+            .collect();
+        check_encode_64_batch(&keys_64);
+        check_encode_32_batch(&[]);
+        check_encode_64_batch(&[[1; 64]; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_encode_batch_length_mismatch() {
+        encode_32_batch(&[[0; 32]; 2], &mut [[0; 44]; 2], &mut [0; 1]);
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_encode_32_batch(keys in prop::collection::vec(uniform32(0u8..), 0..10)) {
+            check_encode_32_batch(&keys);
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_encode_64_batch(halves in prop::collection::vec((uniform32(0u8..), uniform32(0u8..)), 0..10)) {
+            let keys: std::vec::Vec<[u8; 64]> = halves
+                .iter()
+                .map(|(first_half, second_half)| {
+                    let mut combined = [0u8; 64];
+                    combined[..32].copy_from_slice(first_half);
+                    combined[32..].copy_from_slice(second_half);
+                    combined
+                })
+                .collect();
+            check_encode_64_batch(&keys);
+        }
+    }
+
     #[cfg(target_feature = "avx2")]
     #[test]
     fn test_u8s_to_u32s_swapped_32() {
@@ -980,4 +2029,193 @@ mod tests {
             assert_eq!(bs58_res, out[..len as usize].to_vec());
         }
     }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_encode_32_avx2_matches_scalar(key in uniform32(0u8..)) {
+            if std::is_x86_feature_detected!("avx2") {
+                let mut scalar_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let mut avx2_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let scalar_len = encode_32_scalar(Bitcoin, &key, &mut scalar_out);
+                let avx2_len = unsafe { encode_32_avx2(&key, &mut avx2_out) };
+                assert_eq!(scalar_out[..scalar_len as usize], avx2_out[..avx2_len as usize]);
+            }
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_encode_64_avx2_matches_scalar(first_half in uniform32(0u8..), second_half in uniform32(0u8..)) {
+            if std::is_x86_feature_detected!("avx2") {
+                let mut combined = [0u8; 64];
+                combined[..32].copy_from_slice(&first_half);
+                combined[32..].copy_from_slice(&second_half);
+                let mut scalar_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+                let mut avx2_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+                let scalar_len = encode_64_scalar(Bitcoin, &combined, &mut scalar_out);
+                let avx2_len = unsafe { encode_64_avx2(&combined, &mut avx2_out) };
+                assert_eq!(scalar_out[..scalar_len as usize], avx2_out[..avx2_len as usize]);
+            }
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    #[test]
+    fn test_encode_ssse3_leading_zeros() {
+        if !std::is_x86_feature_detected!("ssse3") {
+            return;
+        }
+        for zeros in 0..=64 {
+            let mut bytes = [0xffu8; 64];
+            bytes[..zeros].fill(0);
+            let mut scalar_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let mut ssse3_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let scalar_len = encode_64_scalar(Bitcoin, &bytes, &mut scalar_out);
+            let ssse3_len = unsafe { encode_64_ssse3(&bytes, &mut ssse3_out) };
+            assert_eq!(
+                scalar_out[..scalar_len as usize],
+                ssse3_out[..ssse3_len as usize]
+            );
+            if zeros <= 32 {
+                let mut bytes = [0xffu8; 32];
+                bytes[..zeros].fill(0);
+                let mut scalar_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let mut ssse3_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let scalar_len = encode_32_scalar(Bitcoin, &bytes, &mut scalar_out);
+                let ssse3_len = unsafe { encode_32_ssse3(&bytes, &mut ssse3_out) };
+                assert_eq!(
+                    scalar_out[..scalar_len as usize],
+                    ssse3_out[..ssse3_len as usize]
+                );
+            }
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_encode_32_ssse3_matches_scalar(key in uniform32(0u8..)) {
+            if std::is_x86_feature_detected!("ssse3") {
+                let mut scalar_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let mut ssse3_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let scalar_len = encode_32_scalar(Bitcoin, &key, &mut scalar_out);
+                let ssse3_len = unsafe { encode_32_ssse3(&key, &mut ssse3_out) };
+                assert_eq!(scalar_out[..scalar_len as usize], ssse3_out[..ssse3_len as usize]);
+            }
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
+    proptest! {
+        #[test]
+        fn proptest_encode_64_ssse3_matches_scalar(first_half in uniform32(0u8..), second_half in uniform32(0u8..)) {
+            if std::is_x86_feature_detected!("ssse3") {
+                let mut combined = [0u8; 64];
+                combined[..32].copy_from_slice(&first_half);
+                combined[32..].copy_from_slice(&second_half);
+                let mut scalar_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+                let mut ssse3_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+                let scalar_len = encode_64_scalar(Bitcoin, &combined, &mut scalar_out);
+                let ssse3_len = unsafe { encode_64_ssse3(&combined, &mut ssse3_out) };
+                assert_eq!(scalar_out[..scalar_len as usize], ssse3_out[..ssse3_len as usize]);
+            }
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    #[test]
+    fn test_encode_neon_leading_zeros() {
+        for zeros in 0..=64 {
+            let mut bytes = [0xffu8; 64];
+            bytes[..zeros].fill(0);
+            let mut scalar_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let mut neon_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let scalar_len = encode_64_scalar(Bitcoin, &bytes, &mut scalar_out);
+            let neon_len = encode_64_neon(Bitcoin, &bytes, &mut neon_out);
+            assert_eq!(
+                scalar_out[..scalar_len as usize],
+                neon_out[..neon_len as usize]
+            );
+            if zeros <= 32 {
+                let mut bytes = [0xffu8; 32];
+                bytes[..zeros].fill(0);
+                let mut scalar_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let mut neon_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+                let scalar_len = encode_32_scalar(Bitcoin, &bytes, &mut scalar_out);
+                let neon_len = encode_32_neon(Bitcoin, &bytes, &mut neon_out);
+                assert_eq!(
+                    scalar_out[..scalar_len as usize],
+                    neon_out[..neon_len as usize]
+                );
+            }
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "aarch64", target_feature = "neon"))]
+    proptest! {
+        #[test]
+        fn proptest_encode_32_neon_matches_scalar(key in uniform32(0u8..)) {
+            let mut scalar_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+            let mut neon_out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+            let scalar_len = encode_32_scalar(Bitcoin, &key, &mut scalar_out);
+            let neon_len = encode_32_neon(Bitcoin, &key, &mut neon_out);
+            assert_eq!(scalar_out[..scalar_len as usize], neon_out[..neon_len as usize]);
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "aarch64", target_feature = "neon"))]
+    proptest! {
+        #[test]
+        fn proptest_encode_64_neon_matches_scalar(first_half in uniform32(0u8..), second_half in uniform32(0u8..)) {
+            let mut combined = [0u8; 64];
+            combined[..32].copy_from_slice(&first_half);
+            combined[32..].copy_from_slice(&second_half);
+            let mut scalar_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let mut neon_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let scalar_len = encode_64_scalar(Bitcoin, &combined, &mut scalar_out);
+            let neon_len = encode_64_neon(Bitcoin, &combined, &mut neon_out);
+            assert_eq!(scalar_out[..scalar_len as usize], neon_out[..neon_len as usize]);
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64", feature = "avx512"))]
+    fn check_encode_64_avx512_matches_scalar(bytes: &[u8; 64]) {
+        if crate::cpu::avx512_available() {
+            let mut scalar_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let mut avx512_out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let scalar_len = encode_64_scalar(Bitcoin, bytes, &mut scalar_out);
+            let avx512_len = unsafe { encode_64_avx512(bytes, &mut avx512_out) };
+            assert_eq!(
+                scalar_out[..scalar_len as usize],
+                avx512_out[..avx512_len as usize]
+            );
+            // nothing is written past the end of the string
+            assert!(avx512_out[avx512_len as usize..].iter().all(|c| *c == 0));
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64", feature = "avx512"))]
+    #[test]
+    fn test_encode_64_avx512_leading_zeros() {
+        for leading_zeros in 0..=64 {
+            let mut bytes = [255u8; 64];
+            bytes[..leading_zeros].fill(0);
+            check_encode_64_avx512_matches_scalar(&bytes);
+            bytes[63] = 1;
+            check_encode_64_avx512_matches_scalar(&bytes);
+        }
+    }
+
+    #[cfg(all(not(miri), target_arch = "x86_64", feature = "avx512"))]
+    proptest! {
+        #[test]
+        fn proptest_encode_64_avx512_matches_scalar(first_half in uniform32(0u8..), second_half in uniform32(0u8..)) {
+            let mut combined = [0u8; 64];
+            combined[..32].copy_from_slice(&first_half);
+            combined[32..].copy_from_slice(&second_half);
+            check_encode_64_avx512_matches_scalar(&combined);
+        }
+    }
 }
@@ -0,0 +1,69 @@
+use core::fmt;
+
+use five8_core::DecodeError;
+
+/// An error from decoding Base58Check data, or one of the other formats
+/// that check more than the base58.
+///
+/// The checksum and version errors have a type of their own, which wraps
+/// [`DecodeError`] for the base58 errors, since adding variants to
+/// `DecodeError` would break exhaustive matches on it. The other formats
+/// return it too, with variants for the checks only they make, which is
+/// why it's non-exhaustive.
+///
+/// `N` is the length of the checksum, which is 4 bytes but for SS58's 2.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckDecodeError<const N: usize = 4> {
+    /// The data isn't base58 of the right length, or is too short to have
+    /// a checksum.
+    Decode(DecodeError),
+    /// The checksum computed from the decoded data (`expected`) is not the
+    /// one at the end of it (`found`).
+    InvalidChecksum { expected: [u8; N], found: [u8; N] },
+    /// The decoded data doesn't start with the expected version bytes.
+    InvalidVersion,
+    /// The byte after the secret key of a 38-byte WIF key isn't 0x01.
+    InvalidCompressionFlag(u8),
+    /// The key of an extended key isn't of the type, public or private,
+    /// that its version bytes say.
+    KeyTypeMismatch,
+    /// No data has a Monero block base58 encoding of this length.
+    InvalidBlockLength,
+    /// The payload isn't of a length the format allows, like an SS58
+    /// address of something other than a 32-byte account id.
+    InvalidPayloadLength,
+    /// The decoded data starts with this byte rather than the one prefix
+    /// the format allows, like 0x41 for a Tron address.
+    InvalidPrefix(u8),
+}
+
+impl<const N: usize> From<DecodeError> for CheckDecodeError<N> {
+    fn from(error: DecodeError) -> Self {
+        CheckDecodeError::Decode(error)
+    }
+}
+
+impl<const N: usize> core::error::Error for CheckDecodeError<N> {}
+
+impl<const N: usize> fmt::Display for CheckDecodeError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckDecodeError::Decode(error) => error.fmt(f),
+            CheckDecodeError::InvalidChecksum { expected, found } => write!(
+                f,
+                "Invalid checksum: expected {expected:02x?}, found {found:02x?}"
+            ),
+            CheckDecodeError::InvalidVersion => f.write_str("Invalid version bytes"),
+            CheckDecodeError::InvalidCompressionFlag(b) => {
+                write!(f, "Invalid compression flag: {b:#04x}")
+            }
+            CheckDecodeError::KeyTypeMismatch => {
+                f.write_str("Key type doesn't match the version bytes")
+            }
+            CheckDecodeError::InvalidBlockLength => f.write_str("Invalid length of the last block"),
+            CheckDecodeError::InvalidPayloadLength => f.write_str("Invalid payload length"),
+            CheckDecodeError::InvalidPrefix(b) => write!(f, "Invalid prefix: {b:#04x}"),
+        }
+    }
+}
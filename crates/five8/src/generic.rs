@@ -0,0 +1,784 @@
+//! Encoding and decoding for any array length from 1 to [`MAX_GENERIC_LEN`]
+//! bytes, using the same radix 58^5 conversion as the 32- and 64-byte
+//! functions with tables computed at compile time. On x86_64 with AVX2
+//! the other lengths have their own kernels too, with every array sized
+//! for the length at compile time.
+
+use five8_core::{
+    base58_encoded_max_len, binary_sz, dec_table, enc_table, intermediate_sz, raw58_sz, Alphabet,
+    DecodeError, N_32, N_64,
+};
+#[cfg(any(feature = "wif", feature = "bip32"))]
+use zeroize::Zeroizing;
+
+use crate::{
+    alphabet::{Bitcoin, Charset},
+    decode::{base58_decode_after_be_convert, count_chars, decode_32_charset, decode_64_charset},
+    encode::{encode_32_charset, encode_64_charset, term_to_digits, R1DIV},
+    unlikely::unlikely,
+    BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN,
+};
+#[cfg(target_arch = "x86_64")]
+use crate::{avx, cpu::avx2_available};
+
+/// The largest array length [`encode`] and [`decode`] support.
+pub const MAX_GENERIC_LEN: usize = 128;
+
+const MAX_BINARY_SZ: usize = binary_sz(MAX_GENERIC_LEN);
+const MAX_INTERMEDIATE_SZ: usize = intermediate_sz(MAX_GENERIC_LEN);
+const MAX_RAW58_SZ: usize = raw58_sz(MAX_GENERIC_LEN);
+
+/* The AVX2 code works on whole registers: it multiplies 8 columns of the
+tables at a time, converts between 4 terms and 20 digits at a time and
+between 32 digits and characters at a time, so its arrays are padded.
+The digits have a byte in front of them when decoding, see
+decode_avx2. */
+#[cfg(target_arch = "x86_64")]
+const MAX_INTERMEDIATE_SZ_W_PADDING: usize = MAX_INTERMEDIATE_SZ.next_multiple_of(4);
+#[cfg(target_arch = "x86_64")]
+const ENC_COLS_W_PADDING: usize = MAX_INTERMEDIATE_SZ.next_multiple_of(8);
+#[cfg(target_arch = "x86_64")]
+const DEC_COLS_W_PADDING: usize = (2 * MAX_BINARY_SZ).next_multiple_of(8);
+#[cfg(target_arch = "x86_64")]
+const RAW58_SZ_W_PADDING: usize = (1 + 5 * MAX_INTERMEDIATE_SZ_W_PADDING + 8).next_multiple_of(32);
+
+/* The terms are at most 2^32 * 58^5 < 2^61.3 after multiplying, so at
+most 6 of them can be added to a reduced sum before it could overflow. */
+const ROWS_PER_REDUCTION: usize = 6;
+
+/* The sizes and tables for N-byte arrays. The arrays are all sized for
+MAX_GENERIC_LEN and only the first part of them is used, since their
+sizes can't depend on N. */
+struct Sizes<const N: usize>;
+
+impl<const N: usize> Sizes<N> {
+    const BINARY_SZ: usize = binary_sz(N);
+    /* Bytes of zero padding in front of the input to make it BINARY_SZ
+    whole u32s */
+    const PAD: usize = 4 * Self::BINARY_SZ - N;
+    const INTERMEDIATE_SZ: usize = intermediate_sz(N);
+    const RAW58_SZ: usize = raw58_sz(N);
+    const ENCODED_LEN: usize = base58_encoded_max_len(N);
+    /* With a column for every intermediate term, since the first one
+    isn't always zero for lengths that aren't a multiple of 4 */
+    const ENC_TABLE: &'static [[u32; MAX_INTERMEDIATE_SZ]; MAX_BINARY_SZ] =
+        &enc_table(Self::BINARY_SZ, Self::INTERMEDIATE_SZ);
+    const DEC_TABLE: &'static [[u32; MAX_BINARY_SZ]; MAX_INTERMEDIATE_SZ] =
+        &dec_table(Self::INTERMEDIATE_SZ, Self::BINARY_SZ);
+    #[cfg(target_arch = "x86_64")]
+    const ENC_TABLE_AVX: &'static [[u32; ENC_COLS_W_PADDING]; MAX_BINARY_SZ] =
+        &enc_table(Self::BINARY_SZ, Self::INTERMEDIATE_SZ);
+    /* DEC_TABLE with each u32 column split into two 16-bit ones, most
+    significant first */
+    #[cfg(target_arch = "x86_64")]
+    const DEC_TABLE_AVX: &'static [[u32; DEC_COLS_W_PADDING]; MAX_INTERMEDIATE_SZ_W_PADDING] =
+        &split_columns(Self::DEC_TABLE);
+}
+
+#[cfg(target_arch = "x86_64")]
+const fn split_columns(
+    table: &[[u32; MAX_BINARY_SZ]; MAX_INTERMEDIATE_SZ],
+) -> [[u32; DEC_COLS_W_PADDING]; MAX_INTERMEDIATE_SZ_W_PADDING] {
+    let mut split = [[0; DEC_COLS_W_PADDING]; MAX_INTERMEDIATE_SZ_W_PADDING];
+    let mut i = 0;
+    while i < MAX_INTERMEDIATE_SZ {
+        let mut j = 0;
+        while j < MAX_BINARY_SZ {
+            split[i][2 * j] = table[i][j] >> 16;
+            split[i][2 * j + 1] = table[i][j] & 0xFFFF;
+            j += 1;
+        }
+        i += 1;
+    }
+    split
+}
+
+/// Encode an array of any length from 1 to [`MAX_GENERIC_LEN`] bytes.
+///
+/// Works like [`encode_32`]: writes the encoding to the start of `out` and
+/// returns its length. `out` must have room for the longest encoding,
+/// [`base58_encoded_max_len(N)`](base58_encoded_max_len), which is checked
+/// at compile time. 32- and 64-byte arrays use [`encode_32`] and
+/// [`encode_64`].
+///
+/// [`encode_32`]: crate::encode_32
+/// [`encode_64`]: crate::encode_64
+///
+/// # Examples
+///
+/// ```
+/// // A 20-byte hash, e.g. from a Bitcoin address.
+/// let hash = [
+///     0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3,
+///     0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6,
+/// ];
+/// let mut out = [0u8; five8::base58_encoded_max_len(20)];
+/// let len = five8::encode(&hash, &mut out);
+/// assert_eq!(&out[..len as usize], b"2ddvLKZUnFosBYkLrzayChzQUNzq");
+/// ```
+#[inline]
+pub fn encode<const N: usize, const M: usize>(bytes: &[u8; N], out: &mut [u8; M]) -> u8 {
+    encode_charset(Bitcoin, bytes, out)
+}
+
+/* encode with the characters of any alphabet */
+#[inline]
+pub(crate) fn encode_charset<C: Charset, const N: usize, const M: usize>(
+    charset: C,
+    bytes: &[u8; N],
+    out: &mut [u8; M],
+) -> u8 {
+    const {
+        assert!(N >= 1 && N <= MAX_GENERIC_LEN);
+        assert!(
+            M >= Sizes::<N>::ENCODED_LEN,
+            "the output buffer is too small"
+        );
+    }
+    /* These branches are resolved at compile time */
+    if N == N_32 {
+        let out: &mut [u8; BASE58_ENCODED_32_MAX_LEN] =
+            (&mut out[..BASE58_ENCODED_32_MAX_LEN]).try_into().unwrap();
+        return encode_32_charset(charset, bytes.as_slice().try_into().unwrap(), out);
+    }
+    if N == N_64 {
+        let out: &mut [u8; BASE58_ENCODED_64_MAX_LEN] =
+            (&mut out[..BASE58_ENCODED_64_MAX_LEN]).try_into().unwrap();
+        return encode_64_charset(charset, bytes.as_slice().try_into().unwrap(), out);
+    }
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        return unsafe { encode_avx2(charset, bytes, out) };
+    }
+    encode_scalar(charset.alphabet(), bytes, out)
+}
+
+/* The same conversion as encode_scalar using AVX2, with everything
+sized for N at compile time */
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn encode_avx2<C: Charset, const N: usize, const M: usize>(
+    charset: C,
+    bytes: &[u8; N],
+    out: &mut [u8; M],
+) -> u8 {
+    let binary_sz = Sizes::<N>::BINARY_SZ;
+    let intermediate_sz = Sizes::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Sizes::<N>::RAW58_SZ;
+
+    let in_leading_0s = bytes.iter().take_while(|b| **b == 0).count();
+
+    let mut padded = [0u8; 4 * MAX_BINARY_SZ];
+    padded[Sizes::<N>::PAD..4 * binary_sz].copy_from_slice(bytes);
+    /* Multiply the 16-bit halves of the limbs by the table separately.
+    The products are less than 2^16 * 58^5 < 2^45.3, so unlike in the
+    scalar code the sums can't overflow, and there is only one reduction
+    at the end. */
+    let mut limbs_hi = [0u64; MAX_BINARY_SZ];
+    let mut limbs_lo = [0u64; MAX_BINARY_SZ];
+    for (i, limb) in padded.chunks_exact(4).take(binary_sz).enumerate() {
+        let limb = u32::from_be_bytes(limb.try_into().unwrap());
+        limbs_hi[i] = (limb >> 16) as u64;
+        limbs_lo[i] = (limb & 0xFFFF) as u64;
+    }
+    let table = Sizes::<N>::ENC_TABLE_AVX;
+    let blocks = intermediate_sz.div_ceil(8);
+    let hi =
+        avx::table_product::<_, _, { ENC_COLS_W_PADDING / 8 }>(&limbs_hi, table, binary_sz, blocks);
+    let lo =
+        avx::table_product::<_, _, { ENC_COLS_W_PADDING / 8 }>(&limbs_lo, table, binary_sz, blocks);
+    /* Term j is hi[j] * 2^16 + lo[j].  Reduce it, splitting hi[j] so that
+    nothing overflows: the value, the carry included, stays below 2^51. */
+    let mut intermediate = [0u64; MAX_INTERMEDIATE_SZ_W_PADDING];
+    let mut carry = 0;
+    for j in (0..intermediate_sz).rev() {
+        let v = lo[j] + carry + ((hi[j] % R1DIV) << 16);
+        carry = v / R1DIV + ((hi[j] / R1DIV) << 16);
+        intermediate[j] = v % R1DIV;
+    }
+
+    /* Each ulong of intermediate_to_raw_per_ulong holds the 5 digits of a
+    term followed by 3 zero bytes, which the next term's digits
+    overwrite. */
+    let mut raw_base58 = [0u8; RAW58_SZ_W_PADDING];
+    let raw_ptr = raw_base58.as_mut_ptr();
+    for k in 0..intermediate_sz.div_ceil(4) {
+        let terms = avx::wuc_ldu(intermediate.as_ptr().add(4 * k) as *const u8);
+        let mut digits = [0u64; 4];
+        avx::wuc_stu(
+            digits.as_mut_ptr() as *mut u8,
+            avx::intermediate_to_raw_per_ulong(terms),
+        );
+        for (l, digits) in digits.into_iter().enumerate() {
+            (raw_ptr.add(20 * k + 5 * l) as *mut u64).write_unaligned(digits);
+        }
+    }
+    let raw_leading_0s = raw_base58[..raw58_sz]
+        .iter()
+        .take_while(|d| **d == 0)
+        .count();
+    let skip = raw_leading_0s - in_leading_0s;
+    let len = raw58_sz - skip;
+    let chars = avx::chars_table(charset.alphabet().as_bytes());
+    for k in 0..raw58_sz.div_ceil(32) {
+        let p = raw_ptr.add(32 * k);
+        avx::wuc_stu(p, avx::raw_to_chars::<C>(avx::wuc_ldu(p), &chars));
+    }
+    out[..len].copy_from_slice(&raw_base58[skip..raw58_sz]);
+    len as u8
+}
+
+/* encode for secret data, like private keys: always the scalar code, since
+its arrays, which hold the data in other forms, can be cleared when it
+returns */
+#[cfg(any(feature = "wif", feature = "bip32"))]
+#[inline]
+pub(crate) fn encode_secret<const N: usize, const M: usize>(
+    bytes: &[u8; N],
+    out: &mut [u8; M],
+) -> u8 {
+    const {
+        assert!(N >= 1 && N <= MAX_GENERIC_LEN);
+        assert!(
+            M >= Sizes::<N>::ENCODED_LEN,
+            "the output buffer is too small"
+        );
+    }
+    encode_scalar_in(
+        &Alphabet::BITCOIN,
+        bytes,
+        out,
+        &mut Zeroizing::new([0; 4 * MAX_BINARY_SZ]),
+        &mut Zeroizing::new([0; MAX_INTERMEDIATE_SZ]),
+        &mut Zeroizing::new([0; MAX_RAW58_SZ]),
+    )
+}
+
+#[inline(always)]
+fn encode_scalar<const N: usize, const M: usize>(
+    alphabet: &Alphabet,
+    bytes: &[u8; N],
+    out: &mut [u8; M],
+) -> u8 {
+    encode_scalar_in(
+        alphabet,
+        bytes,
+        out,
+        &mut [0; 4 * MAX_BINARY_SZ],
+        &mut [0; MAX_INTERMEDIATE_SZ],
+        &mut [0; MAX_RAW58_SZ],
+    )
+}
+
+#[inline(always)]
+fn encode_scalar_in<const N: usize, const M: usize>(
+    alphabet: &Alphabet,
+    bytes: &[u8; N],
+    out: &mut [u8; M],
+    padded: &mut [u8; 4 * MAX_BINARY_SZ],
+    intermediate: &mut [u64; MAX_INTERMEDIATE_SZ],
+    raw_base58: &mut [u8; MAX_RAW58_SZ],
+) -> u8 {
+    let binary_sz = Sizes::<N>::BINARY_SZ;
+    let intermediate_sz = Sizes::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Sizes::<N>::RAW58_SZ;
+    let table = Sizes::<N>::ENC_TABLE;
+
+    let in_leading_0s = bytes.iter().take_while(|b| **b == 0).count();
+
+    /* Convert to big-endian u32s, the first of which holds whatever bytes
+    are left over */
+    padded[Sizes::<N>::PAD..4 * binary_sz].copy_from_slice(bytes);
+
+    /* Convert to the intermediate format:
+      X = sum_i intermediate[i] * 58^(5*(INTERMEDIATE_SZ-1-i))
+    reducing every few rows so that the sums don't overflow. */
+    let intermediate = &mut intermediate[..intermediate_sz];
+    for i in 0..binary_sz {
+        let limb = u32::from_be_bytes(padded[4 * i..4 * i + 4].try_into().unwrap()) as u64;
+        let row = &table[i][..intermediate_sz];
+        for (term, multiplier) in intermediate.iter_mut().zip(row) {
+            *term += limb * *multiplier as u64;
+        }
+        if i % ROWS_PER_REDUCTION == ROWS_PER_REDUCTION - 1 {
+            reduce_intermediate(intermediate);
+        }
+    }
+    reduce_intermediate(intermediate);
+
+    for (digits, term) in raw_base58.chunks_exact_mut(5).zip(&*intermediate) {
+        term_to_digits(*term as u32, digits.try_into().unwrap());
+    }
+    /* As in the fixed-size functions, raw_base58 has at least
+    in_leading_0s leading zeros, which become the leading zero digits. */
+    let raw_leading_0s = raw_base58[..raw58_sz]
+        .iter()
+        .take_while(|d| **d == 0)
+        .count();
+    let skip = raw_leading_0s - in_leading_0s;
+    let len = raw58_sz - skip;
+    let chars = alphabet.as_bytes();
+    for (c, digit) in out[..len].iter_mut().zip(&raw_base58[skip..raw58_sz]) {
+        *c = chars[*digit as usize];
+    }
+    len as u8
+}
+
+/* Make every term but the first less than 58^5 */
+#[inline(always)]
+fn reduce_intermediate(intermediate: &mut [u64]) {
+    for i in (1..intermediate.len()).rev() {
+        intermediate[i - 1] += intermediate[i] / R1DIV;
+        intermediate[i] %= R1DIV;
+    }
+}
+
+/// Decode base58 data onto an array of any length from 1 to
+/// [`MAX_GENERIC_LEN`] bytes.
+///
+/// Works like [`decode_32`]: the decoded value must fit in exactly `N`
+/// bytes, with as many leading zero bytes as the input has leading
+/// `'1'`s. 32- and 64-byte arrays use [`decode_32`] and [`decode_64`].
+///
+/// [`decode_32`]: crate::decode_32
+/// [`decode_64`]: crate::decode_64
+///
+/// # Examples
+///
+/// ```
+/// let mut hash = [0u8; 20];
+/// five8::decode("2ddvLKZUnFosBYkLrzayChzQUNzq", &mut hash).unwrap();
+/// assert_eq!(hash[..4], [0x75, 0x1e, 0x76, 0xe8]);
+/// assert_eq!(
+///     five8::decode::<25>("2ddvLKZUnFosBYkLrzayChzQUNzq", &mut [0; 25]),
+///     Err(five8::DecodeError::TooShort)
+/// );
+/// ```
+#[inline]
+pub fn decode<const N: usize>(
+    encoded: impl AsRef<[u8]>,
+    out: &mut [u8; N],
+) -> Result<(), DecodeError> {
+    decode_charset(Bitcoin, encoded.as_ref(), out)
+}
+
+/* decode with the characters of any alphabet */
+#[inline]
+pub(crate) fn decode_charset<C: Charset, const N: usize>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N],
+) -> Result<(), DecodeError> {
+    const { assert!(N >= 1 && N <= MAX_GENERIC_LEN) };
+    /* These branches are resolved at compile time */
+    if N == N_32 {
+        return decode_32_charset(charset, encoded, out.as_mut_slice().try_into().unwrap());
+    }
+    if N == N_64 {
+        return decode_64_charset(charset, encoded, out.as_mut_slice().try_into().unwrap());
+    }
+    #[cfg(target_arch = "x86_64")]
+    if avx2_available() {
+        return unsafe { decode_avx2(charset, encoded, out) };
+    }
+    decode_scalar(charset.alphabet(), encoded, out)
+}
+
+/* The same conversion as decode_scalar using AVX2, with everything
+sized for N at compile time */
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn decode_avx2<C: Charset, const N: usize>(
+    charset: C,
+    encoded: &[u8],
+    out: &mut [u8; N],
+) -> Result<(), DecodeError> {
+    let binary_sz = Sizes::<N>::BINARY_SZ;
+    let intermediate_sz = Sizes::<N>::INTERMEDIATE_SZ;
+    let raw58_sz = Sizes::<N>::RAW58_SZ;
+    let encoded_len = Sizes::<N>::ENCODED_LEN;
+
+    /* As in the fixed-size functions, copy up to ENCODED_LEN + 1
+    characters right-aligned into a zeroed buffer and convert them in
+    place.  For some lengths RAW58_SZ is ENCODED_LEN, so the digits
+    start at raw_base58[1], leaving room for one more character. */
+    let char_cnt = encoded.len().min(encoded_len + 1);
+    let start = 1 + raw58_sz - char_cnt;
+    let mut raw_base58 = [0u8; RAW58_SZ_W_PADDING];
+    raw_base58[start..1 + raw58_sz].copy_from_slice(&encoded[..char_cnt]);
+    let raw_ptr = raw_base58.as_mut_ptr();
+    let digits = avx::digits_table(charset.alphabet().decode_table());
+    for k in 0..(1 + raw58_sz).div_ceil(32) {
+        let invalid = avx::chars_to_digits::<C>(raw_ptr.add(32 * k), &digits) as u64;
+        /* Only the bits of the characters count, and the lowest one is the
+        first bad character */
+        let from = start.saturating_sub(32 * k).min(32);
+        let to = (1 + raw58_sz).saturating_sub(32 * k).min(32);
+        let invalid = invalid & ((1 << to) - 1) & !((1 << from) - 1);
+        if unlikely(invalid != 0) {
+            let c = encoded[32 * k + invalid.trailing_zeros() as usize - start];
+            return Err(DecodeError::InvalidChar(c));
+        }
+    }
+    if unlikely(char_cnt == encoded_len + 1) {
+        return Err(DecodeError::TooLong);
+    }
+
+    let mut intermediate = [0u64; MAX_INTERMEDIATE_SZ_W_PADDING];
+    for k in 0..intermediate_sz.div_ceil(4) {
+        avx::wuc_stu(
+            intermediate.as_mut_ptr().add(4 * k) as *mut u8,
+            avx::raw_to_intermediate(raw_ptr.add(1 + 20 * k)),
+        );
+    }
+    /* With the table split into 16-bit columns, the products are less
+    than 58^5 * 2^16 < 2^45.3, so the sums can't overflow and the carries
+    are propagated once at the end. */
+    let mut binary = avx::table_product::<_, _, { DEC_COLS_W_PADDING / 8 }>(
+        &intermediate,
+        Sizes::<N>::DEC_TABLE_AVX,
+        intermediate_sz,
+        (2 * binary_sz).div_ceil(8),
+    );
+    for j in (1..2 * binary_sz).rev() {
+        binary[j - 1] += binary[j] >> 16;
+        binary[j] &= 0xFFFF;
+    }
+    /* The first limb only has room for the bytes left over */
+    let first_limb = (binary[0] as u128) << 16 | binary[1] as u128;
+    if unlikely(first_limb >> (8 * (4 - Sizes::<N>::PAD)) != 0) {
+        return Err(DecodeError::LargestTermTooHigh);
+    }
+
+    let mut padded = [0u8; 4 * MAX_BINARY_SZ];
+    for (bytes, limb) in padded.chunks_exact_mut(2).zip(&binary[..2 * binary_sz]) {
+        bytes.copy_from_slice(&(*limb as u16).to_be_bytes());
+    }
+    out.copy_from_slice(&padded[Sizes::<N>::PAD..4 * binary_sz]);
+    base58_decode_after_be_convert(out, encoded, charset.alphabet().as_bytes()[0])
+}
+
+/* decode for secret data, like encode_secret */
+#[cfg(any(feature = "wif", feature = "bip32"))]
+#[inline]
+pub(crate) fn decode_secret<const N: usize>(
+    encoded: &[u8],
+    out: &mut [u8; N],
+) -> Result<(), DecodeError> {
+    const { assert!(N >= 1 && N <= MAX_GENERIC_LEN) };
+    decode_scalar_in(
+        &Alphabet::BITCOIN,
+        encoded,
+        out,
+        &mut Zeroizing::new([0; MAX_RAW58_SZ]),
+        &mut Zeroizing::new([0; MAX_BINARY_SZ]),
+        &mut Zeroizing::new([0; 4 * MAX_BINARY_SZ]),
+    )
+}
+
+#[inline(always)]
+fn decode_scalar<const N: usize>(
+    alphabet: &Alphabet,
+    encoded: &[u8],
+    out: &mut [u8; N],
+) -> Result<(), DecodeError> {
+    decode_scalar_in(
+        alphabet,
+        encoded,
+        out,
+        &mut [0; MAX_RAW58_SZ],
+        &mut [0; MAX_BINARY_SZ],
+        &mut [0; 4 * MAX_BINARY_SZ],
+    )
+}
+
+#[inline(always)]
+fn decode_scalar_in<const N: usize>(
+    alphabet: &Alphabet,
+    encoded: &[u8],
+    out: &mut [u8; N],
+    raw_base58: &mut [u8; MAX_RAW58_SZ],
+    binary: &mut [u64; MAX_BINARY_SZ],
+    padded: &mut [u8; 4 * MAX_BINARY_SZ],
+) -> Result<(), DecodeError> {
+    let binary_sz = Sizes::<N>::BINARY_SZ;
+    let raw58_sz = Sizes::<N>::RAW58_SZ;
+    let table = Sizes::<N>::DEC_TABLE;
+
+    let decode_table = alphabet.decode_table();
+    let char_cnt = count_chars(encoded, Sizes::<N>::ENCODED_LEN, decode_table)?;
+    for (digit, c) in raw_base58[raw58_sz - char_cnt..raw58_sz]
+        .iter_mut()
+        .zip(encoded)
+    {
+        /* The characters were validated by count_chars */
+        *digit = decode_table[*c as usize];
+    }
+
+    /* Convert to u32 limbs, propagating the carries every few rows so
+    that the sums don't overflow */
+    let binary = &mut binary[..binary_sz];
+    for (i, digits) in raw_base58[..raw58_sz].chunks_exact(5).enumerate() {
+        let term = digits[0] as u64 * 11316496
+            + digits[1] as u64 * 195112
+            + digits[2] as u64 * 3364
+            + digits[3] as u64 * 58
+            + digits[4] as u64;
+        let row = &table[i][..binary_sz];
+        for (limb, multiplier) in binary.iter_mut().zip(row) {
+            *limb += term * *multiplier as u64;
+        }
+        if i % ROWS_PER_REDUCTION == ROWS_PER_REDUCTION - 1 {
+            propagate_carries(binary);
+        }
+    }
+    propagate_carries(binary);
+    /* The first limb only has room for the bytes left over */
+    if unlikely(binary[0] >> (8 * (4 - Sizes::<N>::PAD)) != 0) {
+        return Err(DecodeError::LargestTermTooHigh);
+    }
+
+    for (bytes, limb) in padded.chunks_exact_mut(4).zip(&*binary) {
+        bytes.copy_from_slice(&(*limb as u32).to_be_bytes());
+    }
+    out.copy_from_slice(&padded[Sizes::<N>::PAD..4 * binary_sz]);
+    base58_decode_after_be_convert(out, encoded, alphabet.as_bytes()[0])
+}
+
+/* Make every limb but the first less than 2^32 */
+#[inline(always)]
+fn propagate_carries(binary: &mut [u64]) {
+    for i in (1..binary.len()).rev() {
+        binary[i - 1] += binary[i] >> 32;
+        binary[i] &= 0xFFFFFFFF;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_32, decode_64, encode_64};
+    use core::array::from_fn;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+
+    #[test]
+    fn test_encoded_max_len() {
+        for n in 1..=MAX_GENERIC_LEN {
+            let max = bs58::encode(std::vec![255u8; n]).into_vec();
+            assert_eq!(base58_encoded_max_len(n), max.len(), "{n}");
+        }
+    }
+
+    fn check_generic<const N: usize, const M: usize>(bytes: &[u8; N]) {
+        let expected = bs58::encode(bytes).into_vec();
+        let mut out = [0u8; M];
+        let len = encode_scalar(&Alphabet::BITCOIN, bytes, &mut out);
+        assert_eq!(&out[..len as usize], expected, "{N}");
+        assert_eq!(encode(bytes, &mut out), len);
+        let mut decoded = [0u8; N];
+        decode_scalar(&Alphabet::BITCOIN, &expected, &mut decoded).unwrap();
+        assert_eq!(decoded, *bytes, "{N}");
+        decode(&expected, &mut decoded).unwrap();
+        assert_eq!(decoded, *bytes, "{N}");
+    }
+
+    fn check_all_sizes(seed: u8) {
+        fn check<const N: usize, const M: usize>(seed: u8) {
+            check_generic::<N, M>(&[0; N]);
+            check_generic::<N, M>(&[255; N]);
+            check_generic::<N, M>(&from_fn(|i| if i < N / 3 { 0 } else { seed ^ i as u8 }));
+            check_generic::<N, M>(&from_fn(|i| seed.wrapping_mul(i as u8 + 1)));
+        }
+        check::<1, 2>(seed);
+        check::<2, 3>(seed);
+        check::<3, 5>(seed);
+        check::<20, 28>(seed);
+        check::<25, 35>(seed);
+        check::<32, 44>(seed);
+        check::<33, 46>(seed);
+        check::<36, 50>(seed);
+        check::<64, 88>(seed);
+        check::<65, 89>(seed);
+        check::<100, 137>(seed);
+        check::<127, 174>(seed);
+        check::<128, 175>(seed);
+    }
+
+    #[test]
+    fn test_generic_sizes() {
+        check_all_sizes(0x5a);
+        check_all_sizes(0xff);
+    }
+
+    #[test]
+    fn test_generic_matches_fixed_size() {
+        /* The scalar code for 32 and 64 bytes, which encode and decode
+        don't use, gives the same results and errors as the fixed-size
+        functions */
+        let bytes: [u8; 64] = from_fn(|i| (i * 37) as u8);
+        let mut out = [0u8; 88];
+        let len = encode_scalar(&Alphabet::BITCOIN, &bytes, &mut out);
+        let mut expected = [0u8; 88];
+        assert_eq!(encode_64(&bytes, &mut expected), len);
+        assert_eq!(out, expected);
+        for encoded in [
+            "1",
+            "",
+            "11111111111111111111111111111111",
+            "111111111111111111111111111111111",
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFF",
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG",
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFFF",
+            "zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz",
+            "0EKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFF",
+            "1EKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFF",
+        ] {
+            let mut out = [0u8; 32];
+            let mut expected = [0u8; 32];
+            let res = decode_scalar(&Alphabet::BITCOIN, encoded.as_bytes(), &mut out);
+            assert_eq!(res, decode_32(encoded, &mut expected), "{encoded}");
+            assert_eq!(out, expected);
+            let mut out = [0u8; 64];
+            let mut expected = [0u8; 64];
+            let res = decode_scalar(&Alphabet::BITCOIN, encoded.as_bytes(), &mut out);
+            assert_eq!(res, decode_64(encoded, &mut expected), "{encoded}");
+        }
+    }
+
+    #[test]
+    fn test_generic_decode_errors() {
+        let mut out = [0u8; 20];
+        /* 2^160 doesn't fit, and its top limb is less than 2^32 */
+        let too_big = bs58::encode([&[1u8][..], &[0u8; 20]].concat()).into_string();
+        assert_eq!(
+            decode(&too_big, &mut out),
+            Err(DecodeError::LargestTermTooHigh)
+        );
+        let max = bs58::encode([255u8; 20]).into_string();
+        decode(&max, &mut out).unwrap();
+        assert_eq!(out, [255; 20]);
+        assert_eq!(decode("1", &mut [0u8; 25]), Err(DecodeError::TooShort));
+        assert_eq!(decode("12", &mut [0u8; 1]), Err(DecodeError::OutputTooLong));
+        assert_eq!(
+            decode("l", &mut [0u8; 1]),
+            Err(DecodeError::InvalidChar(b'l'))
+        );
+        assert_eq!(decode("2222", &mut [0u8; 2]), Err(DecodeError::TooLong));
+    }
+
+    #[cfg(any(feature = "wif", feature = "bip32"))]
+    #[test]
+    fn test_secret_arrays_cleared() {
+        use zeroize::Zeroize;
+
+        /* The arrays hold the data in other forms after converting a
+        37-byte WIF key each way, until they're cleared */
+        let secret: [u8; 37] = from_fn(|i| 0x80 | i as u8);
+        let mut encoded = [0u8; base58_encoded_max_len(37)];
+        let mut decoded = [0u8; 37];
+        let mut padded = Zeroizing::new([0; 4 * MAX_BINARY_SZ]);
+        let mut intermediate = Zeroizing::new([0; MAX_INTERMEDIATE_SZ]);
+        let mut binary = Zeroizing::new([0; MAX_BINARY_SZ]);
+        let mut raw_base58 = Zeroizing::new([0; MAX_RAW58_SZ]);
+        let len = encode_scalar_in(
+            &Alphabet::BITCOIN,
+            &secret,
+            &mut encoded,
+            &mut padded,
+            &mut intermediate,
+            &mut raw_base58,
+        );
+        assert!(padded.windows(37).any(|w| w == secret));
+        assert!(intermediate.iter().any(|term| *term != 0));
+        let encoded = &encoded[..len as usize];
+        padded.zeroize();
+        raw_base58.zeroize();
+        decode_scalar_in(
+            &Alphabet::BITCOIN,
+            encoded,
+            &mut decoded,
+            &mut raw_base58,
+            &mut binary,
+            &mut padded,
+        )
+        .unwrap();
+        assert_eq!(decoded, secret);
+        assert!(padded.windows(37).any(|w| w == secret));
+        assert!(binary.iter().any(|limb| *limb != 0));
+        assert!(raw_base58.iter().any(|digit| *digit != 0));
+        padded.zeroize();
+        intermediate.zeroize();
+        binary.zeroize();
+        raw_base58.zeroize();
+        assert_eq!(*padded, [0; 4 * MAX_BINARY_SZ]);
+        assert_eq!(*intermediate, [0; MAX_INTERMEDIATE_SZ]);
+        assert_eq!(*binary, [0; MAX_BINARY_SZ]);
+        assert_eq!(*raw_base58, [0; MAX_RAW58_SZ]);
+
+        let mut out = [0u8; base58_encoded_max_len(37)];
+        assert_eq!(encode_secret(&secret, &mut out) as usize, encoded.len());
+        assert_eq!(&out[..encoded.len()], encoded);
+        decoded = [0; 37];
+        decode_secret(encoded, &mut decoded).unwrap();
+        assert_eq!(decoded, secret);
+        assert_eq!(
+            decode_secret(b"1", &mut decoded),
+            Err(DecodeError::TooShort)
+        );
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_generic(seed in 0u8..) {
+            check_all_sizes(seed);
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_generic_decode_matches_scalar(encoded in "[0-9A-Za-z]{0,180}") {
+            /* Whichever code decode uses gives the same results and errors
+            as the scalar code, including for the lengths whose encodings
+            can be RAW58_SZ characters long */
+            fn check<const N: usize>(encoded: &str) {
+                let mut out = [0u8; N];
+                let mut expected = [0u8; N];
+                let res = decode(encoded, &mut out);
+                let expected_res =
+                    decode_scalar(&Alphabet::BITCOIN, encoded.as_bytes(), &mut expected);
+                assert_eq!(res, expected_res, "{N}");
+                if res.is_ok() {
+                    assert_eq!(out, expected, "{N}");
+                }
+            }
+            check::<3>(&encoded);
+            check::<20>(&encoded);
+            check::<25>(&encoded);
+            check::<73>(&encoded);
+            check::<82>(&encoded);
+            check::<128>(&encoded);
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_generic_decode_25(encoded in "[1-9A-Za-z]{0,40}") {
+            let mut out = [0u8; 25];
+            let res = decode(&encoded, &mut out);
+            match bs58::decode(&encoded).into_vec() {
+                Ok(expected) if expected.len() == 25 => {
+                    res.unwrap();
+                    assert_eq!(out.to_vec(), expected);
+                }
+                _ => assert!(res.is_err()),
+            }
+        }
+    }
+}
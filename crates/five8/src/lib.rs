@@ -1,17 +1,125 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![no_std]
-#[cfg(target_feature = "avx2")]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "address")]
+mod address;
+mod alphabet;
+#[cfg(target_arch = "x86_64")]
 mod avx;
 
-#[cfg(target_feature = "avx2")]
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+mod avx512;
+
+mod backend;
+
+#[cfg(feature = "bip32")]
+mod bip32;
+
+#[cfg(feature = "alloc")]
+mod bignum;
+
+#[cfg(target_arch = "x86_64")]
 mod bits_find_lsb;
 
+#[cfg(feature = "cb58")]
+mod cb58;
+
+#[cfg(feature = "check")]
+mod check;
+
+#[cfg(target_arch = "x86_64")]
+mod cpu;
+
 mod decode;
 mod encode;
-pub use decode::{decode_32, decode_64};
-pub use encode::{encode_32, encode_64};
-pub use five8_core::{DecodeError, BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN};
+mod error;
+mod generic;
+mod monero;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "alloc")]
+mod slice;
+#[cfg(feature = "ss58")]
+mod ss58;
+#[cfg(target_arch = "x86_64")]
+mod sse;
+#[cfg(all(test, any(feature = "check", feature = "cb58", feature = "ss58")))]
+mod test_util;
+#[cfg(feature = "tezos")]
+mod tezos;
+#[cfg(feature = "tron")]
+mod tron;
+#[cfg(feature = "address")]
+pub use address::{
+    decode_address, encode_address, Address, AddressKind, AddressNetwork, ADDRESS_ENCODED_MAX_LEN,
+};
+pub use alphabet::{
+    decode_32_with_alphabet, decode_64_with_alphabet, decode_with_alphabet,
+    encode_32_with_alphabet, encode_64_with_alphabet, encode_with_alphabet,
+};
+#[cfg(feature = "alloc")]
+pub use alphabet::{decode_to_vec_with_alphabet, encode_to_string_with_alphabet};
+pub use backend::{active_backend, Backend};
+#[cfg(feature = "bip32")]
+pub use bip32::{
+    decode_extended_key, encode_extended_key, ExtendedKey, ExtendedKeyData, ExtendedKeyNetwork,
+    EXTENDED_KEY_ENCODED_MAX_LEN,
+};
+#[cfg(feature = "cb58")]
+pub use cb58::{decode_cb58, encode_cb58};
+#[cfg(all(feature = "cb58", feature = "alloc"))]
+pub use cb58::{decode_cb58_to_vec, encode_cb58_to_string};
+#[cfg(feature = "check")]
+pub use check::{decode_check, encode_check, CHECKSUM_LEN};
+#[cfg(all(feature = "check", feature = "alloc"))]
+pub use check::{decode_check_to_vec, encode_check_to_string};
+pub use decode::{
+    decode_32, decode_32_batch, decode_32_with, decode_64, decode_64_batch, decode_64_with,
+    BatchDecodeError,
+};
+pub use encode::{
+    encode_32, encode_32_batch, encode_32_with, encode_64, encode_64_batch, encode_64_with,
+};
+pub use error::CheckDecodeError;
+pub use five8_core::{
+    base58_encoded_max_len, Alphabet, AlphabetError, DecodeError, BASE58_ENCODED_32_MAX_LEN,
+    BASE58_ENCODED_64_MAX_LEN,
+};
+pub use generic::{decode, encode, MAX_GENERIC_LEN};
+pub use monero::{
+    decode_monero, encode_monero, monero_encoded_len, MONERO_ADDRESS_ENCODED_LEN,
+    MONERO_ADDRESS_LEN,
+};
+#[cfg(feature = "alloc")]
+pub use monero::{decode_monero_to_vec, encode_monero_to_string};
+#[cfg(feature = "rayon")]
+pub use par::{par_decode_32, par_decode_64, par_encode_32, par_encode_64};
+#[cfg(feature = "alloc")]
+pub use slice::{decode_to_vec, encode_to_string};
+#[cfg(feature = "ss58")]
+pub use ss58::{decode_ss58, encode_ss58, Ss58Address, SS58_ENCODED_MAX_LEN};
+#[cfg(feature = "tezos")]
+pub use tezos::{
+    decode_tezos, encode_tezos, TezosKind, TezosPayloadLengthError, TezosValue,
+    TEZOS_ENCODED_MAX_LEN,
+};
+#[cfg(feature = "tron")]
+pub use tron::{
+    decode_tron_address, encode_tron_address, TronAddress, TRON_ADDRESS_ENCODED_LEN,
+    TRON_ADDRESS_PREFIX,
+};
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+pub use {decode::decode_64_avx512, encode::encode_64_avx512};
+#[cfg(target_arch = "x86_64")]
+pub use {
+    decode::{decode_32_avx2, decode_32_ssse3, decode_64_avx2, decode_64_ssse3},
+    encode::{encode_32_avx2, encode_32_ssse3, encode_64_avx2, encode_64_ssse3},
+};
 #[cfg(feature = "dev-utils")]
 pub use {
     decode::{truncate_and_swap_u64s_64_pub, truncate_and_swap_u64s_scalar_pub},
@@ -22,3 +130,7 @@ pub use {
     },
 };
 mod unlikely;
+#[cfg(feature = "wif")]
+mod wif;
+#[cfg(feature = "wif")]
+pub use wif::{decode_wif, encode_wif, WifKey, WifNetwork, WIF_ENCODED_MAX_LEN};
@@ -0,0 +1,437 @@
+//! Monero's block base58, which encodes each 8 bytes as exactly 11
+//! characters and a final partial block of 1 to 7 bytes as 2 to 10
+//! characters, so the encoded length only depends on the data length.
+//!
+//! An 8-byte block is a u64 below 58^11, so it splits into three radix 58^5
+//! terms like the intermediate form in encode.rs and decode.rs, the first
+//! of which is a single digit.
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+
+use five8_core::{Alphabet, DecodeError, BASE58_INVERSE, BASE58_INVERSE_TABLE_OFFSET};
+
+use crate::{
+    decode::count_chars,
+    encode::{term_to_digits, BASE58_CHARS, R1DIV},
+    CheckDecodeError,
+};
+
+const BLOCK_LEN: usize = 8;
+const ENCODED_BLOCK_LEN: usize = 11;
+/* The number of characters for a block of 0 to 8 bytes: the length of
+the encoding of 256^n - 1 */
+const ENCODED_BLOCK_LENS: [usize; BLOCK_LEN + 1] = [0, 2, 3, 5, 6, 7, 9, 10, 11];
+
+/// The length of a standard Monero address before encoding: a network
+/// byte, two 32-byte public keys and a 4-byte checksum.
+pub const MONERO_ADDRESS_LEN: usize = 69;
+/// The length of an encoded standard Monero address.
+pub const MONERO_ADDRESS_ENCODED_LEN: usize = monero_encoded_len(MONERO_ADDRESS_LEN);
+
+/// The length of the Monero encoding of `len` bytes.
+pub const fn monero_encoded_len(len: usize) -> usize {
+    len / BLOCK_LEN * ENCODED_BLOCK_LEN + ENCODED_BLOCK_LENS[len % BLOCK_LEN]
+}
+
+/* The length of the data with an encoding of `encoded_len` characters, if
+any data has one that long */
+const fn monero_decoded_len(encoded_len: usize) -> Option<usize> {
+    let tail = encoded_len % ENCODED_BLOCK_LEN;
+    let mut len = 0;
+    while len < BLOCK_LEN {
+        if ENCODED_BLOCK_LENS[len] == tail {
+            return Some(encoded_len / ENCODED_BLOCK_LEN * BLOCK_LEN + len);
+        }
+        len += 1;
+    }
+    None
+}
+
+/// Encode data with Monero's block base58.
+///
+/// `out` must be exactly [`monero_encoded_len(N)`](monero_encoded_len)
+/// long, which is checked at compile time. The standard address length is
+/// [`MONERO_ADDRESS_LEN`].
+///
+/// # Examples
+///
+/// ```
+/// let mut out = [0u8; five8::monero_encoded_len(13)];
+/// five8::encode_monero(
+///     &[0x06, 0x15, 0x60, 0x13, 0x76, 0x28, 0x79, 0xf7, 0xff, 0xff, 0xff, 0xff, 0xff],
+///     &mut out,
+/// );
+/// assert_eq!(&out, b"22222222222VtB5VXc");
+/// ```
+#[inline]
+pub fn encode_monero<const N: usize, const M: usize>(bytes: &[u8; N], out: &mut [u8; M]) {
+    const {
+        assert!(
+            M == monero_encoded_len(N),
+            "the output buffer must be monero_encoded_len(N) long"
+        )
+    };
+    encode_blocks(bytes, out);
+}
+
+/// Decode data encoded with Monero's block base58.
+///
+/// A length that no data encodes to gives
+/// [`CheckDecodeError::InvalidBlockLength`]. The base58 errors come in
+/// [`CheckDecodeError::Decode`]: other lengths than
+/// [`monero_encoded_len(N)`](monero_encoded_len) give
+/// [`DecodeError::TooShort`] or [`DecodeError::TooLong`], and a block whose
+/// value doesn't fit in its number of bytes gives
+/// [`DecodeError::OutputTooLong`].
+///
+/// # Examples
+///
+/// ```
+/// use five8::{CheckDecodeError, DecodeError};
+///
+/// let mut out = [0u8; 69];
+/// five8::decode_monero(
+///     "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A",
+///     &mut out,
+/// )
+/// .unwrap();
+/// assert_eq!(out[0], 18);
+/// assert_eq!(
+///     five8::decode_monero("5R", &mut [0u8; 1]),
+///     Err(CheckDecodeError::Decode(DecodeError::OutputTooLong))
+/// );
+/// assert_eq!(
+///     five8::decode_monero("1", &mut [0u8; 1]),
+///     Err(CheckDecodeError::InvalidBlockLength)
+/// );
+/// ```
+#[inline]
+pub fn decode_monero<const N: usize>(
+    encoded: impl AsRef<[u8]>,
+    out: &mut [u8; N],
+) -> Result<(), CheckDecodeError> {
+    let encoded = encoded.as_ref();
+    if encoded.len() != monero_encoded_len(N) {
+        return match monero_decoded_len(encoded.len()) {
+            None => Err(CheckDecodeError::InvalidBlockLength),
+            Some(len) if len < N => Err(DecodeError::TooShort.into()),
+            Some(_) => Err(DecodeError::TooLong.into()),
+        };
+    }
+    Ok(decode_blocks(encoded, out)?)
+}
+
+/// Encode data of any length with Monero's block base58.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8::encode_monero_to_string(&[0xff; 8]), "jpXCZedGfVQ");
+/// assert_eq!(five8::encode_monero_to_string(&[0, 0]), "111");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_monero_to_string(bytes: &[u8]) -> String {
+    let mut out = vec![0u8; monero_encoded_len(bytes.len())];
+    encode_blocks(bytes, &mut out);
+    /* The base58 alphabet is ASCII */
+    unsafe { String::from_utf8_unchecked(out) }
+}
+
+/// Decode Monero block base58 data of any length, with the same errors as
+/// [`decode_monero`] except for `TooShort` and `TooLong`.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8::decode_monero_to_vec("jpXCZedGfVQ").unwrap(), [0xff; 8]);
+/// assert_eq!(
+///     five8::decode_monero_to_vec("jpXCZedGfVQ1"),
+///     Err(five8::CheckDecodeError::InvalidBlockLength)
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_monero_to_vec<I: AsRef<[u8]>>(encoded: I) -> Result<Vec<u8>, CheckDecodeError> {
+    let encoded = encoded.as_ref();
+    let len = monero_decoded_len(encoded.len()).ok_or(CheckDecodeError::InvalidBlockLength)?;
+    let mut out = vec![0u8; len];
+    decode_blocks(encoded, &mut out)?;
+    Ok(out)
+}
+
+/* `out` must be monero_encoded_len(bytes.len()) long, so that its last
+chunk has room for exactly the last block.
+
+The full blocks are converted as arrays, without the leading zero
+handling and the lengths of a partial block.  With a fixed length, like
+MONERO_ADDRESS_LEN in encode_monero, the loop is unrolled into straight
+line code, which is the fast path for addresses. */
+#[inline(always)]
+fn encode_blocks(bytes: &[u8], out: &mut [u8]) {
+    let mut blocks = bytes.chunks_exact(BLOCK_LEN);
+    let mut chunks = out.chunks_exact_mut(ENCODED_BLOCK_LEN);
+    for (block, chars) in (&mut blocks).zip(&mut chunks) {
+        let digits = block_digits(u64::from_be_bytes(block.try_into().unwrap()));
+        for (c, digit) in chars.iter_mut().zip(digits) {
+            *c = BASE58_CHARS[digit as usize];
+        }
+    }
+    let block = blocks.remainder();
+    if !block.is_empty() {
+        let v = block.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+        let digits = block_digits(v);
+        /* A partial block's value has fewer digits than it has characters,
+        so only leading zeros are dropped */
+        let chars = chunks.into_remainder();
+        let skip = ENCODED_BLOCK_LEN - chars.len();
+        for (c, digit) in chars.iter_mut().zip(&digits[skip..]) {
+            *c = BASE58_CHARS[*digit as usize];
+        }
+    }
+}
+
+#[inline(always)]
+fn block_digits(v: u64) -> [u8; ENCODED_BLOCK_LEN] {
+    /* v = hi * 58^10 + mid * 58^5 + lo, and hi < 58 since v < 58^11 */
+    let lo = (v % R1DIV) as u32;
+    let mid = ((v / R1DIV) % R1DIV) as u32;
+    let hi = (v / (R1DIV * R1DIV)) as u8;
+    let mut digits = [0u8; ENCODED_BLOCK_LEN];
+    digits[0] = hi;
+    term_to_digits(mid, (&mut digits[1..6]).try_into().unwrap());
+    term_to_digits(lo, (&mut digits[6..]).try_into().unwrap());
+    digits
+}
+
+/* `encoded` must be monero_encoded_len(out.len()) long.  As in
+encode_blocks, the full blocks are converted as arrays. */
+#[inline(always)]
+fn decode_blocks(encoded: &[u8], out: &mut [u8]) -> Result<(), DecodeError> {
+    count_chars(encoded, encoded.len(), Alphabet::BITCOIN.decode_table())?;
+    let mut chunks = encoded.chunks_exact(ENCODED_BLOCK_LEN);
+    let mut blocks = out.chunks_exact_mut(BLOCK_LEN);
+    for (chars, block) in (&mut chunks).zip(&mut blocks) {
+        let mut digits = [0u8; ENCODED_BLOCK_LEN];
+        for (digit, c) in digits.iter_mut().zip(chars) {
+            *digit = char_to_digit(*c);
+        }
+        /* hi * 58^10 overflows a u64 for the blocks that are too big */
+        let v = (digits[0] as u64)
+            .checked_mul(R1DIV * R1DIV)
+            .and_then(|hi| hi.checked_add(lo_terms(&digits)))
+            .ok_or(DecodeError::OutputTooLong)?;
+        block.copy_from_slice(&v.to_be_bytes());
+    }
+    let chars = chunks.remainder();
+    if !chars.is_empty() {
+        let block = blocks.into_remainder();
+        let mut digits = [0u8; ENCODED_BLOCK_LEN];
+        for (digit, c) in digits[ENCODED_BLOCK_LEN - chars.len()..]
+            .iter_mut()
+            .zip(chars)
+        {
+            *digit = char_to_digit(*c);
+        }
+        /* A partial block has at most 10 characters, so its value is
+        below 58^10 and fits in a u64 */
+        let v = lo_terms(&digits);
+        if v >> (8 * block.len()) != 0 {
+            return Err(DecodeError::OutputTooLong);
+        }
+        block.copy_from_slice(&v.to_be_bytes()[BLOCK_LEN - block.len()..]);
+    }
+    Ok(())
+}
+
+#[inline(always)]
+fn char_to_digit(c: u8) -> u8 {
+    /* The characters were validated by count_chars */
+    BASE58_INVERSE[(c - BASE58_INVERSE_TABLE_OFFSET) as usize]
+}
+
+/* mid * 58^5 + lo for the digits of a block, which is below 58^10 */
+#[inline(always)]
+fn lo_terms(digits: &[u8; ENCODED_BLOCK_LEN]) -> u64 {
+    let term = |digits: &[u8]| {
+        digits[0] as u64 * 11316496
+            + digits[1] as u64 * 195112
+            + digits[2] as u64 * 3364
+            + digits[3] as u64 * 58
+            + digits[4] as u64
+    };
+    term(&digits[1..6]) * R1DIV + term(&digits[6..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::array::from_fn;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::vec::Vec;
+
+    /* Each block as a big-endian number with bs58, padded with '1's */
+    fn reference_encode(bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for block in bytes.chunks(BLOCK_LEN) {
+            let v = block.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            /* The digits of v, without the '1's for the leading zero bytes */
+            let encoded = bs58::encode(v.to_be_bytes()).into_vec();
+            let encoded: Vec<u8> = encoded.into_iter().skip_while(|c| *c == b'1').collect();
+            let width = ENCODED_BLOCK_LENS[block.len()];
+            out.resize(out.len() + width - encoded.len(), b'1');
+            out.extend_from_slice(&encoded);
+        }
+        out
+    }
+
+    fn check_monero<const N: usize, const M: usize>(bytes: &[u8; N]) {
+        let expected = reference_encode(bytes);
+        let mut out = [0u8; M];
+        encode_monero(bytes, &mut out);
+        assert_eq!(out.as_slice(), expected);
+        let mut decoded = [0u8; N];
+        decode_monero(out, &mut decoded).unwrap();
+        assert_eq!(&decoded, bytes);
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(encode_monero_to_string(bytes).as_bytes(), expected);
+            assert_eq!(decode_monero_to_vec(&expected).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_monero_vectors() {
+        /* From the Monero base58 tests */
+        for (hex, encoded) in [
+            ("00", "11"),
+            ("39", "1z"),
+            ("ff", "5Q"),
+            ("0000", "111"),
+            ("ffff", "LUv"),
+            ("00000000000000", "1111111111"),
+            ("0000000000000000", "11111111111"),
+            ("ffffffffffffffff", "jpXCZedGfVQ"),
+            ("06156013762879f7ffffffffff", "22222222222VtB5VXc"),
+        ] {
+            let bytes: Vec<u8> = (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect();
+            assert_eq!(reference_encode(&bytes), encoded.as_bytes());
+            let mut out = [0u8; ENCODED_BLOCK_LEN * 2];
+            let mut decoded = [0u8; BLOCK_LEN * 2];
+            encode_blocks(&bytes, &mut out[..encoded.len()]);
+            assert_eq!(&out[..encoded.len()], encoded.as_bytes());
+            decode_blocks(encoded.as_bytes(), &mut decoded[..bytes.len()]).unwrap();
+            assert_eq!(&decoded[..bytes.len()], bytes);
+        }
+    }
+
+    #[test]
+    fn test_monero_sizes() {
+        fn check<const N: usize, const M: usize>() {
+            check_monero::<N, M>(&[0; N]);
+            check_monero::<N, M>(&[0xff; N]);
+            check_monero::<N, M>(&from_fn(|i| (i * 73 + 5) as u8));
+        }
+        check::<1, 2>();
+        check::<7, 10>();
+        check::<8, 11>();
+        check::<9, 13>();
+        check::<32, 44>();
+        check::<MONERO_ADDRESS_LEN, MONERO_ADDRESS_ENCODED_LEN>();
+        check::<77, 106>();
+        assert_eq!(MONERO_ADDRESS_ENCODED_LEN, 95);
+    }
+
+    #[test]
+    fn test_monero_decoded_len() {
+        for len in 0..100 {
+            assert_eq!(monero_decoded_len(monero_encoded_len(len)), Some(len));
+        }
+        for encoded_len in [1, 4, 8, 12, 15, 19, 89] {
+            assert_eq!(monero_decoded_len(encoded_len), None);
+        }
+    }
+
+    #[test]
+    fn test_monero_errors() {
+        let mut out = [0u8; 8];
+        assert_eq!(
+            decode_monero("jpXCZedGfVR", &mut out),
+            Err(DecodeError::OutputTooLong.into())
+        );
+        assert_eq!(
+            decode_monero("zzzzzzzzzzz", &mut out),
+            Err(DecodeError::OutputTooLong.into())
+        );
+        assert_eq!(
+            decode_monero("jpXCZedGfV0", &mut out),
+            Err(DecodeError::InvalidChar(b'0').into())
+        );
+        assert_eq!(
+            decode_monero("jpXCZedGfV", &mut out),
+            Err(DecodeError::TooShort.into())
+        );
+        assert_eq!(
+            decode_monero("jpXCZedGfVQ11", &mut out),
+            Err(DecodeError::TooLong.into())
+        );
+        assert_eq!(
+            decode_monero("jpXCZedG", &mut out),
+            Err(CheckDecodeError::InvalidBlockLength)
+        );
+        /* Only the value of the last block is limited by a partial length */
+        assert_eq!(
+            decode_monero("11111111111LUw", &mut [0u8; 10]),
+            Err(DecodeError::OutputTooLong.into())
+        );
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(decode_monero_to_vec("").unwrap(), []);
+            assert_eq!(
+                decode_monero_to_vec("1"),
+                Err(CheckDecodeError::InvalidBlockLength)
+            );
+            assert_eq!(
+                decode_monero_to_vec("11111111111LUw"),
+                Err(DecodeError::OutputTooLong.into())
+            );
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_monero_address(bytes in prop::collection::vec(0u8.., MONERO_ADDRESS_LEN)) {
+            check_monero::<MONERO_ADDRESS_LEN, MONERO_ADDRESS_ENCODED_LEN>(&bytes.try_into().unwrap());
+        }
+
+        #[test]
+        fn proptest_monero_decode(encoded in "[1-9A-Za-z]{0,40}") {
+            let expected: Option<Vec<u8>> = monero_decoded_len(encoded.len()).and_then(|_| {
+                let mut out = Vec::new();
+                for chars in encoded.as_bytes().chunks(ENCODED_BLOCK_LEN) {
+                    let len = ENCODED_BLOCK_LENS.iter().position(|l| *l == chars.len()).unwrap();
+                    let v = bs58::decode(chars).into_vec().ok()?;
+                    let v = v.iter().fold(0u128, |acc, b| (acc << 8) | *b as u128);
+                    if v >> (8 * len) != 0 {
+                        return None;
+                    }
+                    out.extend_from_slice(&v.to_be_bytes()[16 - len..]);
+                }
+                Some(out)
+            });
+            let mut out = [0u8; 29];
+            let res = match encoded.len() {
+                40 => decode_monero(&encoded, &mut out).map(|()| out.to_vec()),
+                _ => monero_decoded_len(encoded.len())
+                    .ok_or(CheckDecodeError::InvalidBlockLength)
+                    .and_then(|len| Ok(decode_blocks(encoded.as_bytes(), &mut out[..len]).map(|()| out[..len].to_vec())?)),
+            };
+            assert_eq!(res.ok(), expected);
+        }
+    }
+}
@@ -0,0 +1,365 @@
+/* NEON versions of the helpers in avx.rs, for aarch64.  NEON registers
+are 128 bits wide, so each helper works on half as many terms as its
+AVX2 counterpart, but since there's no 128-bit lane boundary to work
+around, the shuffles are mostly simpler. */
+use core::arch::aarch64::{
+    uint64x2_t, uint8x16_t, uint8x16x4_t, vaddq_u8, vandq_u8, vcgtq_u8, vcombine_u32, vdupq_n_u8,
+    vextq_u8, vget_lane_u64, vld1q_u64, vld1q_u8, vmls_n_u32, vmovn_u64, vmull_n_u32, vorr_u32,
+    vorrq_u8, vqtbl1q_u8, vqtbl4q_u8, vreinterpret_u64_u8, vreinterpretq_u16_u8,
+    vreinterpretq_u32_u64, vreinterpretq_u8_u32, vrev32q_u8, vshl_n_u32, vshr_n_u32, vshrn_n_u16,
+    vshrq_n_u64, vst1q_u8, vsubq_u8, vtstq_u8, vuzp1q_u32,
+};
+
+use crate::alphabet::Charset;
+
+#[inline(always)]
+pub(crate) fn ld(p: *const u8) -> uint8x16_t {
+    unsafe { vld1q_u8(p) }
+}
+
+#[inline(always)]
+pub(crate) fn st(p: *mut u8, i: uint8x16_t) {
+    unsafe { vst1q_u8(p, i) };
+}
+
+#[inline(always)]
+pub(crate) fn ld_u64(p: *const u64) -> uint64x2_t {
+    unsafe { vld1q_u64(p) }
+}
+
+/* Reverses the bytes of each 4-byte word in the 16 bytes at p, turning
+big endian u32s into native (little endian) ones. */
+#[inline(always)]
+pub(crate) fn u8s_to_u32s_swapped(p: *const u8) -> uint8x16_t {
+    unsafe { vrev32q_u8(ld(p)) }
+}
+
+/* The inverse direction for decoding: takes the 4 u64s at p, each less
+than 2^32, and returns the low 4 bytes of each in big endian order. */
+#[inline(always)]
+pub(crate) fn truncate_and_swap_u64s(p: *const u64) -> uint8x16_t {
+    unsafe {
+        let lo = vreinterpretq_u32_u64(ld_u64(p));
+        let hi = vreinterpretq_u32_u64(ld_u64(p.add(2)));
+        vrev32q_u8(vreinterpretq_u8_u32(vuzp1q_u32(lo, hi)))
+    }
+}
+
+/* Same as intermediate_to_raw in avx.rs, but for two terms at a time.
+Refer to the comment there for how the divisions work; the constants
+are the same.  NEON has a 32x32->64 multiply by a scalar (umull), so
+the quotients live in 32-bit lanes and only widen for the multiply.
+
+The output is the 10 digits of the two terms in place value order,
+followed by 6 zero bytes:
+
+  [ a b c d e f g h i j 0 0 0 0 0 0 ]
+
+i.e. the same format as one half of the AVX2 output, which means
+ten_per_slot_down can use the same sequence of byte shifts. */
+#[inline(always)]
+pub(crate) fn intermediate_to_raw(intermediate: uint64x2_t) -> uint8x16_t {
+    unsafe {
+        let div3364 = |r| {
+            vmovn_u64(vshrq_n_u64::<40>(vmull_n_u32(
+                vshr_n_u32::<2>(r),
+                1307386003,
+            )))
+        };
+        /* div(k) stores floor(x/58^k). rem(k) stores div(k) % 58 */
+        let div0 = vmovn_u64(intermediate);
+        let div1 = vmovn_u64(vshrq_n_u64::<37>(vmull_n_u32(div0, 2369637129)));
+        let rem0 = vmls_n_u32(div0, div1, 58);
+        let div2 = div3364(div0);
+        let rem1 = vmls_n_u32(div1, div2, 58);
+        let div3 = div3364(div1);
+        let rem2 = vmls_n_u32(div2, div3, 58);
+        let div4 = div3364(div2);
+        let rem3 = vmls_n_u32(div3, div4, 58);
+        let rem4 = div4;
+        /* The first four digits of each term fit in one 32-bit lane:
+
+          [ a b c d f g h i ]
+
+        and then a table lookup interleaves them with the last digit of
+        each term, which is in the low byte of each lane of rem0. */
+        let first4 = vorr_u32(
+            vorr_u32(rem4, vshl_n_u32::<8>(rem3)),
+            vorr_u32(vshl_n_u32::<16>(rem2), vshl_n_u32::<24>(rem1)),
+        );
+        let both = vreinterpretq_u8_u32(vcombine_u32(first4, rem0));
+        const IDX: [u8; 16] = [0, 1, 2, 3, 8, 4, 5, 6, 7, 12, 16, 16, 16, 16, 16, 16];
+        vqtbl1q_u8(both, ld(IDX.as_ptr()))
+    }
+}
+
+/* Converts each byte in the NEON register from raw base58 [0,58) to
+base58 digits ('1'-'z', with some skips).  Same arithmetic map as the
+AVX2 version:
+  b58ch(x) = '1' + x + 7*[x>8] + [x>16] + [x>21] + 6*[x>32] + [x>43]
+The comparisons are unsigned here, but that doesn't matter for inputs
+in [0, 58). */
+#[inline(always)]
+pub(crate) fn raw_to_base58(in_: uint8x16_t) -> uint8x16_t {
+    unsafe {
+        let gt0 = vcgtq_u8(in_, vdupq_n_u8(8)); /* skip 7 */
+        let gt1 = vcgtq_u8(in_, vdupq_n_u8(16));
+        let gt2 = vcgtq_u8(in_, vdupq_n_u8(21));
+        let gt3 = vcgtq_u8(in_, vdupq_n_u8(32)); /* skip 6 */
+        let gt4 = vcgtq_u8(in_, vdupq_n_u8(43));
+        /* The comparisons give 0xFF (=-1) for true, so as in the AVX2
+        version we subtract the sum of the masks, and AND with -7 and -6
+        to multiply. */
+        let gt0_7 = vandq_u8(gt0, vdupq_n_u8(-7i8 as u8));
+        let gt3_6 = vandq_u8(gt3, vdupq_n_u8(-6i8 as u8));
+        let sum = vaddq_u8(
+            vaddq_u8(
+                vaddq_u8(vdupq_n_u8(-('1' as i8) as u8), gt1),
+                vaddq_u8(gt2, gt4),
+            ),
+            vaddq_u8(gt0_7, gt3_6),
+        );
+        vsubq_u8(in_, sum)
+    }
+}
+
+/* chars_table loads an alphabet's 58 characters into a 64-byte table
+for tbl, zero padded.  The last register is loaded 6 bytes early and
+shifted down so that it doesn't read past the characters. */
+#[inline(always)]
+pub(crate) fn chars_table(chars: &[u8; 58]) -> uint8x16x4_t {
+    let p = chars.as_ptr();
+    unsafe {
+        uint8x16x4_t(
+            ld(p),
+            ld(p.add(16)),
+            ld(p.add(32)),
+            vextq_u8::<6>(ld(p.add(42)), vdupq_n_u8(0)),
+        )
+    }
+}
+
+/* raw_to_chars is raw_to_base58 for any alphabet: the Bitcoin alphabet
+keeps the arithmetic, and the others are looked up in their chars_table
+with a single tbl. */
+#[inline(always)]
+pub(crate) fn raw_to_chars<C: Charset>(in_: uint8x16_t, chars: &uint8x16x4_t) -> uint8x16_t {
+    if C::BITCOIN {
+        raw_to_base58(in_)
+    } else {
+        unsafe { vqtbl4q_u8(*chars, in_) }
+    }
+}
+
+/* NEON has no movemask, but narrowing each 16-bit lane by a shift of 4
+keeps the high nibble of the low byte and the low nibble of the high
+byte, so a byte mask from a comparison turns into a u64 with 4 bits per
+byte, in order. */
+#[inline(always)]
+fn nonzero_nibble_mask(in_: uint8x16_t) -> u64 {
+    unsafe {
+        let nonzero = vreinterpretq_u16_u8(vtstq_u8(in_, in_));
+        vget_lane_u64::<0>(vreinterpret_u64_u8(vshrn_n_u16::<4>(nonzero)))
+    }
+}
+
+/* count_leading_zeros_{n} counts the number of zero bytes prior to the
+first non-zero byte in the first n bytes.  If all n bytes are zero,
+returns n.  Return value is in [0, n].  in_[0] contains the first 16
+bytes, in_[1] the next 16 and so on.  Bytes past n have to be zero. */
+#[inline(always)]
+fn count_leading_zeros<const REGISTERS: usize>(in_: &[uint8x16_t; REGISTERS], n: u64) -> u64 {
+    let mut cnt = 0;
+    for register in in_ {
+        let mask = nonzero_nibble_mask(*register);
+        if mask != 0 {
+            return cnt + mask.trailing_zeros() as u64 / 4;
+        }
+        cnt += 16;
+    }
+    n
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_32(in_: &[uint8x16_t; 2]) -> u64 {
+    count_leading_zeros(in_, 32)
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_45(in_: &[uint8x16_t; 3]) -> u64 {
+    count_leading_zeros(in_, 45)
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_64(in_: &[uint8x16_t; 4]) -> u64 {
+    count_leading_zeros(in_, 64)
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_90(in_: &[uint8x16_t; 6]) -> u64 {
+    count_leading_zeros(in_, 90)
+}
+
+/* ten_per_slot_down_{32,64}: Packs {45,90} raw base58 digits stored in
+the groups of 10 format from intermediate_to_raw into {3,6} NEON
+registers with the digits stored contiguously.  The byte shifts are
+the ones from the diagrams in avx.rs.  vextq_u8 takes bytes from the
+concatenation of its arguments, so extracting from (zero, x) moves x
+later in memory (a left shift in AVX terms), and from (x, zero) moves it
+earlier. */
+#[inline(always)]
+pub(crate) fn ten_per_slot_down_32(in_: &[uint8x16_t; 5]) -> [uint8x16_t; 3] {
+    unsafe {
+        let z = vdupq_n_u8(0);
+        let [g0, g1, g2, g3, g4] = *in_;
+        let o0 = vorrq_u8(g0, vextq_u8::<6>(z, g1));
+        let o1 = vorrq_u8(
+            vorrq_u8(vextq_u8::<6>(g1, z), vextq_u8::<12>(z, g2)),
+            vextq_u8::<2>(z, g3),
+        );
+        let o2 = vorrq_u8(vextq_u8::<2>(g3, z), vextq_u8::<8>(z, g4));
+        [o0, o1, o2]
+    }
+}
+
+#[inline(always)]
+pub(crate) fn ten_per_slot_down_64(in_: &[uint8x16_t; 9]) -> [uint8x16_t; 6] {
+    unsafe {
+        let z = vdupq_n_u8(0);
+        let [g0, g1, g2, g3, g4, g5, g6, g7, g8] = *in_;
+        let o0 = vorrq_u8(g0, vextq_u8::<6>(z, g1));
+        let o1 = vorrq_u8(
+            vorrq_u8(vextq_u8::<6>(g1, z), vextq_u8::<12>(z, g2)),
+            vextq_u8::<2>(z, g3),
+        );
+        let o2 = vorrq_u8(vextq_u8::<2>(g3, z), vextq_u8::<8>(z, g4));
+        let o3 = vorrq_u8(
+            vorrq_u8(vextq_u8::<8>(g4, z), vextq_u8::<14>(z, g5)),
+            vextq_u8::<4>(z, g6),
+        );
+        let o4 = vorrq_u8(vextq_u8::<4>(g6, z), vextq_u8::<10>(z, g7));
+        [o0, o1, o2, o3, o4, g8]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::array::from_fn;
+    use core::mem::transmute;
+    use five8_core::Alphabet;
+
+    fn to_bytes(v: uint8x16_t) -> [u8; 16] {
+        unsafe { transmute(v) }
+    }
+
+    fn digits(x: u64) -> [u8; 5] {
+        from_fn(|k| ((x / 58u64.pow(4 - k as u32)) % 58) as u8)
+    }
+
+    #[test]
+    fn test_intermediate_to_raw() {
+        let samples = [
+            0, 1, 57, 58, 3363, 11316496, 656356767, 123456789, 656356766,
+        ];
+        for a in samples {
+            for b in samples {
+                let terms = [a, b];
+                let raw = to_bytes(intermediate_to_raw(ld_u64(terms.as_ptr())));
+                let mut expected = [0u8; 16];
+                expected[..5].copy_from_slice(&digits(a));
+                expected[5..10].copy_from_slice(&digits(b));
+                assert_eq!(raw, expected, "{a} {b}");
+            }
+        }
+    }
+
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    #[test]
+    fn test_raw_to_base58() {
+        let raw: [u8; 64] = from_fn(|i| (i % 58) as u8);
+        for k in 0..4 {
+            let chars = to_bytes(raw_to_base58(ld(raw[16 * k..].as_ptr())));
+            let expected: [u8; 16] = from_fn(|i| ALPHABET[raw[16 * k + i] as usize]);
+            assert_eq!(chars, expected);
+        }
+    }
+
+    #[test]
+    fn test_raw_to_chars() {
+        let raw: [u8; 64] = from_fn(|i| (i % 58) as u8);
+        for alphabet in [Alphabet::RIPPLE, Alphabet::FLICKR] {
+            let table = chars_table(alphabet.as_bytes());
+            for k in 0..4 {
+                let chars = to_bytes(raw_to_chars::<&Alphabet>(
+                    ld(raw[16 * k..].as_ptr()),
+                    &table,
+                ));
+                let expected: [u8; 16] = from_fn(|i| alphabet.as_bytes()[raw[16 * k + i] as usize]);
+                assert_eq!(chars, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_leading_zeros() {
+        for n in 0..=90 {
+            let mut bytes = [0u8; 96];
+            if n < 90 {
+                bytes[n] = 1 + n as u8;
+                bytes[89] = 1;
+            }
+            let regs: [uint8x16_t; 6] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+            assert_eq!(count_leading_zeros_90(&regs), n as u64);
+            if n <= 45 {
+                let mut bytes = bytes;
+                bytes[45..].fill(0);
+                if n < 45 {
+                    bytes[44] = 1;
+                }
+                let regs: [uint8x16_t; 3] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+                assert_eq!(count_leading_zeros_45(&regs), n as u64);
+            }
+            if n <= 64 {
+                let regs: [uint8x16_t; 4] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+                assert_eq!(count_leading_zeros_64(&regs), n as u64);
+            }
+            if n <= 32 {
+                let regs: [uint8x16_t; 2] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+                assert_eq!(count_leading_zeros_32(&regs), n as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ten_per_slot_down() {
+        let groups: [[u8; 16]; 9] =
+            from_fn(|g| from_fn(|i| if i < 10 { (10 * g + i + 1) as u8 } else { 0 }));
+        let regs: [uint8x16_t; 9] = from_fn(|g| ld(groups[g].as_ptr()));
+        let compact: [[u8; 16]; 6] = ten_per_slot_down_64(&regs).map(to_bytes);
+        let compact: [u8; 96] = unsafe { transmute(compact) };
+        let expected: [u8; 96] = from_fn(|i| if i < 90 { (i + 1) as u8 } else { 0 });
+        assert_eq!(compact, expected);
+
+        let mut groups = groups;
+        groups[4][5..].fill(0);
+        let regs: [uint8x16_t; 5] = from_fn(|g| ld(groups[g].as_ptr()));
+        let compact: [[u8; 16]; 3] = ten_per_slot_down_32(&regs).map(to_bytes);
+        let compact: [u8; 48] = unsafe { transmute(compact) };
+        let expected: [u8; 48] = from_fn(|i| if i < 45 { (i + 1) as u8 } else { 0 });
+        assert_eq!(compact, expected);
+    }
+
+    #[test]
+    fn test_byte_swaps() {
+        let bytes: [u8; 16] = from_fn(|i| i as u8);
+        let swapped = to_bytes(u8s_to_u32s_swapped(bytes.as_ptr()));
+        assert_eq!(
+            swapped,
+            [3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12]
+        );
+        let nums = [0x00010203u64, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+        assert_eq!(to_bytes(truncate_and_swap_u64s(nums.as_ptr())), bytes);
+    }
+}
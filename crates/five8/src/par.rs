@@ -0,0 +1,270 @@
+use rayon::prelude::*;
+
+use crate::{
+    decode_32_batch, decode_64_batch, encode_32_batch, encode_64_batch, BatchDecodeError,
+    DecodeError, BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN,
+};
+
+/* Each rayon task handles this many inputs with the batch functions, which is
+tens of microseconds of work: enough to make the task overhead negligible
+while still splitting a few thousand inputs across the threads. */
+const CHUNK_LEN: usize = 512;
+
+/// Encode many 32-byte arrays in parallel on the rayon thread pool.
+///
+/// Does the same as [`encode_32_batch`], splitting the inputs into chunks
+/// that are encoded on different threads. The output doesn't depend on
+/// how the chunks are scheduled.
+///
+/// # Panics
+///
+/// Panics if `bytes`, `out` and `lens` don't have the same length.
+///
+/// # Examples
+///
+/// ```
+/// let keys = vec![[0u8; 32]; 10_000];
+/// let mut out = vec![[0u8; 44]; keys.len()];
+/// let mut lens = vec![0u8; keys.len()];
+/// five8::par_encode_32(&keys, &mut out, &mut lens);
+/// assert!(lens.iter().all(|len| *len == 32));
+/// ```
+pub fn par_encode_32(
+    bytes: &[[u8; 32]],
+    out: &mut [[u8; BASE58_ENCODED_32_MAX_LEN]],
+    lens: &mut [u8],
+) {
+    assert!(
+        bytes.len() == out.len() && bytes.len() == lens.len(),
+        "bytes, out and lens must have the same length"
+    );
+    bytes
+        .par_chunks(CHUNK_LEN)
+        .zip(out.par_chunks_mut(CHUNK_LEN))
+        .zip(lens.par_chunks_mut(CHUNK_LEN))
+        .for_each(|((bytes, out), lens)| encode_32_batch(bytes, out, lens));
+}
+
+/// Encode many 64-byte arrays in parallel on the rayon thread pool.
+///
+/// Does the same as [`encode_64_batch`], splitting the inputs into chunks
+/// that are encoded on different threads. The output doesn't depend on
+/// how the chunks are scheduled.
+///
+/// # Panics
+///
+/// Panics if `bytes`, `out` and `lens` don't have the same length.
+pub fn par_encode_64(
+    bytes: &[[u8; 64]],
+    out: &mut [[u8; BASE58_ENCODED_64_MAX_LEN]],
+    lens: &mut [u8],
+) {
+    assert!(
+        bytes.len() == out.len() && bytes.len() == lens.len(),
+        "bytes, out and lens must have the same length"
+    );
+    bytes
+        .par_chunks(CHUNK_LEN)
+        .zip(out.par_chunks_mut(CHUNK_LEN))
+        .zip(lens.par_chunks_mut(CHUNK_LEN))
+        .for_each(|((bytes, out), lens)| encode_64_batch(bytes, out, lens));
+}
+
+/// Decode many base58 strings onto 32-byte arrays in parallel on the rayon
+/// thread pool.
+///
+/// Does the same as [`decode_32_batch`], splitting the inputs into chunks
+/// that are decoded on different threads. Every input is decoded, and the
+/// outputs, `results` and the returned error don't depend on how the
+/// chunks are scheduled.
+///
+/// # Errors
+///
+/// If any input fails to decode, returns the index and error of the one
+/// with the lowest index.
+///
+/// # Panics
+///
+/// Panics if `encoded`, `out` and `results` don't have the same length.
+///
+/// # Examples
+///
+/// ```
+/// use five8::{BatchDecodeError, DecodeError};
+///
+/// let mut encoded = vec!["11111111111111111111111111111111"; 10_000];
+/// encoded[7000] = "1111";
+/// encoded[9000] = "111";
+/// let mut out = vec![[0u8; 32]; encoded.len()];
+/// let mut results = vec![Ok(()); encoded.len()];
+/// let err = five8::par_decode_32(&encoded, &mut out, &mut results).unwrap_err();
+/// assert_eq!(err, BatchDecodeError { index: 7000, error: DecodeError::TooShort });
+/// assert_eq!(results[9000], Err(DecodeError::TooShort));
+/// ```
+pub fn par_decode_32<I: AsRef<[u8]> + Sync>(
+    encoded: &[I],
+    out: &mut [[u8; 32]],
+    results: &mut [Result<(), DecodeError>],
+) -> Result<(), BatchDecodeError> {
+    assert!(
+        encoded.len() == out.len() && encoded.len() == results.len(),
+        "encoded, out and results must have the same length"
+    );
+    encoded
+        .par_chunks(CHUNK_LEN)
+        .zip(out.par_chunks_mut(CHUNK_LEN))
+        .zip(results.par_chunks_mut(CHUNK_LEN))
+        .for_each(|((encoded, out), results)| {
+            let _ = decode_32_batch(encoded, out, results);
+        });
+    first_par_error(results)
+}
+
+/// Decode many base58 strings onto 64-byte arrays in parallel on the rayon
+/// thread pool.
+///
+/// Does the same as [`decode_64_batch`], splitting the inputs into chunks
+/// that are decoded on different threads. Every input is decoded, and the
+/// outputs, `results` and the returned error don't depend on how the
+/// chunks are scheduled.
+///
+/// # Errors
+///
+/// If any input fails to decode, returns the index and error of the one
+/// with the lowest index.
+///
+/// # Panics
+///
+/// Panics if `encoded`, `out` and `results` don't have the same length.
+pub fn par_decode_64<I: AsRef<[u8]> + Sync>(
+    encoded: &[I],
+    out: &mut [[u8; 64]],
+    results: &mut [Result<(), DecodeError>],
+) -> Result<(), BatchDecodeError> {
+    assert!(
+        encoded.len() == out.len() && encoded.len() == results.len(),
+        "encoded, out and results must have the same length"
+    );
+    encoded
+        .par_chunks(CHUNK_LEN)
+        .zip(out.par_chunks_mut(CHUNK_LEN))
+        .zip(results.par_chunks_mut(CHUNK_LEN))
+        .for_each(|((encoded, out), results)| {
+            let _ = decode_64_batch(encoded, out, results);
+        });
+    first_par_error(results)
+}
+
+fn first_par_error(results: &[Result<(), DecodeError>]) -> Result<(), BatchDecodeError> {
+    /* position_first, unlike position_any, always finds the lowest index */
+    match results.par_iter().position_first(Result::is_err) {
+        Some(index) => Err(BatchDecodeError {
+            index,
+            error: results[index].unwrap_err(),
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_32, encode_32, encode_64};
+    #[cfg(not(miri))]
+    use prop::array::uniform32;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::vec::Vec;
+
+    fn check_par_32(keys: &[[u8; 32]], bad: &[usize]) {
+        let mut out = std::vec![[0u8; BASE58_ENCODED_32_MAX_LEN]; keys.len()];
+        let mut lens = std::vec![0u8; keys.len()];
+        par_encode_32(keys, &mut out, &mut lens);
+        let mut encoded: Vec<Vec<u8>> = Vec::with_capacity(keys.len());
+        for ((key, out), len) in keys.iter().zip(&out).zip(&lens) {
+            let mut expected = [0u8; BASE58_ENCODED_32_MAX_LEN];
+            let expected_len = encode_32(key, &mut expected);
+            assert_eq!(out[..*len as usize], expected[..expected_len as usize]);
+            encoded.push(out[..*len as usize].to_vec());
+        }
+        for &i in bad {
+            encoded[i][0] = b'0';
+        }
+        let mut decoded = std::vec![[0u8; 32]; keys.len()];
+        let mut results = std::vec![Ok(()); keys.len()];
+        let res = par_decode_32(&encoded, &mut decoded, &mut results);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(*result, decode_32(&encoded[i], &mut [0; 32]));
+            if result.is_ok() {
+                assert_eq!(decoded[i], keys[i]);
+            }
+        }
+        let expected_res = match bad.iter().min() {
+            Some(&index) => Err(BatchDecodeError {
+                index,
+                error: DecodeError::InvalidChar(b'0'),
+            }),
+            None => Ok(()),
+        };
+        assert_eq!(res, expected_res);
+    }
+
+    #[test]
+    fn test_par_32() {
+        let keys: Vec<[u8; 32]> = (0..3 * CHUNK_LEN + 5)
+            .map(|i| core::array::from_fn(|j| (i * 31 + j * 7) as u8))
+            .collect();
+        check_par_32(&keys, &[]);
+        check_par_32(&keys, &[3 * CHUNK_LEN + 4, CHUNK_LEN + 1, 2 * CHUNK_LEN]);
+        check_par_32(&[], &[]);
+    }
+
+    #[test]
+    fn test_par_64() {
+        let keys: Vec<[u8; 64]> = (0..2 * CHUNK_LEN + 1)
+            .map(|i| core::array::from_fn(|j| if j < i % 65 { 0 } else { (i + j) as u8 }))
+            .collect();
+        let mut out = std::vec![[0u8; BASE58_ENCODED_64_MAX_LEN]; keys.len()];
+        let mut lens = std::vec![0u8; keys.len()];
+        par_encode_64(&keys, &mut out, &mut lens);
+        let mut encoded: Vec<&[u8]> = Vec::with_capacity(keys.len());
+        for ((key, out), len) in keys.iter().zip(&out).zip(&lens) {
+            let mut expected = [0u8; BASE58_ENCODED_64_MAX_LEN];
+            let expected_len = encode_64(key, &mut expected);
+            assert_eq!(out[..*len as usize], expected[..expected_len as usize]);
+            encoded.push(&out[..*len as usize]);
+        }
+        let mut decoded = std::vec![[0u8; 64]; keys.len()];
+        let mut results = std::vec![Ok(()); keys.len()];
+        par_decode_64(&encoded, &mut decoded, &mut results).unwrap();
+        assert_eq!(decoded, keys);
+        encoded[CHUNK_LEN + 3] = b"1";
+        let err = par_decode_64(&encoded, &mut decoded, &mut results).unwrap_err();
+        assert_eq!(
+            err,
+            BatchDecodeError {
+                index: CHUNK_LEN + 3,
+                error: DecodeError::TooShort
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_par_length_mismatch() {
+        par_encode_64(&[[0; 64]; 2], &mut [[0; 88]; 3], &mut [0; 2]);
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_par_32(
+            keys in prop::collection::vec(uniform32(0u8..), 0..2 * CHUNK_LEN),
+            bad in prop::collection::vec(0usize..2 * CHUNK_LEN, 0..4),
+        ) {
+            let bad: Vec<usize> = bad.into_iter().filter(|i| *i < keys.len()).collect();
+            check_par_32(&keys, &bad);
+        }
+    }
+}
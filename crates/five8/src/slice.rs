@@ -0,0 +1,256 @@
+//! Encoding and decoding slices of any length, with the same results as
+//! the `bs58` crate.
+
+use alloc::{string::String, vec::Vec};
+
+use five8_core::{
+    Alphabet, DecodeError, BASE58_ENCODED_32_MAX_LEN, BASE58_ENCODED_64_MAX_LEN, N_32, N_64,
+};
+
+use crate::{
+    alphabet::{Bitcoin, Charset},
+    bignum::{convert, BINARY, INTERMEDIATE},
+    decode::{count_chars, decode_32_charset, decode_64_charset},
+    encode::{encode_32_charset, encode_64_charset, term_to_digits},
+};
+
+/// Encode a byte slice of any length.
+///
+/// Each leading zero byte becomes a leading `'1'`, like in the `bs58`
+/// crate. 32- and 64-byte slices use [`encode_32`] and [`encode_64`].
+///
+/// [`encode_32`]: crate::encode_32
+/// [`encode_64`]: crate::encode_64
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8::encode_to_string(b"hello world"), "StV1DL6CwTryKyV");
+/// assert_eq!(five8::encode_to_string(&[0, 0, 1]), "112");
+/// assert_eq!(five8::encode_to_string(&[]), "");
+/// ```
+pub fn encode_to_string(bytes: &[u8]) -> String {
+    encode_to_string_charset(Bitcoin, bytes)
+}
+
+/* encode_to_string with the characters of any alphabet */
+pub(crate) fn encode_to_string_charset<C: Charset>(charset: C, bytes: &[u8]) -> String {
+    let encoded = if let Ok(bytes) = <&[u8; N_32]>::try_from(bytes) {
+        let mut out = [0u8; BASE58_ENCODED_32_MAX_LEN];
+        let len = encode_32_charset(charset, bytes, &mut out);
+        out[..len as usize].to_vec()
+    } else if let Ok(bytes) = <&[u8; N_64]>::try_from(bytes) {
+        let mut out = [0u8; BASE58_ENCODED_64_MAX_LEN];
+        let len = encode_64_charset(charset, bytes, &mut out);
+        out[..len as usize].to_vec()
+    } else {
+        encode_slice(charset.alphabet(), bytes)
+    };
+    /* Alphabets are ASCII */
+    unsafe { String::from_utf8_unchecked(encoded) }
+}
+
+/// Decode base58 data of any length.
+///
+/// Each leading `'1'` becomes a leading zero byte, like in the `bs58`
+/// crate. The only possible error is [`DecodeError::InvalidChar`], for
+/// the first character that isn't in the base58 alphabet. Inputs that
+/// could decode to 32 or 64 bytes try [`decode_32`] or [`decode_64`]
+/// first.
+///
+/// [`decode_32`]: crate::decode_32
+/// [`decode_64`]: crate::decode_64
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8::decode_to_vec("StV1DL6CwTryKyV").unwrap(), b"hello world");
+/// assert_eq!(five8::decode_to_vec("112").unwrap(), [0, 0, 1]);
+/// assert_eq!(
+///     five8::decode_to_vec("I2"),
+///     Err(five8::DecodeError::InvalidChar(b'I'))
+/// );
+/// ```
+pub fn decode_to_vec<I: AsRef<[u8]>>(encoded: I) -> Result<Vec<u8>, DecodeError> {
+    decode_to_vec_charset(Bitcoin, encoded.as_ref())
+}
+
+/* decode_to_vec with the characters of any alphabet */
+pub(crate) fn decode_to_vec_charset<C: Charset>(
+    charset: C,
+    encoded: &[u8],
+) -> Result<Vec<u8>, DecodeError> {
+    /* Only these lengths can decode to 32 or 64 bytes, and the fixed-size
+    functions succeed exactly when that's what they decode to */
+    if (N_32..=BASE58_ENCODED_32_MAX_LEN).contains(&encoded.len()) {
+        let mut out = [0u8; N_32];
+        match decode_32_charset(charset, encoded, &mut out) {
+            Ok(()) => return Ok(out.to_vec()),
+            Err(e @ DecodeError::InvalidChar(_)) => return Err(e),
+            Err(_) => {}
+        }
+    } else if (N_64..=BASE58_ENCODED_64_MAX_LEN).contains(&encoded.len()) {
+        let mut out = [0u8; N_64];
+        match decode_64_charset(charset, encoded, &mut out) {
+            Ok(()) => return Ok(out.to_vec()),
+            Err(e @ DecodeError::InvalidChar(_)) => return Err(e),
+            Err(_) => {}
+        }
+    }
+    decode_slice(charset.alphabet(), encoded)
+}
+
+fn encode_slice(alphabet: &Alphabet, bytes: &[u8]) -> Vec<u8> {
+    let chars = alphabet.as_bytes();
+    let in_leading_0s = bytes.iter().take_while(|b| **b == 0).count();
+    let rest = &bytes[in_leading_0s..];
+
+    /* Little-endian u32 limbs, with the leftover bytes in the top one */
+    let (head, tail) = rest.split_at(rest.len() % 4);
+    let mut binary: Vec<u32> = tail
+        .rchunks_exact(4)
+        .map(|limb| u32::from_be_bytes(limb.try_into().unwrap()))
+        .collect();
+    if !head.is_empty() {
+        binary.push(head.iter().fold(0, |acc, b| (acc << 8) | *b as u32));
+    }
+    let intermediate = convert::<BINARY, INTERMEDIATE>(&binary);
+
+    let mut out = Vec::with_capacity(in_leading_0s + 5 * intermediate.len());
+    out.resize(in_leading_0s, chars[0]);
+    if let Some((top, terms)) = intermediate.split_last() {
+        let mut digits = [0u8; 5];
+        term_to_digits(*top, &mut digits);
+        let top_leading_0s = digits.iter().take_while(|d| **d == 0).count();
+        out.extend(digits[top_leading_0s..].iter().map(|d| chars[*d as usize]));
+        for term in terms.iter().rev() {
+            term_to_digits(*term, &mut digits);
+            out.extend(digits.iter().map(|d| chars[*d as usize]));
+        }
+    }
+    out
+}
+
+fn decode_slice(alphabet: &Alphabet, encoded: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let decode_table = alphabet.decode_table();
+    /* With no length limit, the only error is an invalid character */
+    count_chars(encoded, encoded.len(), decode_table)?;
+    let zero = alphabet.as_bytes()[0];
+    let leading_1s = encoded.iter().take_while(|c| **c == zero).count();
+    let rest = &encoded[leading_1s..];
+
+    /* Little-endian radix 58^5 terms, with the leftover digits in the top
+    one */
+    let (head, tail) = rest.split_at(rest.len() % 5);
+    let mut intermediate: Vec<u32> = tail
+        .rchunks_exact(5)
+        .map(|chars| digits_to_term(decode_table, chars))
+        .collect();
+    if !head.is_empty() {
+        intermediate.push(digits_to_term(decode_table, head));
+    }
+    let binary = convert::<INTERMEDIATE, BINARY>(&intermediate);
+
+    let mut out = Vec::with_capacity(leading_1s + 4 * binary.len());
+    out.resize(leading_1s, 0);
+    if let Some((top, limbs)) = binary.split_last() {
+        let top = top.to_be_bytes();
+        let top_leading_0s = top.iter().take_while(|b| **b == 0).count();
+        out.extend_from_slice(&top[top_leading_0s..]);
+        for limb in limbs.iter().rev() {
+            out.extend_from_slice(&limb.to_be_bytes());
+        }
+    }
+    Ok(out)
+}
+
+/* The value of up to 5 (already validated) base58 characters */
+#[inline(always)]
+fn digits_to_term(decode_table: &[u8; 128], chars: &[u8]) -> u32 {
+    chars
+        .iter()
+        .fold(0, |acc, c| acc * 58 + decode_table[*c as usize] as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+
+    fn check_roundtrip(bytes: &[u8]) {
+        let expected = bs58::encode(bytes).into_string();
+        assert_eq!(encode_to_string(bytes), expected);
+        assert_eq!(encode_slice(&Alphabet::BITCOIN, bytes), expected.as_bytes());
+        assert_eq!(decode_to_vec(&expected).unwrap(), bytes);
+        assert_eq!(
+            decode_slice(&Alphabet::BITCOIN, expected.as_bytes()).unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn test_slice_roundtrip() {
+        check_roundtrip(&[]);
+        check_roundtrip(&[0]);
+        check_roundtrip(&[0; 7]);
+        check_roundtrip(&[255; 100]);
+        check_roundtrip(b"hello world");
+        for len in [1, 2, 3, 4, 5, 31, 32, 33, 63, 64, 65, 1232, 4099] {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 71 + 13) as u8).collect();
+            check_roundtrip(&bytes);
+            let mut with_zeros = alloc::vec![0u8; 3];
+            with_zeros.extend_from_slice(&bytes);
+            check_roundtrip(&with_zeros);
+        }
+    }
+
+    #[test]
+    fn test_decode_to_vec_near_fixed_sizes() {
+        /* These could be 32 or 64 bytes long but aren't, so decode_32 and
+        decode_64 fail and the slice code is used */
+        check_roundtrip(&[255; 33]);
+        check_roundtrip(&[1; 31]);
+        check_roundtrip(&[0; 33]);
+        check_roundtrip(&[255; 65]);
+        check_roundtrip(&[0; 63]);
+        assert_eq!(decode_to_vec("1".repeat(40)).unwrap(), [0; 40]);
+    }
+
+    #[test]
+    fn test_decode_to_vec_invalid_char() {
+        assert_eq!(decode_to_vec("0"), Err(DecodeError::InvalidChar(b'0')));
+        let mut encoded = bs58::encode([7u8; 32]).into_vec();
+        encoded[20] = b'O';
+        assert_eq!(decode_to_vec(&encoded), Err(DecodeError::InvalidChar(b'O')));
+        encoded.extend_from_slice(b"12l");
+        assert_eq!(decode_to_vec(&encoded), Err(DecodeError::InvalidChar(b'O')));
+        assert_eq!(
+            decode_to_vec("2222222222222l"),
+            Err(DecodeError::InvalidChar(b'l'))
+        );
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_slice_roundtrip(zeros in 0usize..4, bytes in prop::collection::vec(0u8.., 0..200)) {
+            let mut with_zeros = alloc::vec![0u8; zeros];
+            with_zeros.extend_from_slice(&bytes);
+            check_roundtrip(&with_zeros);
+        }
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_decode_to_vec(encoded in "[1-9A-Za-z]{0,100}") {
+            let res = decode_to_vec(&encoded);
+            match bs58::decode(&encoded).into_vec() {
+                Ok(expected) => assert_eq!(res.unwrap(), expected),
+                Err(_) => assert!(res.is_err()),
+            }
+        }
+    }
+}
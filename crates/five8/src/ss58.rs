@@ -0,0 +1,310 @@
+//! Substrate SS58 addresses: base58 of a one- or two-byte network prefix,
+//! a 32-byte account id and the first 2 bytes of the blake2b-512 hash of
+//! `"SS58PRE"` and both.
+//!
+//! The data is 35 bytes, or 36 with a two-byte prefix, and is converted by
+//! [`encode`] and [`decode`] specialized for those lengths.
+
+use blake2::{Blake2b512, Digest};
+use five8_core::base58_encoded_max_len;
+
+use crate::{decode, encode, CheckDecodeError};
+
+const ACCOUNT_LEN: usize = 32;
+const CHECKSUM_LEN: usize = 2;
+const SHORT_LEN: usize = 1 + ACCOUNT_LEN + CHECKSUM_LEN;
+const LONG_LEN: usize = 2 + ACCOUNT_LEN + CHECKSUM_LEN;
+const SHORT_ENCODED_MAX_LEN: usize = base58_encoded_max_len(SHORT_LEN);
+const CHECKSUM_PREFIX: &[u8] = b"SS58PRE";
+const MAX_NETWORK_ID: u16 = 0x3fff;
+
+/* The payload lengths SS58 allows and their checksum lengths: account
+indices have 1-byte checksums and account ids and public keys 2-byte ones */
+const PAYLOAD_LENS: [(usize, usize); 6] = [(1, 1), (2, 1), (4, 1), (8, 1), (32, 2), (33, 2)];
+
+/// The longest encoding of an SS58 address, and the buffer size for
+/// [`encode_ss58`].
+pub const SS58_ENCODED_MAX_LEN: usize = base58_encoded_max_len(LONG_LEN);
+
+/// A decoded SS58 address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ss58Address {
+    /// The network prefix, e.g. 0 for Polkadot, 2 for Kusama and 42 for
+    /// generic Substrate addresses. At most 16383.
+    pub network_id: u16,
+    pub account: [u8; ACCOUNT_LEN],
+}
+
+#[inline]
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let hash = Blake2b512::new()
+        .chain_update(CHECKSUM_PREFIX)
+        .chain_update(data)
+        .finalize();
+    [hash[0], hash[1]]
+}
+
+/// Encode an SS58 address and return the length of the encoding.
+///
+/// Network ids below 64 take one byte and the others two.
+///
+/// # Panics
+///
+/// Panics if `network_id` is above 16383, the largest that fits in the
+/// two-byte prefix.
+///
+/// # Examples
+///
+/// ```
+/// use five8::Ss58Address;
+///
+/// let account = [
+///     212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159, 214, 130, 44, 133, 88,
+///     133, 76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125,
+/// ];
+/// let mut out = [0u8; five8::SS58_ENCODED_MAX_LEN];
+/// let len = five8::encode_ss58(&Ss58Address { network_id: 0, account }, &mut out);
+/// assert_eq!(
+///     &out[..len as usize],
+///     b"15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"
+/// );
+/// let len = five8::encode_ss58(&Ss58Address { network_id: 1284, account }, &mut out);
+/// assert_eq!(
+///     &out[..len as usize],
+///     b"VdvKmYJfD4VXA9fzz1SbmCo2eYHSzUFbaDCZSuaNKJAe8YNg6"
+/// );
+/// ```
+pub fn encode_ss58(address: &Ss58Address, out: &mut [u8; SS58_ENCODED_MAX_LEN]) -> u8 {
+    let id = address.network_id;
+    assert!(id <= MAX_NETWORK_ID, "SS58 network ids are at most 16383");
+    if id < 64 {
+        encode_data::<SHORT_LEN>(&[id as u8], &address.account, out)
+    } else {
+        /* The lower 8 bits are split across both bytes, with the upper 6
+        bits in the low bits of the second */
+        let prefix = [
+            ((id & 0xfc) >> 2) as u8 | 0x40,
+            (id >> 8) as u8 | ((id & 0x03) << 6) as u8,
+        ];
+        encode_data::<LONG_LEN>(&prefix, &address.account, out)
+    }
+}
+
+#[inline(always)]
+fn encode_data<const N: usize>(
+    prefix: &[u8],
+    account: &[u8; ACCOUNT_LEN],
+    out: &mut [u8; SS58_ENCODED_MAX_LEN],
+) -> u8 {
+    let mut data = [0u8; N];
+    data[..prefix.len()].copy_from_slice(prefix);
+    data[prefix.len()..N - CHECKSUM_LEN].copy_from_slice(account);
+    let checksum = checksum(&data[..N - CHECKSUM_LEN]);
+    data[N - CHECKSUM_LEN..].copy_from_slice(&checksum);
+    encode(&data, out)
+}
+
+/// Decode an SS58 address with a 32-byte account id.
+///
+/// The checksum length is checked against the payload length, so
+/// addresses of other payloads, like 33-byte ECDSA public keys, give
+/// [`CheckDecodeError::InvalidPayloadLength`]. A first byte above 127,
+/// which SS58 reserves, or a two-byte prefix for a network id below 64
+/// gives [`CheckDecodeError::InvalidVersion`], and a wrong checksum gives
+/// [`CheckDecodeError::InvalidChecksum`] with the 2-byte checksums.
+///
+/// # Examples
+///
+/// ```
+/// use five8::CheckDecodeError;
+///
+/// let address = five8::decode_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
+/// assert_eq!(address.network_id, 42);
+/// assert_eq!(address.account[..4], [212, 53, 147, 199]);
+/// assert!(matches!(
+///     five8::decode_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"),
+///     Err(CheckDecodeError::InvalidChecksum { .. })
+/// ));
+/// ```
+pub fn decode_ss58(
+    encoded: impl AsRef<[u8]>,
+) -> Result<Ss58Address, CheckDecodeError<CHECKSUM_LEN>> {
+    let encoded = encoded.as_ref();
+    /* 35-byte data is at most 48 characters long, and 36-byte data with a
+    two-byte prefix, whose first byte is at least 64, at least 49, so the
+    length says which one to decode */
+    if encoded.len() <= SHORT_ENCODED_MAX_LEN {
+        let mut data = [0u8; SHORT_LEN];
+        decode(encoded, &mut data)?;
+        address_from_data(&data)
+    } else {
+        let mut data = [0u8; LONG_LEN];
+        decode(encoded, &mut data)?;
+        address_from_data(&data)
+    }
+}
+
+#[inline(always)]
+fn address_from_data(data: &[u8]) -> Result<Ss58Address, CheckDecodeError<CHECKSUM_LEN>> {
+    let (prefix_len, network_id) = match data[0] {
+        id @ 0..=63 => (1, id as u16),
+        64..=127 => {
+            let lower = (data[0] << 2) | (data[1] >> 6);
+            let upper = data[1] & 0x3f;
+            (2, lower as u16 | (upper as u16) << 8)
+        }
+        _ => return Err(CheckDecodeError::InvalidVersion),
+    };
+    let rest = &data[prefix_len..];
+    let Some(&(payload_len, checksum_len)) = PAYLOAD_LENS
+        .iter()
+        .find(|(payload_len, checksum_len)| payload_len + checksum_len == rest.len())
+    else {
+        return Err(CheckDecodeError::InvalidPayloadLength);
+    };
+    if payload_len != ACCOUNT_LEN {
+        return Err(CheckDecodeError::InvalidPayloadLength);
+    }
+    let (checked, found) = data.split_at(data.len() - checksum_len);
+    let expected = checksum(checked);
+    if expected != found {
+        return Err(CheckDecodeError::InvalidChecksum {
+            expected,
+            found: found.try_into().unwrap(),
+        });
+    }
+    if prefix_len == 2 && network_id < 64 {
+        return Err(CheckDecodeError::InvalidVersion);
+    }
+    Ok(Ss58Address {
+        network_id,
+        account: checked[prefix_len..].try_into().unwrap(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    use five8_core::DecodeError;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::vec::Vec;
+
+    const ALICE: [u8; 32] = [
+        212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159, 214, 130, 44, 133, 88,
+        133, 76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125,
+    ];
+
+    /* Build the data byte by byte and encode it with bs58 */
+    fn bs58_ss58(prefix: &[u8], payload: &[u8], checksum_len: usize) -> Vec<u8> {
+        let mut data = [prefix, payload].concat();
+        let hash = Blake2b512::new()
+            .chain_update(b"SS58PRE")
+            .chain_update(&data)
+            .finalize();
+        data.extend_from_slice(&hash[..checksum_len]);
+        bs58::encode(data).into_vec()
+    }
+
+    fn check_roundtrip(address: &Ss58Address, expected: &[u8]) {
+        test_util::check_against_bs58::<SS58_ENCODED_MAX_LEN, _, _>(
+            expected,
+            |out| encode_ss58(address, out),
+            |encoded| decode_ss58(encoded),
+            *address,
+        );
+    }
+
+    #[test]
+    fn test_ss58_vectors() {
+        for (network_id, expected) in [
+            (0, "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"),
+            (2, "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F"),
+            (42, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            (63, "7NPoMQbiA6trJKkjB35uk96MeJD4PGWkLQLH7k7hXEkZpiba"),
+            (64, "cEaNSpz4PxFcZ7nT1VEKrKewH67rfx6MfcM6yKojyyPz7qaqp"),
+            (255, "yGHXkYLYqxijLKKfd9Q2CB9shRVu8rPNBS53wvwGTutYg4zTg"),
+            (1284, "VdvKmYJfD4VXA9fzz1SbmCo2eYHSzUFbaDCZSuaNKJAe8YNg6"),
+            (16383, "yNa8JpqfFB3q8A29rCwSgxvdU94ufJw2yKKxDgznS5m1PoFvn"),
+        ] {
+            check_roundtrip(
+                &Ss58Address {
+                    network_id,
+                    account: ALICE,
+                },
+                expected.as_bytes(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_ss58_errors() {
+        /* A 33-byte public key, and a 31-byte payload with a two-byte
+        prefix, which have the lengths of the two kinds of account id
+        address */
+        let encoded = bs58_ss58(&[42], &[7; 33], 2);
+        assert_eq!(
+            decode_ss58(&encoded),
+            Err(CheckDecodeError::InvalidPayloadLength)
+        );
+        let encoded = bs58_ss58(&[0x50, 0x00], &[7; 31], 2);
+        assert_eq!(
+            decode_ss58(&encoded),
+            Err(CheckDecodeError::InvalidPayloadLength)
+        );
+        /* A reserved first byte */
+        let encoded = bs58_ss58(&[0x80], &[7; 32], 2);
+        assert_eq!(decode_ss58(&encoded), Err(CheckDecodeError::InvalidVersion));
+        /* Network id 42 in two bytes */
+        let encoded = bs58_ss58(&[0x4a, 0x80], &ALICE, 2);
+        assert_eq!(decode_ss58(&encoded), Err(CheckDecodeError::InvalidVersion));
+        /* An account index */
+        let encoded = bs58_ss58(&[42], &[7; 4], 1);
+        assert_eq!(decode_ss58(&encoded), Err(DecodeError::TooShort.into()));
+
+        let mut encoded = bs58_ss58(&[42], &ALICE, 2);
+        let last = encoded.len() - 1;
+        test_util::corrupt(&mut encoded, last);
+        let Err(CheckDecodeError::InvalidChecksum { expected, found }) = decode_ss58(&encoded)
+        else {
+            panic!("expected a checksum error");
+        };
+        assert_ne!(expected, found);
+        encoded[last] = b'l';
+        assert_eq!(
+            decode_ss58(&encoded),
+            Err(DecodeError::InvalidChar(b'l').into())
+        );
+        assert_eq!(decode_ss58(""), Err(DecodeError::TooShort.into()));
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 16383")]
+    fn test_ss58_network_id_too_large() {
+        let mut out = [0u8; SS58_ENCODED_MAX_LEN];
+        encode_ss58(
+            &Ss58Address {
+                network_id: 16384,
+                account: ALICE,
+            },
+            &mut out,
+        );
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_ss58_roundtrip(network_id in 0..=MAX_NETWORK_ID, account in prop::array::uniform32(0u8..)) {
+            let prefix = if network_id < 64 {
+                Vec::from([network_id as u8])
+            } else {
+                let lower = network_id & 0xff;
+                Vec::from([0x40 | (lower >> 2) as u8, (lower << 6) as u8 | (network_id >> 8) as u8])
+            };
+            let expected = bs58_ss58(&prefix, &account, 2);
+            check_roundtrip(&Ss58Address { network_id, account }, &expected);
+        }
+    }
+}
@@ -0,0 +1,484 @@
+/* 128-bit versions of the helpers in avx.rs, for x86_64 machines
+without AVX2. */
+use core::{
+    arch::x86_64::{
+        __m128i, _mm_add_epi8, _mm_and_si128, _mm_andnot_si128, _mm_cmpeq_epi8, _mm_cmpgt_epi8,
+        _mm_loadu_si128, _mm_min_epu8, _mm_movemask_epi8, _mm_mul_epu32, _mm_or_si128,
+        _mm_set1_epi64x, _mm_set1_epi8, _mm_setr_epi8, _mm_setzero_si128, _mm_shuffle_epi8,
+        _mm_slli_si128, _mm_srli_epi16, _mm_srli_epi64, _mm_srli_si128, _mm_storeu_si128,
+        _mm_sub_epi64, _mm_sub_epi8,
+    },
+    array::from_fn,
+};
+
+use five8_core::BASE58_INVALID_CHAR;
+
+use crate::alphabet::Charset;
+
+#[inline(always)]
+pub(crate) fn ld(p: *const u8) -> __m128i {
+    unsafe { _mm_loadu_si128(p as *const __m128i) }
+}
+
+#[inline(always)]
+pub(crate) fn st(p: *mut u8, i: __m128i) {
+    unsafe { _mm_storeu_si128(p as *mut __m128i, i) };
+}
+
+/* Reverses the bytes of each 4-byte word in the 16 bytes at p, turning
+big endian u32s into little endian ones. */
+#[inline(always)]
+pub(crate) fn u8s_to_u32s_swapped(p: *const u8) -> __m128i {
+    unsafe {
+        _mm_shuffle_epi8(
+            ld(p),
+            _mm_setr_epi8(3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12),
+        )
+    }
+}
+
+/* Same as intermediate_to_raw in avx.rs, for the two terms in one
+128-bit register.  The AVX2 version already works on each 128-bit
+half separately, so this is exactly one half of it, and the output is
+the 10 digits of the two terms followed by 6 zero bytes:
+
+  [ a b c d e f g h i j 0 0 0 0 0 0 ] */
+#[inline(always)]
+pub(crate) fn intermediate_to_raw(intermediate: __m128i) -> __m128i {
+    unsafe {
+        let ca = _mm_set1_epi64x(2369637129);
+        let cb = _mm_set1_epi64x(1307386003);
+        let broadcast_58 = _mm_set1_epi64x(58);
+        let div3364 = |r| _mm_srli_epi64::<40>(_mm_mul_epu32(_mm_srli_epi64::<2>(r), cb));
+        /* div(k) stores floor(x/58^k). rem(k) stores div(k) % 58 */
+        let div0 = intermediate;
+        let div1 = _mm_srli_epi64::<37>(_mm_mul_epu32(div0, ca));
+        let rem0 = _mm_sub_epi64(div0, _mm_mul_epu32(div1, broadcast_58));
+        let div2 = div3364(div0);
+        let rem1 = _mm_sub_epi64(div1, _mm_mul_epu32(div2, broadcast_58));
+        let div3 = div3364(div1);
+        let rem2 = _mm_sub_epi64(div2, _mm_mul_epu32(div3, broadcast_58));
+        let div4 = div3364(div2);
+        let rem3 = _mm_sub_epi64(div3, _mm_mul_epu32(div4, broadcast_58));
+        let rem4 = div4;
+        /* Move the second value to byte 5, then shift each register to
+        its place and OR them together. */
+        let shuffle1 = _mm_setr_epi8(0, 1, 1, 1, 1, 8, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1);
+        let shift4 = _mm_shuffle_epi8(rem4, shuffle1);
+        let shift3 = _mm_slli_si128::<1>(_mm_shuffle_epi8(rem3, shuffle1));
+        let shift2 = _mm_slli_si128::<2>(_mm_shuffle_epi8(rem2, shuffle1));
+        let shift1 = _mm_slli_si128::<3>(_mm_shuffle_epi8(rem1, shuffle1));
+        let shift0 = _mm_slli_si128::<4>(_mm_shuffle_epi8(rem0, shuffle1));
+        _mm_or_si128(
+            _mm_or_si128(_mm_or_si128(shift4, shift3), _mm_or_si128(shift2, shift1)),
+            shift0,
+        )
+    }
+}
+
+/* Converts each byte in the register from raw base58 [0,58) to base58
+digits ('1'-'z', with some skips).  Same arithmetic map as the AVX2
+version:
+  b58ch(x) = '1' + x + 7*[x>8] + [x>16] + [x>21] + 6*[x>32] + [x>43] */
+#[inline(always)]
+pub(crate) fn raw_to_base58(in_: __m128i) -> __m128i {
+    unsafe {
+        let gt0 = _mm_cmpgt_epi8(in_, _mm_set1_epi8(8)); /* skip 7 */
+        let gt1 = _mm_cmpgt_epi8(in_, _mm_set1_epi8(16));
+        let gt2 = _mm_cmpgt_epi8(in_, _mm_set1_epi8(21));
+        let gt3 = _mm_cmpgt_epi8(in_, _mm_set1_epi8(32)); /* skip 6 */
+        let gt4 = _mm_cmpgt_epi8(in_, _mm_set1_epi8(43));
+        let gt0_7 = _mm_and_si128(gt0, _mm_set1_epi8(-7));
+        let gt3_6 = _mm_and_si128(gt3, _mm_set1_epi8(-6));
+        let sum = _mm_add_epi8(
+            _mm_add_epi8(
+                _mm_add_epi8(_mm_set1_epi8(-('1' as i8)), gt1),
+                _mm_add_epi8(gt2, gt4),
+            ),
+            _mm_add_epi8(gt0_7, gt3_6),
+        );
+        _mm_sub_epi8(in_, sum)
+    }
+}
+
+/* Looks up each byte of idx in a table of 16*TABLES bytes, table[k]
+holding entries 16*k to 16*k+15.  pshufb only looks at the low nibble, so
+each of the registers is looked up and the high nibble selects which
+result to keep.  Bytes of idx past the end of the table give 0. */
+#[inline(always)]
+pub(crate) fn lookup<const TABLES: usize>(idx: __m128i, table: &[__m128i; TABLES]) -> __m128i {
+    unsafe {
+        let low_nibble_mask = _mm_set1_epi8(0x0f);
+        let lo = _mm_and_si128(idx, low_nibble_mask);
+        let hi = _mm_and_si128(_mm_srli_epi16::<4>(idx), low_nibble_mask);
+        let mut out = _mm_setzero_si128();
+        for (k, t) in table.iter().enumerate() {
+            let select = _mm_cmpeq_epi8(hi, _mm_set1_epi8(k as i8));
+            out = _mm_or_si128(out, _mm_and_si128(select, _mm_shuffle_epi8(*t, lo)));
+        }
+        out
+    }
+}
+
+/* chars_table loads an alphabet's 58 characters into lookup tables for
+raw_to_chars, zero padded to 64.  The last register is loaded 6 bytes
+early and shifted down so that it doesn't read past the characters. */
+#[inline(always)]
+pub(crate) fn chars_table(chars: &[u8; 58]) -> [__m128i; 4] {
+    let p = chars.as_ptr();
+    unsafe {
+        [
+            ld(p),
+            ld(p.add(16)),
+            ld(p.add(32)),
+            _mm_srli_si128::<6>(ld(p.add(42))),
+        ]
+    }
+}
+
+/* digits_table loads an alphabet's 128-entry decode table into lookup
+tables for chars_to_digits. */
+#[inline(always)]
+pub(crate) fn digits_table(decode: &[u8; 128]) -> [__m128i; 8] {
+    from_fn(|k| ld(decode[16 * k..].as_ptr()))
+}
+
+/* raw_to_chars is raw_to_base58 for any alphabet: the Bitcoin alphabet
+keeps the arithmetic, and the others are looked up in their chars_table. */
+#[inline(always)]
+pub(crate) fn raw_to_chars<C: Charset>(in_: __m128i, chars: &[__m128i; 4]) -> __m128i {
+    if C::BITCOIN {
+        raw_to_base58(in_)
+    } else {
+        lookup(in_, chars)
+    }
+}
+
+/* chars_to_digits is base58_chars_to_digits for any alphabet.  Other
+alphabets than Bitcoin's are looked up in their digits_table, which only
+covers the bytes < 0x80, so the others are caught by their top bit.
+Zero bytes are invalid too, but are set to the zero digit so that zero
+padding stays zero padding. */
+#[inline(always)]
+pub(crate) fn chars_to_digits<C: Charset>(p: *mut u8, digits: &[__m128i; 8]) -> u32 {
+    if C::BITCOIN {
+        return base58_chars_to_digits(p);
+    }
+    unsafe {
+        let chars = ld(p);
+        let looked_up = lookup(chars, digits);
+        let zero = _mm_cmpeq_epi8(chars, _mm_setzero_si128());
+        st(p, _mm_andnot_si128(zero, looked_up));
+        let invalid = _mm_or_si128(
+            chars,
+            _mm_cmpeq_epi8(looked_up, _mm_set1_epi8(BASE58_INVALID_CHAR as i8)),
+        );
+        _mm_movemask_epi8(invalid) as u32
+    }
+}
+
+/* count_leading_zeros_{n} counts the number of zero bytes prior to the
+first non-zero byte in the first n bytes.  If all n bytes are zero,
+returns n.  Return value is in [0, n].  in_[0] contains the first 16
+bytes, in_[1] the next 16 and so on. */
+#[inline(always)]
+fn count_leading_zeros<const REGISTERS: usize>(in_: &[__m128i; REGISTERS], n: u32) -> u64 {
+    let mut zero_mask = 0u128;
+    for (i, register) in in_.iter().enumerate() {
+        let zeros = unsafe { _mm_movemask_epi8(_mm_cmpeq_epi8(*register, _mm_setzero_si128())) };
+        zero_mask |= (zeros as u32 as u128) << (16 * i);
+    }
+    /* Flips the mask and puts a 1 in bit n */
+    (!zero_mask | (1 << n)).trailing_zeros() as u64
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_32(in_: &[__m128i; 2]) -> u64 {
+    count_leading_zeros(in_, 32)
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_45(in_: &[__m128i; 3]) -> u64 {
+    count_leading_zeros(in_, 45)
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_64(in_: &[__m128i; 4]) -> u64 {
+    count_leading_zeros(in_, 64)
+}
+
+#[inline(always)]
+pub(crate) fn count_leading_zeros_90(in_: &[__m128i; 6]) -> u64 {
+    count_leading_zeros(in_, 90)
+}
+
+/* ten_per_slot_down_{32,64}: Packs {45,90} raw base58 digits stored in
+the groups of 10 format from intermediate_to_raw into {3,6} registers
+with the digits stored contiguously.  These are the same byte shifts as
+in avx.rs, see the diagrams there. */
+#[inline(always)]
+pub(crate) fn ten_per_slot_down_32(in_: &[__m128i; 5]) -> [__m128i; 3] {
+    unsafe {
+        let [g0, g1, g2, g3, g4] = *in_;
+        let o0 = _mm_or_si128(g0, _mm_slli_si128::<10>(g1));
+        let o1 = _mm_or_si128(
+            _mm_or_si128(_mm_srli_si128::<6>(g1), _mm_slli_si128::<4>(g2)),
+            _mm_slli_si128::<14>(g3),
+        );
+        let o2 = _mm_or_si128(_mm_srli_si128::<2>(g3), _mm_slli_si128::<8>(g4));
+        [o0, o1, o2]
+    }
+}
+
+#[inline(always)]
+pub(crate) fn ten_per_slot_down_64(in_: &[__m128i; 9]) -> [__m128i; 6] {
+    unsafe {
+        let [g0, g1, g2, g3, g4, g5, g6, g7, g8] = *in_;
+        let o0 = _mm_or_si128(g0, _mm_slli_si128::<10>(g1));
+        let o1 = _mm_or_si128(
+            _mm_or_si128(_mm_srli_si128::<6>(g1), _mm_slli_si128::<4>(g2)),
+            _mm_slli_si128::<14>(g3),
+        );
+        let o2 = _mm_or_si128(_mm_srli_si128::<2>(g3), _mm_slli_si128::<8>(g4));
+        let o3 = _mm_or_si128(
+            _mm_or_si128(_mm_srli_si128::<8>(g4), _mm_slli_si128::<2>(g5)),
+            _mm_slli_si128::<12>(g6),
+        );
+        let o4 = _mm_or_si128(_mm_srli_si128::<4>(g6), _mm_slli_si128::<6>(g7));
+        [o0, o1, o2, o3, o4, g8]
+    }
+}
+
+/* base58_chars_to_digits converts the 16 bytes at p from base58
+characters to their digit values in place, and returns a bitmask with
+bit i set if byte i is not a base58 character.
+
+Both the validation and the conversion only depend on the two nibbles
+of each byte, so they can be done with pshufb table lookups.  The base58
+characters have high nibbles 3 to 7:
+
+  3: 1-9             (low nibbles 1-9)
+  4: A-H J-N         (1-8, a-e)
+  5: P-Z             (0-a)
+  6: a-k m-o         (1-b, d-f)
+  7: p-z             (0-a)
+
+so each of these high nibbles gets a bit, and a byte is valid if the bit
+for its high nibble is set in the table entry for its low nibble.  The
+high nibbles 8 to f (i.e. bytes >= 0x80) have no bit and are rejected.
+
+Within a high nibble, the digit value is the character plus a constant
+offset, except that the digits after the I and l gaps are one lower.
+So the offset is looked up from the high nibble, and one is subtracted
+when the low nibble is past the gap, which is again a lookup and a bit
+test.  Invalid bytes convert to garbage, except that zero bytes convert
+to 0, so zero padding stays zero padding. */
+#[inline(always)]
+pub(crate) fn base58_chars_to_digits(p: *mut u8) -> u32 {
+    unsafe {
+        let chars = _mm_loadu_si128(p as *const __m128i);
+        let low_nibble_mask = _mm_set1_epi8(0x0f);
+        let lo = _mm_and_si128(chars, low_nibble_mask);
+        let hi = _mm_and_si128(_mm_srli_epi16::<4>(chars), low_nibble_mask);
+        let hi_bit = _mm_shuffle_epi8(
+            _mm_setr_epi8(0, 0, 0, 1, 2, 4, 8, 16, 0, 0, 0, 0, 0, 0, 0, 0),
+            hi,
+        );
+        let valid = _mm_and_si128(
+            _mm_shuffle_epi8(
+                _mm_setr_epi8(20, 31, 31, 31, 31, 31, 31, 31, 31, 29, 30, 10, 2, 10, 10, 8),
+                lo,
+            ),
+            hi_bit,
+        );
+        let past_gap = _mm_and_si128(
+            _mm_shuffle_epi8(
+                _mm_setr_epi8(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 2, 2, 10, 10, 10),
+                lo,
+            ),
+            hi_bit,
+        );
+        let offset = _mm_shuffle_epi8(
+            _mm_setr_epi8(0, 0, 0, -49, -56, -58, -64, -65, 0, 0, 0, 0, 0, 0, 0, 0),
+            hi,
+        );
+        let digits = _mm_sub_epi8(
+            _mm_add_epi8(chars, offset),
+            _mm_min_epu8(past_gap, _mm_set1_epi8(1)),
+        );
+        _mm_storeu_si128(p as *mut __m128i, digits);
+        _mm_movemask_epi8(_mm_cmpeq_epi8(valid, _mm_setzero_si128())) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{array::from_fn, mem::transmute};
+    use five8_core::{
+        Alphabet, BASE58_INVALID_CHAR, BASE58_INVERSE, BASE58_INVERSE_TABLE_OFFSET,
+        BASE58_INVERSE_TABLE_SENTINEL,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_base58_chars_to_digits() {
+        for block in 0..16 {
+            let mut buf: [u8; 16] = core::array::from_fn(|i| (16 * block + i) as u8);
+            let invalid = base58_chars_to_digits(buf.as_mut_ptr());
+            for (i, digit) in buf.iter().enumerate() {
+                let c = 16 * block + i;
+                let expected = if c < BASE58_INVERSE_TABLE_OFFSET as usize {
+                    BASE58_INVALID_CHAR
+                } else {
+                    BASE58_INVERSE[(c - BASE58_INVERSE_TABLE_OFFSET as usize)
+                        .min(BASE58_INVERSE_TABLE_SENTINEL as usize)]
+                };
+                if expected == BASE58_INVALID_CHAR {
+                    assert_ne!(invalid & (1 << i), 0, "{c}");
+                } else {
+                    assert_eq!(invalid & (1 << i), 0, "{c}");
+                    assert_eq!(*digit, expected, "{c}");
+                }
+            }
+        }
+        let mut zeros = [0u8; 16];
+        assert_eq!(base58_chars_to_digits(zeros.as_mut_ptr()), 0xffff);
+        assert_eq!(zeros, [0u8; 16]);
+    }
+
+    fn to_bytes(v: __m128i) -> [u8; 16] {
+        unsafe { transmute(v) }
+    }
+
+    #[test]
+    fn test_intermediate_to_raw() {
+        let digits =
+            |x: u64| -> [u8; 5] { from_fn(|k| ((x / 58u64.pow(4 - k as u32)) % 58) as u8) };
+        let samples = [
+            0, 1, 57, 58, 3363, 11316496, 656356767, 123456789, 656356766,
+        ];
+        for a in samples {
+            for b in samples {
+                let terms = [a, b];
+                let raw = to_bytes(intermediate_to_raw(ld(terms.as_ptr() as *const u8)));
+                let mut expected = [0u8; 16];
+                expected[..5].copy_from_slice(&digits(a));
+                expected[5..10].copy_from_slice(&digits(b));
+                assert_eq!(raw, expected, "{a} {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let entries: [u8; 128] = from_fn(|i| (i as u8).wrapping_mul(97) | 1);
+        let table: [__m128i; 8] = from_fn(|k| ld(entries[16 * k..].as_ptr()));
+        for start in (0..256).step_by(16) {
+            let idx: [u8; 16] = from_fn(|i| (start + i * 7 % 16) as u8);
+            let out = to_bytes(lookup(ld(idx.as_ptr()), &table));
+            let expected = idx.map(|i| *entries.get(i as usize).unwrap_or(&0));
+            assert_eq!(out, expected);
+            let out = to_bytes(lookup(ld(idx.as_ptr()), &[table[0], table[1]]));
+            let expected = idx.map(|i| if i < 32 { entries[i as usize] } else { 0 });
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn test_raw_to_base58() {
+        let alphabet = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+        let raw: [u8; 64] = from_fn(|i| (i % 58) as u8);
+        for k in 0..4 {
+            let chars = to_bytes(raw_to_base58(ld(raw[16 * k..].as_ptr())));
+            let expected: [u8; 16] = from_fn(|i| alphabet[raw[16 * k + i] as usize]);
+            assert_eq!(chars, expected);
+        }
+    }
+
+    #[test]
+    fn test_raw_to_chars() {
+        let raw: [u8; 64] = from_fn(|i| (i % 58) as u8);
+        for alphabet in [Alphabet::RIPPLE, Alphabet::FLICKR] {
+            let table = chars_table(alphabet.as_bytes());
+            for k in 0..4 {
+                let chars = to_bytes(raw_to_chars::<&Alphabet>(
+                    ld(raw[16 * k..].as_ptr()),
+                    &table,
+                ));
+                let expected: [u8; 16] = from_fn(|i| alphabet.as_bytes()[raw[16 * k + i] as usize]);
+                assert_eq!(chars, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chars_to_digits() {
+        let table = digits_table(Alphabet::RIPPLE.decode_table());
+        for block in 0..16 {
+            let mut buf: [u8; 16] = from_fn(|i| (16 * block + i) as u8);
+            let invalid = chars_to_digits::<&Alphabet>(buf.as_mut_ptr(), &table);
+            for (i, digit) in buf.iter().enumerate() {
+                let c = (16 * block + i) as u8;
+                match Alphabet::RIPPLE.digit(c) {
+                    Some(expected) => {
+                        assert_eq!(invalid & (1 << i), 0, "{c}");
+                        assert_eq!(*digit, expected, "{c}");
+                    }
+                    None => assert_ne!(invalid & (1 << i), 0, "{c}"),
+                }
+            }
+        }
+        let mut zeros = [0u8; 16];
+        assert_eq!(
+            chars_to_digits::<&Alphabet>(zeros.as_mut_ptr(), &table),
+            0xffff
+        );
+        assert_eq!(zeros, [0u8; 16]);
+    }
+
+    #[test]
+    fn test_count_leading_zeros() {
+        for n in 0..=96 {
+            let mut bytes = [0u8; 96];
+            if n < 96 {
+                bytes[n] = 1 + n as u8;
+            }
+            let regs: [__m128i; 6] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+            assert_eq!(count_leading_zeros_90(&regs), n.min(90) as u64);
+            let regs: [__m128i; 4] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+            assert_eq!(count_leading_zeros_64(&regs), n.min(64) as u64);
+            let regs: [__m128i; 3] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+            assert_eq!(count_leading_zeros_45(&regs), n.min(45) as u64);
+            let regs: [__m128i; 2] = from_fn(|i| ld(bytes[16 * i..].as_ptr()));
+            assert_eq!(count_leading_zeros_32(&regs), n.min(32) as u64);
+        }
+    }
+
+    #[test]
+    fn test_ten_per_slot_down() {
+        let groups: [[u8; 16]; 9] =
+            from_fn(|g| from_fn(|i| if i < 10 { (10 * g + i + 1) as u8 } else { 0 }));
+        let regs: [__m128i; 9] = from_fn(|g| ld(groups[g].as_ptr()));
+        let compact: [u8; 96] = unsafe { transmute(ten_per_slot_down_64(&regs)) };
+        let expected: [u8; 96] = from_fn(|i| if i < 90 { (i + 1) as u8 } else { 0 });
+        assert_eq!(compact, expected);
+
+        let mut groups = groups;
+        groups[4][5..].fill(0);
+        let regs: [__m128i; 5] = from_fn(|g| ld(groups[g].as_ptr()));
+        let compact: [u8; 48] = unsafe { transmute(ten_per_slot_down_32(&regs)) };
+        let expected: [u8; 48] = from_fn(|i| if i < 45 { (i + 1) as u8 } else { 0 });
+        assert_eq!(compact, expected);
+    }
+
+    #[test]
+    fn test_u8s_to_u32s_swapped() {
+        let bytes: [u8; 16] = from_fn(|i| i as u8);
+        let swapped = to_bytes(u8s_to_u32s_swapped(bytes.as_ptr()));
+        assert_eq!(
+            swapped,
+            [3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12]
+        );
+    }
+}
@@ -0,0 +1,22 @@
+/* Helpers for the tests of the formats built on base58 */
+use core::fmt::Debug;
+
+/* Checks a format's encode and decode against the encoding bs58 gives:
+encode must write `expected` and decode must give `value` back from it */
+pub(crate) fn check_against_bs58<const M: usize, T: Debug + PartialEq, E: Debug>(
+    expected: &[u8],
+    encode: impl FnOnce(&mut [u8; M]) -> u8,
+    decode: impl FnOnce(&[u8]) -> Result<T, E>,
+    value: T,
+) {
+    let mut out = [0u8; M];
+    let len = encode(&mut out);
+    assert_eq!(&out[..len as usize], expected);
+    assert_eq!(decode(expected).unwrap(), value);
+}
+
+/* Replaces character i of an encoding with another base58 character,
+which breaks its checksum */
+pub(crate) fn corrupt(encoded: &mut [u8], i: usize) {
+    encoded[i] = if encoded[i] == b'2' { b'3' } else { b'2' };
+}
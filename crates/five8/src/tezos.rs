@@ -0,0 +1,423 @@
+//! Tezos typed values: Base58Check of a magic prefix and a payload, where
+//! the prefix of each [`TezosKind`] is chosen so that every encoding of
+//! that kind starts with the same tag, like `tz1` or `edsig`, and has the
+//! same length.
+//!
+//! Each length of data is converted by [`encode`](crate::encode) and
+//! [`decode`](crate::decode) specialized for it, with AVX2 when it's
+//! available. Signatures are 64 bytes, but the prefix and checksum are part
+//! of the number that's converted, so an `edsig` is converted as 73 bytes
+//! of data by the code sized for 73 bytes, and can't use
+//! [`encode_64`](crate::encode_64).
+
+use core::fmt;
+
+use five8_core::{base58_encoded_max_len, DecodeError};
+
+use crate::{check::CHECKSUM_LEN, decode_check, encode_check, CheckDecodeError};
+
+const MAX_PAYLOAD_LEN: usize = 64;
+const MAX_DATA_LEN: usize = 5 + MAX_PAYLOAD_LEN + CHECKSUM_LEN;
+
+/// The longest encoding of a Tezos value, and the buffer size for
+/// [`encode_tezos`].
+pub const TEZOS_ENCODED_MAX_LEN: usize = base58_encoded_max_len(MAX_DATA_LEN);
+
+/// The kinds of values [`decode_tezos`] recognizes, by their prefixes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TezosKind {
+    /// `tz1...`, the address of an Ed25519 key.
+    Ed25519PublicKeyHash,
+    /// `tz2...`, the address of a secp256k1 key.
+    Secp256k1PublicKeyHash,
+    /// `tz3...`, the address of a P-256 key.
+    P256PublicKeyHash,
+    /// `tz4...`, the address of a BLS12-381 key.
+    BlsPublicKeyHash,
+    /// `KT1...`, the address of a smart contract.
+    ContractHash,
+    /// `edpk...`
+    Ed25519PublicKey,
+    /// `sppk...`
+    Secp256k1PublicKey,
+    /// `p2pk...`
+    P256PublicKey,
+    /// `edsig...`
+    Ed25519Signature,
+    /// `spsig1...`
+    Secp256k1Signature,
+    /// `p2sig...`
+    P256Signature,
+    /// `sig...`, a signature of any of the curves.
+    GenericSignature,
+    /// `B...`
+    BlockHash,
+    /// `o...`
+    OperationHash,
+    /// `P...`
+    ProtocolHash,
+    /// `Net...`
+    ChainId,
+    /// `expr...`, the hash of a Micheline expression, e.g. a big map key.
+    ScriptExprHash,
+}
+
+/* (kind, tag, prefix, payload length) */
+const REGISTRY: [(TezosKind, &str, &[u8], usize); 17] = [
+    (TezosKind::Ed25519PublicKeyHash, "tz1", &[6, 161, 159], 20),
+    (TezosKind::Secp256k1PublicKeyHash, "tz2", &[6, 161, 161], 20),
+    (TezosKind::P256PublicKeyHash, "tz3", &[6, 161, 164], 20),
+    (TezosKind::BlsPublicKeyHash, "tz4", &[6, 161, 166], 20),
+    (TezosKind::ContractHash, "KT1", &[2, 90, 121], 20),
+    (TezosKind::Ed25519PublicKey, "edpk", &[13, 15, 37, 217], 32),
+    (
+        TezosKind::Secp256k1PublicKey,
+        "sppk",
+        &[3, 254, 226, 86],
+        33,
+    ),
+    (TezosKind::P256PublicKey, "p2pk", &[3, 178, 139, 127], 33),
+    (
+        TezosKind::Ed25519Signature,
+        "edsig",
+        &[9, 245, 205, 134, 18],
+        64,
+    ),
+    (
+        TezosKind::Secp256k1Signature,
+        "spsig1",
+        &[13, 115, 101, 19, 63],
+        64,
+    ),
+    (TezosKind::P256Signature, "p2sig", &[54, 240, 44, 52], 64),
+    (TezosKind::GenericSignature, "sig", &[4, 130, 43], 64),
+    (TezosKind::BlockHash, "B", &[1, 52], 32),
+    (TezosKind::OperationHash, "o", &[5, 116], 32),
+    (TezosKind::ProtocolHash, "P", &[2, 170], 32),
+    (TezosKind::ChainId, "Net", &[87, 82, 0], 4),
+    (TezosKind::ScriptExprHash, "expr", &[13, 44, 64, 27], 32),
+];
+
+impl TezosKind {
+    /// All the kinds, in the order they're listed above.
+    pub const ALL: [TezosKind; REGISTRY.len()] = {
+        let mut all = [TezosKind::Ed25519PublicKeyHash; REGISTRY.len()];
+        let mut i = 0;
+        while i < all.len() {
+            all[i] = REGISTRY[i].0;
+            i += 1;
+        }
+        all
+    };
+
+    /// The text every encoding of this kind starts with.
+    pub const fn tag(self) -> &'static str {
+        REGISTRY[self as usize].1
+    }
+
+    /// The bytes before the payload.
+    pub const fn prefix(self) -> &'static [u8] {
+        REGISTRY[self as usize].2
+    }
+
+    /// The length of the payload.
+    pub const fn payload_len(self) -> usize {
+        REGISTRY[self as usize].3
+    }
+}
+
+/// A decoded Tezos value: its kind and a payload of the kind's length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TezosValue {
+    kind: TezosKind,
+    payload: [u8; MAX_PAYLOAD_LEN],
+}
+
+impl TezosValue {
+    pub const fn kind(&self) -> TezosKind {
+        self.kind
+    }
+
+    /// The payload, [`payload_len`](TezosKind::payload_len) bytes long.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload[..self.kind.payload_len()]
+    }
+}
+
+/// The error [`encode_tezos`] returns when the payload has the wrong
+/// length for its kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TezosPayloadLengthError {
+    pub kind: TezosKind,
+    /// The length of the given payload.
+    pub len: usize,
+}
+
+impl core::error::Error for TezosPayloadLengthError {}
+
+impl fmt::Display for TezosPayloadLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid {} payload length: expected {}, found {}",
+            self.kind.tag(),
+            self.kind.payload_len(),
+            self.len
+        )
+    }
+}
+
+/// Encode a payload of the given kind and return the length of the
+/// encoding.
+///
+/// # Errors
+///
+/// Returns a [`TezosPayloadLengthError`] if the payload isn't
+/// [`kind.payload_len()`](TezosKind::payload_len) bytes long.
+///
+/// # Examples
+///
+/// ```
+/// use five8::TezosKind;
+///
+/// let hash = [
+///     2, 41, 140, 3, 237, 125, 69, 74, 16, 30, 183, 2, 43, 201, 95, 126, 95, 65, 172, 120,
+/// ];
+/// let mut out = [0u8; five8::TEZOS_ENCODED_MAX_LEN];
+/// let len = five8::encode_tezos(TezosKind::Ed25519PublicKeyHash, &hash, &mut out).unwrap();
+/// assert_eq!(&out[..len as usize], b"tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx");
+/// assert!(five8::encode_tezos(TezosKind::Ed25519PublicKey, &hash, &mut out).is_err());
+/// ```
+pub fn encode_tezos(
+    kind: TezosKind,
+    payload: &[u8],
+    out: &mut [u8; TEZOS_ENCODED_MAX_LEN],
+) -> Result<u8, TezosPayloadLengthError> {
+    if payload.len() != kind.payload_len() {
+        return Err(TezosPayloadLengthError {
+            kind,
+            len: payload.len(),
+        });
+    }
+    let prefix = kind.prefix();
+    let len = match prefix.len() + payload.len() + CHECKSUM_LEN {
+        11 => encode_check::<11, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        27 => encode_check::<27, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        38 => encode_check::<38, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        40 => encode_check::<40, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        41 => encode_check::<41, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        71 => encode_check::<71, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        72 => encode_check::<72, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        73 => encode_check::<73, TEZOS_ENCODED_MAX_LEN>(prefix, payload, out),
+        _ => unreachable!(),
+    };
+    Ok(len)
+}
+
+/// Decode a Tezos value of any of the kinds in [`TezosKind`].
+///
+/// A string whose length no kind has gives
+/// [`CheckDecodeError::InvalidPayloadLength`], and a value with a valid
+/// checksum but a prefix that isn't in the registry gives
+/// [`CheckDecodeError::InvalidVersion`].
+///
+/// # Examples
+///
+/// ```
+/// use five8::{CheckDecodeError, TezosKind};
+///
+/// let value = five8::decode_tezos("KT1PWx2mnDueood7fEmfbBDKx1D9BAnnXitn").unwrap();
+/// assert_eq!(value.kind(), TezosKind::ContractHash);
+/// assert_eq!(value.payload()[..4], [163, 208, 245, 141]);
+/// let value = five8::decode_tezos("NetXdQprcVkpaWU").unwrap();
+/// assert_eq!(value.kind(), TezosKind::ChainId);
+/// assert_eq!(value.payload(), [122, 6, 167, 112]);
+/// assert!(matches!(
+///     five8::decode_tezos("NetXdQprcVkpaWV"),
+///     Err(CheckDecodeError::InvalidChecksum { .. })
+/// ));
+/// ```
+pub fn decode_tezos(encoded: impl AsRef<[u8]>) -> Result<TezosValue, CheckDecodeError> {
+    let encoded = encoded.as_ref();
+    /* The kinds with the same encoded length have the same data length */
+    match encoded.len() {
+        15 => decode_data::<11>(encoded),
+        36 => decode_data::<27>(encoded),
+        51 => decode_data::<38>(encoded),
+        54 => decode_data::<40>(encoded),
+        55 => decode_data::<41>(encoded),
+        96 => decode_data::<71>(encoded),
+        98 => decode_data::<72>(encoded),
+        99 => decode_data::<73>(encoded),
+        len if len < 15 => Err(DecodeError::TooShort.into()),
+        len if len > 99 => Err(DecodeError::TooLong.into()),
+        _ => Err(CheckDecodeError::InvalidPayloadLength),
+    }
+}
+
+#[inline(always)]
+fn decode_data<const N: usize>(encoded: &[u8]) -> Result<TezosValue, CheckDecodeError> {
+    let mut data = [0u8; N];
+    let checked = decode_check(encoded, &[], &mut data)?;
+    let Some(&(kind, _, prefix, payload_len)) =
+        REGISTRY.iter().find(|(_, _, prefix, payload_len)| {
+            prefix.len() + payload_len == checked.len() && checked.starts_with(prefix)
+        })
+    else {
+        return Err(CheckDecodeError::InvalidVersion);
+    };
+    let mut payload = [0u8; MAX_PAYLOAD_LEN];
+    payload[..payload_len].copy_from_slice(&checked[prefix.len()..]);
+    Ok(TezosValue { kind, payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::{string::ToString, vec::Vec};
+
+    fn check_against_bs58(kind: TezosKind, payload: &[u8]) {
+        let data = [kind.prefix(), payload].concat();
+        let expected = bs58::encode(&data).with_check().into_vec();
+        assert!(expected.starts_with(kind.tag().as_bytes()));
+        test_util::check_against_bs58::<TEZOS_ENCODED_MAX_LEN, _, _>(
+            &expected,
+            |out| encode_tezos(kind, payload, out).unwrap(),
+            |encoded| decode_tezos(encoded).map(|value| (value.kind(), value.payload().to_vec())),
+            (kind, payload.to_vec()),
+        );
+    }
+
+    #[test]
+    fn test_tezos_vectors() {
+        /* The public key and signature of RFC 8032's first Ed25519 test */
+        let public_key = [
+            0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64,
+            0x07, 0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68,
+            0xf7, 0x07, 0x51, 0x1a,
+        ];
+        let signature = [
+            0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e,
+            0x82, 0x8a, 0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65,
+            0x22, 0x49, 0x01, 0x55, 0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e,
+            0x39, 0x70, 0x1c, 0xf9, 0xb4, 0x6b, 0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24,
+            0x65, 0x51, 0x41, 0x43, 0x8e, 0x7a, 0x10, 0x0b,
+        ];
+        for (kind, payload, expected) in [
+            (
+                TezosKind::Ed25519PublicKey,
+                public_key.as_slice(),
+                "edpkvH4rzbmfvAEgiJQU1TKYfrTvBbpVJGHmQByh9Nph4BzvRh8aXP",
+            ),
+            (
+                TezosKind::Ed25519Signature,
+                &signature,
+                "edsigu3oyyXG6VFE2t8VbPhkWoGaQgEejrsUwXakw5W5miM9RgUGw31w41a5zvA3XfGjRtGwffVXSNe3QtHFX6S756nkHjwzanT",
+            ),
+            (
+                TezosKind::GenericSignature,
+                &signature,
+                "sigszWrU3CDK2zpwWJFfuKqyHtuQZotEcM9u1CMA5ni18UNeLLY81xvWr5sZRjZoZ5SjU3jyk5Y25e9VDKSHm6ujgBsfRTAG",
+            ),
+            (
+                TezosKind::Secp256k1PublicKeyHash,
+                &[0; 20],
+                "tz28KEfLTo3wg2wGyJZMjC1MaDA1q68s6tz5",
+            ),
+            (
+                TezosKind::OperationHash,
+                &[0; 32],
+                "oneDGhZacw99EEFaYDTtWfz5QEhUW3PPVFsHa7GShnLPuDn7gSd",
+            ),
+        ] {
+            let mut out = [0u8; TEZOS_ENCODED_MAX_LEN];
+            let len = encode_tezos(kind, payload, &mut out).unwrap();
+            assert_eq!(&out[..len as usize], expected.as_bytes());
+            let value = decode_tezos(expected).unwrap();
+            assert_eq!((value.kind(), value.payload()), (kind, payload));
+        }
+        let value = decode_tezos("BLockGenesisGenesisGenesisGenesisGenesisf79b5d1CoW2").unwrap();
+        assert_eq!(value.kind(), TezosKind::BlockHash);
+        let value = decode_tezos("PtKathmankSpLLDALzWw7CGD2j2MtyveTwboEYokqUCP4a1LxMg").unwrap();
+        assert_eq!(value.kind(), TezosKind::ProtocolHash);
+    }
+
+    #[test]
+    fn test_tezos_matches_bs58() {
+        for kind in TezosKind::ALL {
+            let len = kind.payload_len();
+            check_against_bs58(kind, &[0; MAX_PAYLOAD_LEN][..len]);
+            check_against_bs58(kind, &[0xff; MAX_PAYLOAD_LEN][..len]);
+            check_against_bs58(kind, &(0..len as u8).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        for (i, kind) in TezosKind::ALL.into_iter().enumerate() {
+            assert_eq!(kind as usize, i);
+            assert!(kind.payload_len() <= MAX_PAYLOAD_LEN);
+            assert!(kind.prefix().len() + kind.payload_len() + CHECKSUM_LEN <= MAX_DATA_LEN);
+        }
+    }
+
+    #[test]
+    fn test_tezos_errors() {
+        let mut out = [0u8; TEZOS_ENCODED_MAX_LEN];
+        let err = encode_tezos(TezosKind::Ed25519Signature, &[0; 63], &mut out).unwrap_err();
+        assert_eq!(
+            err,
+            TezosPayloadLengthError {
+                kind: TezosKind::Ed25519Signature,
+                len: 63
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid edsig payload length: expected 64, found 63"
+        );
+        assert!(encode_tezos(TezosKind::ChainId, &[], &mut out).is_err());
+
+        /* 27 bytes of Base58Check data with an unknown prefix */
+        let encoded = bs58::encode([[6, 161, 160].as_slice(), &[7; 20]].concat())
+            .with_check()
+            .into_vec();
+        assert_eq!(encoded.len(), 36);
+        assert_eq!(
+            decode_tezos(&encoded),
+            Err(CheckDecodeError::InvalidVersion)
+        );
+        /* A Solana public key */
+        assert_eq!(
+            decode_tezos("JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFF"),
+            Err(CheckDecodeError::InvalidPayloadLength)
+        );
+        assert_eq!(decode_tezos("Net"), Err(DecodeError::TooShort.into()));
+        assert_eq!(decode_tezos([b'z'; 100]), Err(DecodeError::TooLong.into()));
+        assert_eq!(
+            decode_tezos("tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZS0"),
+            Err(DecodeError::InvalidChar(b'0').into())
+        );
+        let mut encoded = *b"tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx";
+        test_util::corrupt(&mut encoded, 35);
+        assert!(matches!(
+            decode_tezos(encoded),
+            Err(CheckDecodeError::InvalidChecksum { .. })
+        ));
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_tezos_roundtrip(kind in 0..TezosKind::ALL.len(), payload in prop::array::uniform32(0u8..), more in prop::array::uniform32(0u8..)) {
+            let kind = TezosKind::ALL[kind];
+            let payload = [payload, more].concat();
+            check_against_bs58(kind, &payload[..kind.payload_len()]);
+        }
+    }
+}
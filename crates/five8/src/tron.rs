@@ -0,0 +1,209 @@
+//! Tron addresses: Base58Check of a 0x41 byte and the 20-byte hash that
+//! an EVM address of the same key would have.
+//!
+//! The data is always 25 bytes and the encoding always 34 characters
+//! starting with `T`, so both directions go through [`encode`] and
+//! [`decode`] specialized for 25 bytes, with AVX2 when it's available, and
+//! with no search over lengths.
+
+use five8_core::{base58_encoded_max_len, DecodeError};
+
+use crate::{
+    check::{checksum, verify_check, CHECKSUM_LEN},
+    decode, encode, CheckDecodeError,
+};
+
+const HASH_LEN: usize = 20;
+const DATA_LEN: usize = 1 + HASH_LEN + CHECKSUM_LEN;
+
+/// The byte before the hash in a Tron address.
+pub const TRON_ADDRESS_PREFIX: u8 = 0x41;
+
+/// The length of every Tron address, and the buffer size for
+/// [`encode_tron_address`].
+pub const TRON_ADDRESS_ENCODED_LEN: usize = 34;
+
+/// A Tron address, given by its 20-byte hash.
+///
+/// The hash is the last 20 bytes of the Keccak-256 of the public key, as
+/// in an EVM address, and converts to and from `[u8; 20]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TronAddress([u8; HASH_LEN]);
+
+impl TronAddress {
+    pub const fn from_hash(hash: [u8; HASH_LEN]) -> Self {
+        Self(hash)
+    }
+
+    pub const fn hash(&self) -> &[u8; HASH_LEN] {
+        &self.0
+    }
+}
+
+impl From<[u8; HASH_LEN]> for TronAddress {
+    fn from(hash: [u8; HASH_LEN]) -> Self {
+        Self(hash)
+    }
+}
+
+impl From<TronAddress> for [u8; HASH_LEN] {
+    fn from(address: TronAddress) -> Self {
+        address.0
+    }
+}
+
+/// Encode a Tron address.
+///
+/// # Examples
+///
+/// ```
+/// use five8::TronAddress;
+///
+/// // The USDT contract.
+/// let address = TronAddress::from_hash([
+///     166, 20, 248, 3, 182, 253, 120, 9, 134, 164, 44, 120, 236, 156, 127, 119, 230, 222, 209,
+///     60,
+/// ]);
+/// let mut out = [0u8; five8::TRON_ADDRESS_ENCODED_LEN];
+/// five8::encode_tron_address(&address, &mut out);
+/// assert_eq!(&out, b"TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t");
+/// ```
+pub fn encode_tron_address(address: &TronAddress, out: &mut [u8; TRON_ADDRESS_ENCODED_LEN]) {
+    let mut data = [0u8; DATA_LEN];
+    data[0] = TRON_ADDRESS_PREFIX;
+    data[1..1 + HASH_LEN].copy_from_slice(&address.0);
+    let checksum = checksum(&[], &data[..1 + HASH_LEN]);
+    data[1 + HASH_LEN..].copy_from_slice(&checksum);
+    /* encode needs room for any 25 bytes, but with the prefix the
+    encoding is always one character shorter */
+    let mut encoded = [0u8; base58_encoded_max_len(DATA_LEN)];
+    let len = encode(&data, &mut encoded);
+    debug_assert_eq!(len as usize, TRON_ADDRESS_ENCODED_LEN);
+    out.copy_from_slice(&encoded[..TRON_ADDRESS_ENCODED_LEN]);
+}
+
+/// Decode a Tron address.
+///
+/// Anything but 34 characters gives [`DecodeError::TooShort`] or
+/// [`DecodeError::TooLong`], a wrong checksum gives
+/// [`CheckDecodeError::InvalidChecksum`] and data with a valid checksum
+/// that doesn't start with 0x41 gives [`CheckDecodeError::InvalidPrefix`]
+/// with the byte it starts with.
+///
+/// # Examples
+///
+/// ```
+/// use five8::CheckDecodeError;
+///
+/// let address = five8::decode_tron_address("T9yD14Nj9j7xAB4dbGeiX9h8unkKHxuWwb").unwrap();
+/// assert_eq!(<[u8; 20]>::from(address), [0; 20]);
+/// assert!(matches!(
+///     five8::decode_tron_address("T9yD14Nj9j7xAB4dbGeiX9h8unkKHxuWwc"),
+///     Err(CheckDecodeError::InvalidChecksum { .. })
+/// ));
+/// // A Bitcoin address.
+/// assert_eq!(
+///     five8::decode_tron_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"),
+///     Err(CheckDecodeError::InvalidPrefix(0x00))
+/// );
+/// ```
+pub fn decode_tron_address(encoded: impl AsRef<[u8]>) -> Result<TronAddress, CheckDecodeError> {
+    let encoded = encoded.as_ref();
+    match encoded.len() {
+        TRON_ADDRESS_ENCODED_LEN => {}
+        len if len < TRON_ADDRESS_ENCODED_LEN => return Err(DecodeError::TooShort.into()),
+        _ => return Err(DecodeError::TooLong.into()),
+    }
+    let mut data = [0u8; DATA_LEN];
+    decode(encoded, &mut data)?;
+    let checked = verify_check(&data, &[])?;
+    if checked[0] != TRON_ADDRESS_PREFIX {
+        return Err(CheckDecodeError::InvalidPrefix(checked[0]));
+    }
+    Ok(TronAddress(checked[1..].try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::string::ToString;
+
+    fn check_against_bs58(hash: [u8; HASH_LEN]) {
+        let expected = bs58::encode([[TRON_ADDRESS_PREFIX].as_slice(), &hash].concat())
+            .with_check()
+            .into_vec();
+        assert_eq!(expected[0], b'T');
+        let address = TronAddress::from(hash);
+        test_util::check_against_bs58::<TRON_ADDRESS_ENCODED_LEN, _, _>(
+            &expected,
+            |out| {
+                encode_tron_address(&address, out);
+                TRON_ADDRESS_ENCODED_LEN as u8
+            },
+            |encoded| decode_tron_address(encoded),
+            address,
+        );
+        assert_eq!(<[u8; HASH_LEN]>::from(address), hash);
+    }
+
+    #[test]
+    fn test_tron_matches_bs58() {
+        for hash in [
+            [0; 20],
+            [1; 20],
+            [0xff; 20],
+            core::array::from_fn(|i| i as u8),
+        ] {
+            check_against_bs58(hash);
+        }
+    }
+
+    #[test]
+    fn test_tron_errors() {
+        /* A valid Base58Check string of 25 bytes with prefix 0x42 */
+        let encoded = bs58::encode([[0x42].as_slice(), &[7; 20]].concat())
+            .with_check()
+            .into_vec();
+        assert_eq!(encoded.len(), TRON_ADDRESS_ENCODED_LEN);
+        assert_eq!(
+            decode_tron_address(&encoded),
+            Err(CheckDecodeError::InvalidPrefix(0x42))
+        );
+        assert_eq!(
+            CheckDecodeError::<4>::InvalidPrefix(0x42).to_string(),
+            "Invalid prefix: 0x42"
+        );
+        let mut out = [0u8; TRON_ADDRESS_ENCODED_LEN];
+        encode_tron_address(&TronAddress::from_hash([7; 20]), &mut out);
+        assert_eq!(
+            decode_tron_address(&out[..33]),
+            Err(DecodeError::TooShort.into())
+        );
+        assert_eq!(
+            decode_tron_address([out.as_slice(), b"1"].concat()),
+            Err(DecodeError::TooLong.into())
+        );
+        test_util::corrupt(&mut out, 20);
+        assert!(matches!(
+            decode_tron_address(out),
+            Err(CheckDecodeError::InvalidChecksum { .. })
+        ));
+        out[20] = b'O';
+        assert_eq!(
+            decode_tron_address(out),
+            Err(DecodeError::InvalidChar(b'O').into())
+        );
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_tron_roundtrip(hash in prop::array::uniform20(0u8..)) {
+            check_against_bs58(hash);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Wallet Import Format (WIF) private keys: Base58Check of a version byte,
+//! the 32-byte secret key and, for keys whose public key is compressed, a
+//! 0x01 byte.
+//!
+//! The data is 37 or 38 bytes. `encode_32` converts a 32-byte number, and
+//! base58 can't be split at byte boundaries, so the secret key can't go
+//! through it on its own. The data goes through the scalar code of
+//! [`encode`](crate::encode) and [`decode`](crate::decode) for its length
+//! instead, with their compile-time tables. Its arrays, like the
+//! buffers here that hold the secret, are cleared with `zeroize` when
+//! they're dropped. That doesn't reach copies in registers or spilled by
+//! the compiler, or the SHA-256 state of the checksum, which `sha2` leaves
+//! on the stack.
+
+use core::fmt;
+
+use five8_core::{base58_encoded_max_len, DecodeError};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::{
+    check::{checksum, verify_check, CHECKSUM_LEN},
+    generic::{decode_secret, encode_secret},
+    CheckDecodeError,
+};
+
+const SECRET_LEN: usize = 32;
+const UNCOMPRESSED_LEN: usize = 1 + SECRET_LEN + CHECKSUM_LEN;
+const COMPRESSED_LEN: usize = UNCOMPRESSED_LEN + 1;
+const COMPRESSED_FLAG: u8 = 0x01;
+
+/* Every 37-byte WIF key with version 0x80 or 0xEF is 51 characters long
+and every 38-byte one is 52 */
+const UNCOMPRESSED_ENCODED_LEN: usize = base58_encoded_max_len(UNCOMPRESSED_LEN);
+
+/// The length of a WIF key for a compressed public key, and the buffer
+/// size for [`encode_wif`].
+pub const WIF_ENCODED_MAX_LEN: usize = base58_encoded_max_len(COMPRESSED_LEN);
+
+/// The network a WIF key is for, given by its version byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WifNetwork {
+    /// Version 0x80. Keys start with `5`, or `K` or `L` if compressed.
+    Mainnet,
+    /// Version 0xEF, used by testnet, signet and regtest. Keys start with
+    /// `9`, or `c` if compressed.
+    Testnet,
+}
+
+impl WifNetwork {
+    /// The version byte for the network.
+    pub const fn version(self) -> u8 {
+        match self {
+            WifNetwork::Mainnet => 0x80,
+            WifNetwork::Testnet => 0xef,
+        }
+    }
+
+    /// The network with the given version byte, if any.
+    pub const fn from_version(version: u8) -> Option<Self> {
+        match version {
+            0x80 => Some(WifNetwork::Mainnet),
+            0xef => Some(WifNetwork::Testnet),
+            _ => None,
+        }
+    }
+}
+
+/// A private key with the network and compression flag of its WIF
+/// encoding.
+///
+/// The secret key is cleared when the `WifKey` is dropped, and isn't
+/// printed by `Debug`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct WifKey {
+    secret: [u8; SECRET_LEN],
+    network: WifNetwork,
+    compressed: bool,
+}
+
+impl WifKey {
+    /// `compressed` says whether the key is used with a compressed public
+    /// key, which adds a 0x01 byte to the encoding.
+    pub const fn new(secret: [u8; SECRET_LEN], network: WifNetwork, compressed: bool) -> Self {
+        Self {
+            secret,
+            network,
+            compressed,
+        }
+    }
+
+    pub const fn secret(&self) -> &[u8; SECRET_LEN] {
+        &self.secret
+    }
+
+    pub const fn network(&self) -> WifNetwork {
+        self.network
+    }
+
+    pub const fn compressed(&self) -> bool {
+        self.compressed
+    }
+}
+
+impl fmt::Debug for WifKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WifKey")
+            .field("network", &self.network)
+            .field("compressed", &self.compressed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for WifKey {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for WifKey {}
+
+/// Encode a private key in WIF and return the length of the encoding:
+/// 51 characters, or 52 for a compressed key.
+///
+/// # Examples
+///
+/// ```
+/// use five8::{WifKey, WifNetwork};
+///
+/// let secret = [
+///     12, 40, 252, 163, 134, 199, 162, 39, 96, 11, 47, 229, 11, 124, 174, 17, 236, 134, 211,
+///     191, 31, 190, 71, 27, 232, 152, 39, 225, 157, 114, 170, 29,
+/// ];
+/// let mut out = [0u8; five8::WIF_ENCODED_MAX_LEN];
+/// let len = five8::encode_wif(&WifKey::new(secret, WifNetwork::Mainnet, false), &mut out);
+/// assert_eq!(
+///     &out[..len as usize],
+///     b"5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ"
+/// );
+/// let len = five8::encode_wif(&WifKey::new(secret, WifNetwork::Mainnet, true), &mut out);
+/// assert_eq!(
+///     &out[..len as usize],
+///     b"KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617"
+/// );
+/// ```
+pub fn encode_wif(key: &WifKey, out: &mut [u8; WIF_ENCODED_MAX_LEN]) -> u8 {
+    if key.compressed {
+        encode_data::<COMPRESSED_LEN>(key, out)
+    } else {
+        encode_data::<UNCOMPRESSED_LEN>(key, out)
+    }
+}
+
+#[inline(always)]
+fn encode_data<const N: usize>(key: &WifKey, out: &mut [u8; WIF_ENCODED_MAX_LEN]) -> u8 {
+    let mut data = Zeroizing::new([0u8; N]);
+    data[0] = key.network.version();
+    data[1..1 + SECRET_LEN].copy_from_slice(&key.secret);
+    if N == COMPRESSED_LEN {
+        data[1 + SECRET_LEN] = COMPRESSED_FLAG;
+    }
+    let checksum = checksum(&[], &data[..N - CHECKSUM_LEN]);
+    data[N - CHECKSUM_LEN..].copy_from_slice(&checksum);
+    encode_secret(&data, out)
+}
+
+/// Decode a WIF private key.
+///
+/// Besides the errors of [`decode_check`](crate::decode_check), a version byte other than 0x80
+/// and 0xEF gives [`CheckDecodeError::InvalidVersion`] and a byte other
+/// than 0x01 after the key gives
+/// [`CheckDecodeError::InvalidCompressionFlag`].
+///
+/// # Examples
+///
+/// ```
+/// use five8::{CheckDecodeError, WifNetwork};
+///
+/// let key = five8::decode_wif("cMzLdeGd5vEqxB8B6VFQoRopQ3sLAAvEzDAoQgvX54xwofSWj1fx").unwrap();
+/// assert_eq!(key.network(), WifNetwork::Testnet);
+/// assert!(key.compressed());
+/// assert_eq!(key.secret()[..4], [12, 40, 252, 163]);
+/// assert_eq!(
+///     five8::decode_wif("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvWxyf5d"),
+///     Err(CheckDecodeError::InvalidCompressionFlag(0x02))
+/// );
+/// ```
+pub fn decode_wif(encoded: impl AsRef<[u8]>) -> Result<WifKey, CheckDecodeError> {
+    let encoded = encoded.as_ref();
+    match encoded.len() {
+        UNCOMPRESSED_ENCODED_LEN => decode_data::<UNCOMPRESSED_LEN>(encoded),
+        WIF_ENCODED_MAX_LEN => decode_data::<COMPRESSED_LEN>(encoded),
+        len if len < UNCOMPRESSED_ENCODED_LEN => Err(DecodeError::TooShort.into()),
+        _ => Err(DecodeError::TooLong.into()),
+    }
+}
+
+#[inline(always)]
+fn decode_data<const N: usize>(encoded: &[u8]) -> Result<WifKey, CheckDecodeError> {
+    let mut data = Zeroizing::new([0u8; N]);
+    decode_secret(encoded, &mut data)?;
+    let payload = verify_check(&*data, &[])?;
+    let network = WifNetwork::from_version(payload[0]).ok_or(CheckDecodeError::InvalidVersion)?;
+    let compressed = N == COMPRESSED_LEN;
+    if compressed && payload[1 + SECRET_LEN] != COMPRESSED_FLAG {
+        return Err(CheckDecodeError::InvalidCompressionFlag(
+            payload[1 + SECRET_LEN],
+        ));
+    }
+    let mut key = WifKey::new([0; SECRET_LEN], network, compressed);
+    key.secret.copy_from_slice(&payload[1..1 + SECRET_LEN]);
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    #[cfg(not(miri))]
+    use proptest::prelude::*;
+    extern crate std;
+    use std::{format, string::ToString};
+
+    fn check_against_bs58(key: &WifKey) {
+        let mut data = std::vec![key.network.version()];
+        data.extend_from_slice(&key.secret);
+        if key.compressed {
+            data.push(COMPRESSED_FLAG);
+        }
+        let expected = bs58::encode(&data).with_check().into_vec();
+        test_util::check_against_bs58(
+            &expected,
+            |out| encode_wif(key, out),
+            |encoded| decode_wif(encoded),
+            key.clone(),
+        );
+    }
+
+    #[test]
+    fn test_wif_matches_bs58() {
+        for network in [WifNetwork::Mainnet, WifNetwork::Testnet] {
+            for compressed in [false, true] {
+                for secret in [
+                    [0; 32],
+                    [1; 32],
+                    [0xff; 32],
+                    core::array::from_fn(|i| i as u8),
+                ] {
+                    check_against_bs58(&WifKey::new(secret, network, compressed));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_wif_errors() {
+        let secret = [7u8; 32];
+        /* The same data with an unknown version */
+        let mut data = [0u8; COMPRESSED_LEN];
+        data[0] = 0x81;
+        data[1..33].copy_from_slice(&secret);
+        data[33] = COMPRESSED_FLAG;
+        let mut out = [0u8; WIF_ENCODED_MAX_LEN];
+        let len = crate::encode_check::<COMPRESSED_LEN, WIF_ENCODED_MAX_LEN>(
+            &[],
+            &data[..COMPRESSED_LEN - CHECKSUM_LEN],
+            &mut out,
+        );
+        assert_eq!(
+            decode_wif(&out[..len as usize]),
+            Err(CheckDecodeError::InvalidVersion)
+        );
+
+        let key = WifKey::new(secret, WifNetwork::Mainnet, true);
+        let len = encode_wif(&key, &mut out) as usize;
+        /* Too big for an uncompressed key */
+        assert_eq!(
+            decode_wif(&out[..len - 1]),
+            Err(CheckDecodeError::Decode(DecodeError::LargestTermTooHigh))
+        );
+        assert_eq!(
+            decode_wif(&out[..len - 2]),
+            Err(CheckDecodeError::Decode(DecodeError::TooShort))
+        );
+        let mut long = std::vec::Vec::from(&out[..len]);
+        long.push(b'1');
+        assert_eq!(
+            decode_wif(&long),
+            Err(CheckDecodeError::Decode(DecodeError::TooLong))
+        );
+        test_util::corrupt(&mut out, 10);
+        assert!(matches!(
+            decode_wif(&out[..len]),
+            Err(CheckDecodeError::InvalidChecksum { .. })
+        ));
+        out[10] = b'0';
+        assert_eq!(
+            decode_wif(&out[..len]),
+            Err(CheckDecodeError::Decode(DecodeError::InvalidChar(b'0')))
+        );
+        /* The flag of an otherwise valid compressed key */
+        let mut data = [0u8; COMPRESSED_LEN];
+        data[0] = 0x80;
+        data[33] = 0x02;
+        let len = crate::encode_check::<COMPRESSED_LEN, WIF_ENCODED_MAX_LEN>(
+            &[],
+            &data[..COMPRESSED_LEN - CHECKSUM_LEN],
+            &mut out,
+        );
+        let err = decode_wif(&out[..len as usize]).unwrap_err();
+        assert_eq!(err, CheckDecodeError::InvalidCompressionFlag(0x02));
+        assert_eq!(err.to_string(), "Invalid compression flag: 0x02");
+    }
+
+    #[test]
+    fn test_wif_debug_hides_secret() {
+        let key = WifKey::new([0xab; 32], WifNetwork::Testnet, false);
+        assert_eq!(
+            format!("{key:?}"),
+            "WifKey { network: Testnet, compressed: false, .. }"
+        );
+    }
+
+    #[cfg(not(miri))]
+    proptest! {
+        #[test]
+        fn proptest_wif_roundtrip(secret in prop::array::uniform32(0u8..), testnet in any::<bool>(), compressed in any::<bool>()) {
+            let network = if testnet { WifNetwork::Testnet } else { WifNetwork::Mainnet };
+            check_against_bs58(&WifKey::new(secret, network, compressed));
+        }
+    }
+}
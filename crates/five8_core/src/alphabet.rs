@@ -0,0 +1,205 @@
+use core::fmt;
+
+use crate::BASE58_INVALID_CHAR;
+
+/// A base58 alphabet: the 58 characters for the digits 0 to 57, in order.
+///
+/// The encode and decode functions in `five8` use [`Alphabet::BITCOIN`]
+/// unless they take an `Alphabet`.
+///
+/// # Examples
+///
+/// ```
+/// use five8_core::{Alphabet, AlphabetError};
+///
+/// const REVERSED: Alphabet =
+///     Alphabet::new_unwrap(b"zyxwvutsrqponmkjihgfedcbaZYXWVUTSRQPNMLKJHGFEDCBA987654321");
+/// assert_eq!(REVERSED.as_bytes()[0], b'z');
+///
+/// let err = Alphabet::new(b"1123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxy");
+/// assert_eq!(
+///     err,
+///     Err(AlphabetError::DuplicateCharacter { character: '1', first: 0, second: 1 })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    encode: [u8; 58],
+    decode: [u8; 128],
+}
+
+/// The reason [`Alphabet::new`] rejected an alphabet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphabetError {
+    /// The character at `second` is the same as the one at `first`.
+    DuplicateCharacter {
+        character: char,
+        first: usize,
+        second: usize,
+    },
+    /// The character at `index` is not ASCII.
+    NonAsciiCharacter { index: usize },
+}
+
+impl core::error::Error for AlphabetError {}
+
+impl fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlphabetError::DuplicateCharacter {
+                character,
+                first,
+                second,
+            } => write!(
+                f,
+                "Alphabet has character {character:?} at both index {first} and index {second}"
+            ),
+            AlphabetError::NonAsciiCharacter { index } => {
+                write!(f, "Alphabet has a non-ASCII character at index {index}")
+            }
+        }
+    }
+}
+
+impl Alphabet {
+    /// The Bitcoin alphabet, used by Bitcoin, Solana and most other
+    /// projects.
+    pub const BITCOIN: Alphabet =
+        Alphabet::new_unwrap(b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz");
+    /// The alphabet of the XRP Ledger.
+    pub const RIPPLE: Alphabet =
+        Alphabet::new_unwrap(b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz");
+    /// The alphabet of Flickr short URLs.
+    pub const FLICKR: Alphabet =
+        Alphabet::new_unwrap(b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ");
+
+    /// Creates an alphabet from its 58 characters, which must be distinct
+    /// ASCII characters.
+    pub const fn new(chars: &[u8; 58]) -> Result<Self, AlphabetError> {
+        let mut decode = [BASE58_INVALID_CHAR; 128];
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c >= 128 {
+                return Err(AlphabetError::NonAsciiCharacter { index: i });
+            }
+            if decode[c as usize] != BASE58_INVALID_CHAR {
+                return Err(AlphabetError::DuplicateCharacter {
+                    character: c as char,
+                    first: decode[c as usize] as usize,
+                    second: i,
+                });
+            }
+            decode[c as usize] = i as u8;
+            i += 1;
+        }
+        Ok(Self {
+            encode: *chars,
+            decode,
+        })
+    }
+
+    /// Same as [`Alphabet::new`], but panics instead of returning an error,
+    /// so that an invalid alphabet in a `const` is a compile error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the characters are not distinct ASCII characters.
+    pub const fn new_unwrap(chars: &[u8; 58]) -> Self {
+        match Self::new(chars) {
+            Ok(alphabet) => alphabet,
+            Err(AlphabetError::DuplicateCharacter { .. }) => {
+                panic!("alphabet has a duplicate character")
+            }
+            Err(AlphabetError::NonAsciiCharacter { .. }) => {
+                panic!("alphabet has a non-ASCII character")
+            }
+        }
+    }
+
+    /// The characters for the digits 0 to 57.
+    pub const fn as_bytes(&self) -> &[u8; 58] {
+        &self.encode
+    }
+
+    /// The digit for each ASCII character, or [`BASE58_INVALID_CHAR`] if it
+    /// isn't in the alphabet.
+    pub const fn decode_table(&self) -> &[u8; 128] {
+        &self.decode
+    }
+
+    /// The digit for `c`, or `None` if it isn't in the alphabet.
+    #[inline(always)]
+    pub const fn digit(&self, c: u8) -> Option<u8> {
+        if c >= 128 || self.decode[c as usize] == BASE58_INVALID_CHAR {
+            None
+        } else {
+            Some(self.decode[c as usize])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BASE58_INVERSE, BASE58_INVERSE_TABLE_OFFSET};
+    extern crate std;
+    use std::string::ToString;
+
+    #[test]
+    fn test_bitcoin_matches_inverse_table() {
+        for c in 0..128u8 {
+            let expected = match c.checked_sub(BASE58_INVERSE_TABLE_OFFSET) {
+                Some(i) if (i as usize) < BASE58_INVERSE.len() - 1 => BASE58_INVERSE[i as usize],
+                _ => BASE58_INVALID_CHAR,
+            };
+            assert_eq!(Alphabet::BITCOIN.decode_table()[c as usize], expected);
+        }
+    }
+
+    #[test]
+    fn test_alphabets_are_permutations() {
+        for alphabet in [Alphabet::BITCOIN, Alphabet::RIPPLE, Alphabet::FLICKR] {
+            let mut chars = *alphabet.as_bytes();
+            chars.sort_unstable();
+            let mut expected = *Alphabet::BITCOIN.as_bytes();
+            expected.sort_unstable();
+            assert_eq!(chars, expected);
+            for (i, c) in alphabet.as_bytes().iter().enumerate() {
+                assert_eq!(alphabet.digit(*c), Some(i as u8));
+            }
+            assert_eq!(alphabet.digit(b'0'), None);
+            assert_eq!(alphabet.digit(200), None);
+        }
+    }
+
+    #[test]
+    fn test_invalid_alphabets() {
+        let mut chars = *Alphabet::BITCOIN.as_bytes();
+        chars[57] = 0xc3;
+        assert_eq!(
+            Alphabet::new(&chars),
+            Err(AlphabetError::NonAsciiCharacter { index: 57 })
+        );
+        chars[57] = b'A';
+        let err = Alphabet::new(&chars).unwrap_err();
+        assert_eq!(
+            err,
+            AlphabetError::DuplicateCharacter {
+                character: 'A',
+                first: 9,
+                second: 57
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Alphabet has character 'A' at both index 9 and index 57"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "alphabet has a duplicate character")]
+    fn test_new_unwrap_panics() {
+        Alphabet::new_unwrap(&[b'x'; 58]);
+    }
+}
@@ -3,20 +3,29 @@
 #![no_std]
 
 use core::fmt;
+
+mod alphabet;
+mod tables;
+pub use alphabet::{Alphabet, AlphabetError};
+pub use tables::{
+    base58_encoded_max_len, binary_sz, dec_table, enc_table, intermediate_sz, raw58_sz,
+    MAX_TABLE_LEN, R1DIV,
+};
+
 pub const BASE58_INVERSE_TABLE_OFFSET: u8 = b'1';
 pub const BASE58_INVERSE_TABLE_SENTINEL: u8 = 1 + b'z' - BASE58_INVERSE_TABLE_OFFSET;
 
 pub const BASE58_INVALID_CHAR: u8 = 255;
-pub const BASE58_ENCODED_32_MAX_LEN: usize = 44; /* Computed as ceil(log_58(256^32 - 1)) */
-pub const BASE58_ENCODED_64_MAX_LEN: usize = 88; /* Computed as ceil(log_58(256^64 - 1)) */
-pub const INTERMEDIATE_SZ_32: usize = 9; /* Computed by ceil(log_(58^5) (256^32-1)) */
-pub const INTERMEDIATE_SZ_64: usize = 18; /* Computed by ceil(log_(58^5) (256^64-1)) */
-pub const RAW58_SZ_32: usize = INTERMEDIATE_SZ_32 * 5;
-pub const RAW58_SZ_64: usize = INTERMEDIATE_SZ_64 * 5;
 pub const N_32: usize = 32;
 pub const N_64: usize = 64;
-pub const BINARY_SZ_32: usize = N_32 / 4;
-pub const BINARY_SZ_64: usize = N_64 / 4;
+pub const BASE58_ENCODED_32_MAX_LEN: usize = base58_encoded_max_len(N_32);
+pub const BASE58_ENCODED_64_MAX_LEN: usize = base58_encoded_max_len(N_64);
+pub const INTERMEDIATE_SZ_32: usize = intermediate_sz(N_32);
+pub const INTERMEDIATE_SZ_64: usize = intermediate_sz(N_64);
+pub const RAW58_SZ_32: usize = raw58_sz(N_32);
+pub const RAW58_SZ_64: usize = raw58_sz(N_64);
+pub const BINARY_SZ_32: usize = binary_sz(N_32);
+pub const BINARY_SZ_64: usize = binary_sz(N_64);
 
 const BAD: u8 = BASE58_INVALID_CHAR;
 pub const BASE58_INVERSE: [u8; 75] = [
@@ -28,92 +37,24 @@ pub const BASE58_INVERSE: [u8; 75] = [
 
 /* Contains the unique values less than 2^32 such that:
 58^(5*(8-j)) = sum_k table[j][k]*2^(32*(7-k)) */
-pub const DEC_TABLE_32: [[u32; BINARY_SZ_32]; INTERMEDIATE_SZ_32] = [
-    [
-        1277, 2650397687, 3801011509, 2074386530, 3248244966, 687255411, 2959155456, 0,
-    ],
-    [
-        0, 8360, 1184754854, 3047609191, 3418394749, 132556120, 1199103528, 0,
-    ],
-    [
-        0, 0, 54706, 2996985344, 1834629191, 3964963911, 485140318, 1073741824,
-    ],
-    [
-        0, 0, 0, 357981, 1476998812, 3337178590, 1483338760, 4194304000,
-    ],
-    [0, 0, 0, 0, 2342503, 3052466824, 2595180627, 17825792],
-    [0, 0, 0, 0, 0, 15328518, 1933902296, 4063920128],
-    [0, 0, 0, 0, 0, 0, 100304420, 3355157504],
-    [0, 0, 0, 0, 0, 0, 0, 656356768],
-    [0, 0, 0, 0, 0, 0, 0, 1],
-];
+pub const DEC_TABLE_32: [[u32; BINARY_SZ_32]; INTERMEDIATE_SZ_32] =
+    dec_table(INTERMEDIATE_SZ_32, BINARY_SZ_32);
 
-pub const DEC_TABLE_64: [[u32; BINARY_SZ_64]; INTERMEDIATE_SZ_64] = [
-    [
-        249448, 3719864065, 173911550, 4021557284, 3115810883, 2498525019, 1035889824, 627529458,
-        3840888383, 3728167192, 2901437456, 3863405776, 1540739182, 1570766848, 0, 0,
-    ],
-    [
-        0, 1632305, 1882780341, 4128706713, 1023671068, 2618421812, 2005415586, 1062993857,
-        3577221846, 3960476767, 1695615427, 2597060712, 669472826, 104923136, 0, 0,
-    ],
-    [
-        0, 0, 10681231, 1422956801, 2406345166, 4058671871, 2143913881, 4169135587, 2414104418,
-        2549553452, 997594232, 713340517, 2290070198, 1103833088, 0, 0,
-    ],
-    [
-        0, 0, 0, 69894212, 1038812943, 1785020643, 1285619000, 2301468615, 3492037905, 314610629,
-        2761740102, 3410618104, 1699516363, 910779968, 0, 0,
-    ],
-    [
-        0, 0, 0, 0, 457363084, 927569770, 3976106370, 1389513021, 2107865525, 3716679421,
-        1828091393, 2088408376, 439156799, 2579227194, 0, 0,
-    ],
-    [
-        0, 0, 0, 0, 0, 2992822783, 383623235, 3862831115, 112778334, 339767049, 1447250220,
-        486575164, 3495303162, 2209946163, 268435456, 0,
-    ],
-    [
-        0, 0, 0, 0, 0, 4, 2404108010, 2962826229, 3998086794, 1893006839, 2266258239, 1429430446,
-        307953032, 2361423716, 176160768, 0,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 29, 3596590989, 3044036677, 1332209423, 1014420882, 868688145,
-        4264082837, 3688771808, 2485387264, 0,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 195, 1054003707, 3711696540, 582574436, 3549229270, 1088536814,
-        2338440092, 1468637184, 0,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 1277, 2650397687, 3801011509, 2074386530, 3248244966, 687255411,
-        2959155456, 0,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 8360, 1184754854, 3047609191, 3418394749, 132556120, 1199103528,
-        0,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 54706, 2996985344, 1834629191, 3964963911, 485140318,
-        1073741824,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 357981, 1476998812, 3337178590, 1483338760, 4194304000,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2342503, 3052466824, 2595180627, 17825792,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 15328518, 1933902296, 4063920128,
-    ],
-    [
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 100304420, 3355157504,
-    ],
-    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 656356768],
-    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-];
+pub const DEC_TABLE_64: [[u32; BINARY_SZ_64]; INTERMEDIATE_SZ_64] =
+    dec_table(INTERMEDIATE_SZ_64, BINARY_SZ_64);
+
+/* Contains the unique values less than 58^5 such that:
+  2^(32*(7-j)) = sum_k table[j][k]*58^(5*(7-k))
+
+The second dimension of this table is actually ceil(log_(58^5)
+(2^(32*(BINARY_SZ-1))), but that's almost always INTERMEDIATE_SZ-1 */
+pub const ENC_TABLE_32: [[u32; INTERMEDIATE_SZ_32 - 1]; BINARY_SZ_32] =
+    enc_table(BINARY_SZ_32, INTERMEDIATE_SZ_32 - 1);
+
+pub const ENC_TABLE_64: [[u32; INTERMEDIATE_SZ_64 - 1]; BINARY_SZ_64] =
+    enc_table(BINARY_SZ_64, INTERMEDIATE_SZ_64 - 1);
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DecodeError {
     InvalidChar(u8),
     TooLong,
@@ -137,3 +78,232 @@ impl fmt::Display for DecodeError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+
+    /* The tables as they were typed in before they were computed */
+    const DEC_TABLE_32_LITERAL: [[u32; BINARY_SZ_32]; INTERMEDIATE_SZ_32] = [
+        [
+            1277, 2650397687, 3801011509, 2074386530, 3248244966, 687255411, 2959155456, 0,
+        ],
+        [
+            0, 8360, 1184754854, 3047609191, 3418394749, 132556120, 1199103528, 0,
+        ],
+        [
+            0, 0, 54706, 2996985344, 1834629191, 3964963911, 485140318, 1073741824,
+        ],
+        [
+            0, 0, 0, 357981, 1476998812, 3337178590, 1483338760, 4194304000,
+        ],
+        [0, 0, 0, 0, 2342503, 3052466824, 2595180627, 17825792],
+        [0, 0, 0, 0, 0, 15328518, 1933902296, 4063920128],
+        [0, 0, 0, 0, 0, 0, 100304420, 3355157504],
+        [0, 0, 0, 0, 0, 0, 0, 656356768],
+        [0, 0, 0, 0, 0, 0, 0, 1],
+    ];
+
+    const DEC_TABLE_64_LITERAL: [[u32; BINARY_SZ_64]; INTERMEDIATE_SZ_64] = [
+        [
+            249448, 3719864065, 173911550, 4021557284, 3115810883, 2498525019, 1035889824,
+            627529458, 3840888383, 3728167192, 2901437456, 3863405776, 1540739182, 1570766848, 0,
+            0,
+        ],
+        [
+            0, 1632305, 1882780341, 4128706713, 1023671068, 2618421812, 2005415586, 1062993857,
+            3577221846, 3960476767, 1695615427, 2597060712, 669472826, 104923136, 0, 0,
+        ],
+        [
+            0, 0, 10681231, 1422956801, 2406345166, 4058671871, 2143913881, 4169135587, 2414104418,
+            2549553452, 997594232, 713340517, 2290070198, 1103833088, 0, 0,
+        ],
+        [
+            0, 0, 0, 69894212, 1038812943, 1785020643, 1285619000, 2301468615, 3492037905,
+            314610629, 2761740102, 3410618104, 1699516363, 910779968, 0, 0,
+        ],
+        [
+            0, 0, 0, 0, 457363084, 927569770, 3976106370, 1389513021, 2107865525, 3716679421,
+            1828091393, 2088408376, 439156799, 2579227194, 0, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 2992822783, 383623235, 3862831115, 112778334, 339767049, 1447250220,
+            486575164, 3495303162, 2209946163, 268435456, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 4, 2404108010, 2962826229, 3998086794, 1893006839, 2266258239,
+            1429430446, 307953032, 2361423716, 176160768, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 29, 3596590989, 3044036677, 1332209423, 1014420882, 868688145,
+            4264082837, 3688771808, 2485387264, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 195, 1054003707, 3711696540, 582574436, 3549229270, 1088536814,
+            2338440092, 1468637184, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 1277, 2650397687, 3801011509, 2074386530, 3248244966,
+            687255411, 2959155456, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 8360, 1184754854, 3047609191, 3418394749, 132556120,
+            1199103528, 0,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 54706, 2996985344, 1834629191, 3964963911, 485140318,
+            1073741824,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 357981, 1476998812, 3337178590, 1483338760, 4194304000,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2342503, 3052466824, 2595180627, 17825792,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 15328518, 1933902296, 4063920128,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 100304420, 3355157504,
+        ],
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 656356768],
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+    ];
+
+    const ENC_TABLE_32_LITERAL: [[u32; INTERMEDIATE_SZ_32 - 1]; BINARY_SZ_32] = [
+        [
+            513735, 77223048, 437087610, 300156666, 605448490, 214625350, 141436834, 379377856,
+        ],
+        [
+            0, 78508, 646269101, 118408823, 91512303, 209184527, 413102373, 153715680,
+        ],
+        [
+            0, 0, 11997, 486083817, 3737691, 294005210, 247894721, 289024608,
+        ],
+        [0, 0, 0, 1833, 324463681, 385795061, 551597588, 21339008],
+        [0, 0, 0, 0, 280, 127692781, 389432875, 357132832],
+        [0, 0, 0, 0, 0, 42, 537767569, 410450016],
+        [0, 0, 0, 0, 0, 0, 6, 356826688],
+        [0, 0, 0, 0, 0, 0, 0, 1],
+    ];
+
+    const ENC_TABLE_64_LITERAL: [[u32; INTERMEDIATE_SZ_64 - 1]; BINARY_SZ_64] = [
+        [
+            2631, 149457141, 577092685, 632289089, 81912456, 221591423, 502967496, 403284731,
+            377738089, 492128779, 746799, 366351977, 190199623, 38066284, 526403762, 650603058,
+            454901440,
+        ],
+        [
+            0, 402, 68350375, 30641941, 266024478, 208884256, 571208415, 337765723, 215140626,
+            129419325, 480359048, 398051646, 635841659, 214020719, 136986618, 626219915, 49699360,
+        ],
+        [
+            0, 0, 61, 295059608, 141201404, 517024870, 239296485, 527697587, 212906911, 453637228,
+            467589845, 144614682, 45134568, 184514320, 644355351, 104784612, 308625792,
+        ],
+        [
+            0, 0, 0, 9, 256449755, 500124311, 479690581, 372802935, 413254725, 487877412,
+            520263169, 176791855, 78190744, 291820402, 74998585, 496097732, 59100544,
+        ],
+        [
+            0, 0, 0, 0, 1, 285573662, 455976778, 379818553, 100001224, 448949512, 109507367,
+            117185012, 347328982, 522665809, 36908802, 577276849, 64504928,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 143945778, 651677945, 281429047, 535878743, 264290972, 526964023,
+            199595821, 597442702, 499113091, 424550935, 458949280,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 21997789, 294590275, 148640294, 595017589, 210481832, 404203788,
+            574729546, 160126051, 430102516, 44963712,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 3361701, 325788598, 30977630, 513969330, 194569730, 164019635,
+            136596846, 626087230, 503769920,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 513735, 77223048, 437087610, 300156666, 605448490,
+            214625350, 141436834, 379377856,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 78508, 646269101, 118408823, 91512303, 209184527,
+            413102373, 153715680,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 11997, 486083817, 3737691, 294005210, 247894721,
+            289024608,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1833, 324463681, 385795061, 551597588, 21339008,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 280, 127692781, 389432875, 357132832,
+        ],
+        [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 42, 537767569, 410450016,
+        ],
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6, 356826688],
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+    ];
+
+    #[test]
+    fn test_tables_match_literals() {
+        assert_eq!(DEC_TABLE_32, DEC_TABLE_32_LITERAL);
+        assert_eq!(DEC_TABLE_64, DEC_TABLE_64_LITERAL);
+        assert_eq!(ENC_TABLE_32, ENC_TABLE_32_LITERAL);
+        assert_eq!(ENC_TABLE_64, ENC_TABLE_64_LITERAL);
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(BASE58_ENCODED_32_MAX_LEN, 44);
+        assert_eq!(BASE58_ENCODED_64_MAX_LEN, 88);
+        assert_eq!(INTERMEDIATE_SZ_32, 9);
+        assert_eq!(INTERMEDIATE_SZ_64, 18);
+        assert_eq!(RAW58_SZ_32, 45);
+        assert_eq!(RAW58_SZ_64, 90);
+        assert_eq!(BINARY_SZ_32, 8);
+        assert_eq!(BINARY_SZ_64, 16);
+        assert_eq!(base58_encoded_max_len(0), 0);
+        assert_eq!(base58_encoded_max_len(1), 2);
+        assert_eq!(base58_encoded_max_len(20), 28);
+        assert_eq!(base58_encoded_max_len(25), 35);
+        assert_eq!(base58_encoded_max_len(MAX_TABLE_LEN), 1399);
+        assert_eq!(intermediate_sz(1), 1);
+        assert_eq!(intermediate_sz(128), 35);
+        /* ceil(n * log_58(256)) for irrational log_58(256) */
+        for n in 1..=MAX_TABLE_LEN {
+            let len = base58_encoded_max_len(n);
+            assert!(len as f64 > n as f64 * 256f64.log(58.0), "{n}");
+            assert!(((len - 1) as f64) < n as f64 * 256f64.log(58.0), "{n}");
+            /* 58^(5k) >= 256^n iff 5k >= len */
+            assert_eq!(intermediate_sz(n), len.div_ceil(5), "{n}");
+        }
+    }
+
+    #[test]
+    fn test_padded_tables() {
+        /* A bigger table holds the exact one in its top left corner */
+        let dec: [[u32; 20]; 20] = dec_table(INTERMEDIATE_SZ_32, BINARY_SZ_32);
+        let enc: [[u32; 20]; 20] = enc_table(BINARY_SZ_32, INTERMEDIATE_SZ_32 - 1);
+        for (i, row) in dec.iter().enumerate() {
+            for (j, x) in row.iter().enumerate() {
+                let expected = DEC_TABLE_32.get(i).and_then(|row| row.get(j));
+                assert_eq!(*x, expected.copied().unwrap_or(0));
+            }
+        }
+        for (i, row) in enc.iter().enumerate() {
+            for (j, x) in row.iter().enumerate() {
+                let expected = ENC_TABLE_32.get(i).and_then(|row| row.get(j));
+                assert_eq!(*x, expected.copied().unwrap_or(0));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not enough columns")]
+    fn test_enc_table_too_narrow() {
+        let _: [[u32; 7]; 8] = enc_table(BINARY_SZ_32, 7);
+    }
+}
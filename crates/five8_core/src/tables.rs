@@ -0,0 +1,173 @@
+/* Const fns that compute the sizes and lookup tables for encoding and
+decoding n-byte arrays. The numbers involved are up to 256^n * 58^5, so
+they use little-endian u32 limbs ("big" numbers) with the helpers below. */
+
+/// The largest byte length [`base58_encoded_max_len`] and
+/// [`intermediate_sz`] accept.
+pub const MAX_TABLE_LEN: usize = 1024;
+
+/* Enough for 256^MAX_TABLE_LEN * 58^5 */
+const SIZE_LIMBS: usize = MAX_TABLE_LEN / 4 + 2;
+
+/// 58^5, the radix of the intermediate representation.
+pub const R1DIV: u32 = 656356768;
+
+const fn big_mul_small<const L: usize>(mut a: [u32; L], len: usize, m: u32) -> [u32; L] {
+    let mut carry = 0u64;
+    let mut i = 0;
+    while i < len {
+        let t = a[i] as u64 * m as u64 + carry;
+        a[i] = t as u32;
+        carry = t >> 32;
+        i += 1;
+    }
+    assert!(carry == 0, "big number overflow");
+    a
+}
+
+const fn big_div_small<const L: usize>(mut a: [u32; L], len: usize, d: u32) -> ([u32; L], u32) {
+    let mut rem = 0u64;
+    let mut i = len;
+    while i > 0 {
+        i -= 1;
+        let t = (rem << 32) | a[i] as u64;
+        a[i] = (t / d as u64) as u32;
+        rem = t % d as u64;
+    }
+    (a, rem as u32)
+}
+
+/* The smallest k such that base^k >= 256^n, i.e. the number of base
+`base` digits needed for any n-byte number */
+const fn digits_needed(n: usize, base: u32) -> usize {
+    assert!(n <= MAX_TABLE_LEN, "byte length too large");
+    let mut p = [0u32; SIZE_LIMBS];
+    p[0] = 1;
+    /* p < 2^(8n) fits in the limbs up to and including the one with bit
+    8n, and multiplying by base < 2^32 can only carry into the next one */
+    let len = 8 * n / 32 + 1;
+    let mut k = 0;
+    /* p >= 2^(8n) iff it has any bits at or above bit 8n */
+    while p[len - 1] >> (8 * n % 32) == 0 && p[len] == 0 {
+        p = big_mul_small(p, len + 1, base);
+        k += 1;
+    }
+    k
+}
+
+/// The number of u32 limbs in the binary form of an `n`-byte array.
+pub const fn binary_sz(n: usize) -> usize {
+    n.div_ceil(4)
+}
+
+/// The longest base58 encoding of an `n`-byte array, i.e.
+/// `ceil(log_58(256^n - 1))`. [`BASE58_ENCODED_32_MAX_LEN`](crate::BASE58_ENCODED_32_MAX_LEN)
+/// and [`BASE58_ENCODED_64_MAX_LEN`](crate::BASE58_ENCODED_64_MAX_LEN) are
+/// computed with this.
+///
+/// # Panics
+///
+/// Panics if `n` is more than [`MAX_TABLE_LEN`].
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(five8_core::base58_encoded_max_len(20), 28);
+/// let buf = [0u8; five8_core::base58_encoded_max_len(33)];
+/// assert_eq!(buf.len(), 46);
+/// ```
+pub const fn base58_encoded_max_len(n: usize) -> usize {
+    digits_needed(n, 58)
+}
+
+/// The number of radix 58^5 terms in the intermediate form of an
+/// `n`-byte array, i.e. `ceil(log_(58^5)(256^n - 1))`.
+///
+/// # Panics
+///
+/// Panics if `n` is more than [`MAX_TABLE_LEN`].
+pub const fn intermediate_sz(n: usize) -> usize {
+    digits_needed(n, R1DIV)
+}
+
+/// The number of base58 digits the intermediate form of an `n`-byte array
+/// converts to, five per term.
+pub const fn raw58_sz(n: usize) -> usize {
+    5 * intermediate_sz(n)
+}
+
+/// The encode table for numbers of `binary_sz` u32 limbs: row `i` is
+/// 2^(32*(binary_sz-1-i)) in radix 58^5, with the last term in column
+/// `cols - 1`.
+///
+/// Rows and columns past `binary_sz` and `cols` are zero, so this computes
+/// both exactly sized tables like [`ENC_TABLE_32`](crate::ENC_TABLE_32)
+/// and bigger ones that fit several sizes.
+///
+/// # Panics
+///
+/// Panics if the table has less than `binary_sz` rows or `cols` columns,
+/// or if `cols` terms are not enough for the numbers.
+pub const fn enc_table<const ROWS: usize, const COLS: usize>(
+    binary_sz: usize,
+    cols: usize,
+) -> [[u32; COLS]; ROWS] {
+    assert!(binary_sz <= ROWS && cols <= COLS);
+    let mut table = [[0u32; COLS]; ROWS];
+    let mut i = 0;
+    while i < binary_sz {
+        let mut p = [0u32; ROWS];
+        p[binary_sz - 1 - i] = 1;
+        let mut j = cols;
+        while j > 0 {
+            j -= 1;
+            let (q, r) = big_div_small(p, binary_sz, R1DIV);
+            table[i][j] = r;
+            p = q;
+        }
+        let mut rest = 0;
+        while rest < binary_sz {
+            assert!(p[rest] == 0, "not enough columns");
+            rest += 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+/// The decode table for `intermediate_sz` terms and `binary_sz` u32
+/// limbs: row `i` is 58^(5*(intermediate_sz-1-i)) in radix 2^32, with the
+/// last limb in column `binary_sz - 1`.
+///
+/// Rows and columns past `intermediate_sz` and `binary_sz` are zero, so
+/// this computes both exactly sized tables like
+/// [`DEC_TABLE_32`](crate::DEC_TABLE_32) and bigger ones that fit several
+/// sizes.
+///
+/// # Panics
+///
+/// Panics if the table has less than `intermediate_sz` rows or
+/// `binary_sz` columns, or if `binary_sz` limbs are not enough for the
+/// numbers.
+pub const fn dec_table<const ROWS: usize, const COLS: usize>(
+    intermediate_sz: usize,
+    binary_sz: usize,
+) -> [[u32; COLS]; ROWS] {
+    assert!(intermediate_sz <= ROWS && binary_sz <= COLS);
+    let mut table = [[0u32; COLS]; ROWS];
+    let mut p = [0u32; COLS];
+    p[0] = 1;
+    let mut i = intermediate_sz;
+    while i > 0 {
+        i -= 1;
+        let mut j = 0;
+        while j < binary_sz {
+            table[i][j] = p[binary_sz - 1 - j];
+            j += 1;
+        }
+        if i > 0 {
+            p = big_mul_small(p, binary_sz, R1DIV);
+        }
+    }
+    table
+}